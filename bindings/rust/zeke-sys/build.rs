@@ -1,8 +1,51 @@
+//! Locates or builds `libzeke_ffi` and provides the Rust bindings for it
+//!
+//! The library is taken from the first of these that applies:
+//!
+//! 1. `ZEKE_LIB_DIR`: a directory holding a prebuilt `libzeke_ffi`
+//! 2. `pkg-config`: an installed `zeke` package (`full` feature only)
+//! 3. Zig: compiled from the sources in this repository
+//!
+//! `ZEKE_STATIC=1` forces static linking of a prebuilt library and `ZEKE_STATIC=0`
+//! forces dynamic linking; by default a static archive is preferred when present.
+//!
+//! With the `dynamic` feature nothing is linked: the library is opened at runtime
+//! and the bindings are a table of function pointers instead of `extern` blocks.
+//!
+//! Bindings come from bindgen when the `bindgen` feature is enabled, and from the
+//! checked-in files under `src/bindings/` otherwise. Set `ZEKE_UPDATE_BINDINGS=1`
+//! with `bindgen` enabled to refresh the checked-in files.
+
 use std::env;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// Name of the library, without platform prefix or extension
+const LIB_NAME: &str = "zeke_ffi";
+
+/// pkg-config package an installed libzeke registers
+const PKG_CONFIG_NAME: &str = "zeke";
+
+/// A build failure, reported without a backtrace
+struct BuildError(String);
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+type Result<T> = std::result::Result<T, BuildError>;
+
 fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
     let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
     let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
     let zeke_root = manifest_dir
@@ -10,53 +53,169 @@ fn main() {
         .parent().unwrap()
         .parent().unwrap()
         .to_path_buf();
-    
-    println!("cargo:rerun-if-changed={}", zeke_root.join("src/ffi/zeke_ffi_minimal.zig").display());
-    println!("cargo:rerun-if-changed={}", zeke_root.join("src/ffi/zeke_ffi_minimal.h").display());
+
+    // `full` builds the complete FFI with the whole Zig module graph; without it
+    // only the self-contained minimal stub is compiled
+    let full = env::var_os("CARGO_FEATURE_FULL").is_some();
+    let dynamic = env::var_os("CARGO_FEATURE_DYNAMIC").is_some();
+    let header_name = if full { "zeke_ffi" } else { "zeke_ffi_minimal" };
+    let header_path = zeke_root.join(format!("src/ffi/{}.h", header_name));
+
+    println!("cargo:rerun-if-env-changed=ZEKE_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=ZEKE_STATIC");
+    println!("cargo:rerun-if-env-changed=ZEKE_UPDATE_BINDINGS");
     println!("cargo:rerun-if-changed=build.rs");
-    
-    // Check if Zig is available and verify version compatibility
-    let zig_version = Command::new("zig")
-        .arg("version")
-        .output()
-        .expect("Zig compiler not found. Please install Zig 0.11+ from https://ziglang.org/");
-    
-    if !zig_version.status.success() {
-        panic!("Failed to get Zig version. Please ensure Zig is properly installed.");
-    }
-    
-    let zig_version_str = String::from_utf8_lossy(&zig_version.stdout);
-    let version_clean = zig_version_str.trim();
-    println!("cargo:warning=Building with Zig version: {}", version_clean);
-    
-    // Parse version to ensure compatibility (0.11+, including 0.16)
-    if version_clean.starts_with("0.") {
-        let version_parts: Vec<&str> = version_clean.split('.').collect();
-        if let Some(minor_str) = version_parts.get(1) {
-            if let Some(minor_part) = minor_str.split('-').next() {
-                if let Ok(minor) = minor_part.parse::<u32>() {
-                    if minor < 11 {
-                        panic!("Zig version {} is too old. Please install Zig 0.11+ (0.16+ recommended)", version_clean);
-                    }
-                    if minor >= 16 {
-                        println!("cargo:warning=Using Zig 0.16+ - excellent compatibility");
-                    } else if minor >= 11 {
-                        println!("cargo:warning=Using Zig 0.11-0.15 - good compatibility");
-                    }
-                }
-            }
+
+    let linkage = Linkage::from_env()?;
+    if dynamic {
+        // Resolved at runtime by `zeke_sys::dynamic::library`
+    } else if let Some(lib_dir) = env::var_os("ZEKE_LIB_DIR") {
+        link_lib_dir(Path::new(&lib_dir), linkage)?;
+    } else if full && probe_pkg_config(linkage) {
+        println!("cargo:warning=Using libzeke found by pkg-config");
+    } else {
+        build_with_zig(&zeke_root, &out_dir, full)?;
+    }
+
+    let bindings_path = out_dir.join("bindings.rs");
+    let bindings_name = if dynamic { "zeke_ffi_dynamic" } else { header_name };
+    let checked_in = manifest_dir.join(format!("src/bindings/{}.rs", bindings_name));
+    write_bindings(&header_path, &checked_in, &bindings_path, dynamic)
+}
+
+// ============================================================================
+// Prebuilt libraries
+// ============================================================================
+
+/// How a prebuilt library should be linked
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Linkage {
+    Static,
+    Dynamic,
+    /// Static if an archive is present, dynamic otherwise
+    Either,
+}
+
+impl Linkage {
+    fn from_env() -> Result<Self> {
+        match env::var("ZEKE_STATIC").ok().as_deref() {
+            None => Ok(Linkage::Either),
+            Some("1") | Some("true") | Some("yes") => Ok(Linkage::Static),
+            Some("0") | Some("false") | Some("no") => Ok(Linkage::Dynamic),
+            Some(other) => Err(BuildError(format!(
+                "ZEKE_STATIC must be 1 or 0, got {:?}",
+                other
+            ))),
         }
     }
-    
-    // Build the Zig FFI library (using minimal version for testing)
-    let zig_source = zeke_root.join("src/ffi/zeke_ffi_minimal.zig");
-    let lib_output = out_dir.join("libzeke_ffi.a");
-    
+}
+
+/// Link `libzeke_ffi` from a directory named by `ZEKE_LIB_DIR`
+fn link_lib_dir(lib_dir: &Path, linkage: Linkage) -> Result<()> {
+    if !lib_dir.is_dir() {
+        return Err(BuildError(format!(
+            "ZEKE_LIB_DIR={} is not a directory",
+            lib_dir.display()
+        )));
+    }
+
+    let has_static = static_lib_names().iter().any(|name| lib_dir.join(name).is_file());
+    let has_dynamic = dynamic_lib_names().iter().any(|name| lib_dir.join(name).is_file());
+
+    let is_static = match linkage {
+        Linkage::Static if has_static => true,
+        Linkage::Dynamic if has_dynamic => false,
+        Linkage::Either if has_static || has_dynamic => has_static,
+        _ => {
+            let wanted = match linkage {
+                Linkage::Static => static_lib_names(),
+                Linkage::Dynamic => dynamic_lib_names(),
+                Linkage::Either => [static_lib_names(), dynamic_lib_names()].concat(),
+            };
+            return Err(BuildError(format!(
+                "ZEKE_LIB_DIR={} does not contain {}",
+                lib_dir.display(),
+                wanted.join(" or ")
+            )));
+        }
+    };
+
+    println!("cargo:warning=Using prebuilt libzeke from {}", lib_dir.display());
+    println!("cargo:rustc-link-search=native={}", lib_dir.display());
+    if is_static {
+        println!("cargo:rustc-link-lib=static={}", LIB_NAME);
+        link_system_libs();
+    } else {
+        println!("cargo:rustc-link-lib=dylib={}", LIB_NAME);
+    }
+    Ok(())
+}
+
+fn static_lib_names() -> Vec<String> {
+    vec![format!("lib{}.a", LIB_NAME), format!("{}.lib", LIB_NAME)]
+}
+
+fn dynamic_lib_names() -> Vec<String> {
+    vec![
+        format!("lib{}.so", LIB_NAME),
+        format!("lib{}.dylib", LIB_NAME),
+        format!("{}.dll", LIB_NAME),
+    ]
+}
+
+/// Look for an installed libzeke; the pkg-config crate emits the link flags
+fn probe_pkg_config(linkage: Linkage) -> bool {
+    pkg_config::Config::new()
+        .statik(linkage == Linkage::Static)
+        .probe(PKG_CONFIG_NAME)
+        .is_ok()
+}
+
+/// System libraries a static Zig library needs
+fn link_system_libs() {
+    #[cfg(target_os = "linux")]
+    {
+        println!("cargo:rustc-link-lib=pthread");
+        println!("cargo:rustc-link-lib=dl");
+        println!("cargo:rustc-link-lib=m");
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        println!("cargo:rustc-link-lib=framework=System");
+        println!("cargo:rustc-link-lib=pthread");
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        println!("cargo:rustc-link-lib=ws2_32");
+        println!("cargo:rustc-link-lib=kernel32");
+        println!("cargo:rustc-link-lib=ntdll");
+    }
+}
+
+// ============================================================================
+// Building from source
+// ============================================================================
+
+/// Compile the library with Zig and link it statically
+fn build_with_zig(zeke_root: &Path, out_dir: &Path, full: bool) -> Result<()> {
+    if full {
+        // The full library pulls in everything under src/ plus the build graph
+        println!("cargo:rerun-if-changed={}", zeke_root.join("src").display());
+        println!("cargo:rerun-if-changed={}", zeke_root.join("build.zig").display());
+        println!("cargo:rerun-if-changed={}", zeke_root.join("build.zig.zon").display());
+    } else {
+        println!("cargo:rerun-if-changed={}", zeke_root.join("src/ffi/zeke_ffi_minimal.zig").display());
+    }
+
+    check_zig_version(full)?;
+
     // Determine target architecture for Zig
     let target = env::var("TARGET").unwrap();
     let zig_target = match target.as_str() {
         "x86_64-unknown-linux-gnu" => "x86_64-linux-gnu",
-        "x86_64-unknown-linux-musl" => "x86_64-linux-musl", 
+        "x86_64-unknown-linux-musl" => "x86_64-linux-musl",
         "x86_64-pc-windows-gnu" => "x86_64-windows-gnu",
         "x86_64-pc-windows-msvc" => "x86_64-windows-gnu", // Use MinGW for MSVC too
         "x86_64-apple-darwin" => "x86_64-macos-none",
@@ -64,105 +223,183 @@ fn main() {
         "aarch64-unknown-linux-gnu" => "aarch64-linux-gnu",
         _ => "native", // Fallback to native compilation
     };
-    
-    // Check Zig version for command compatibility
-    let _is_zig_016_plus = version_clean.starts_with("0.") && 
-        version_clean.split('.').nth(1).and_then(|s| s.split('-').next().and_then(|p| p.parse::<u32>().ok())).unwrap_or(0) >= 16;
-    
+
     // Optimization level based on profile
-    let opt_level = if cfg!(debug_assertions) {
-        "Debug"
-    } else {
+    let opt_level = if env::var("PROFILE").as_deref() == Ok("release") {
         "ReleaseFast"
+    } else {
+        "Debug"
     };
-    
+
     println!("cargo:warning=Compiling Zig FFI library with target: {} optimization: {}", zig_target, opt_level);
-    
-    let mut zig_build = Command::new("zig");
-    zig_build
-        .args(&[
-            "build-lib",
-            zig_source.to_str().unwrap(),
-            "-target", zig_target,
-            &format!("-O{}", opt_level),
-            "--name", "zeke_ffi",
-            &format!("-femit-bin={}", lib_output.display()),
-            "-fno-emit-h", // We provide our own header
-            "-lc", // Link with C standard library
-        ]);
-    
-    // For now, compile without complex dependencies for testing
-    // In a real implementation, we would set up the proper module path
-    
-    let output = zig_build
-        .output()
-        .expect("Failed to execute Zig compiler");
-    
-    if !output.status.success() {
-        eprintln!("Zig compilation failed:");
-        eprintln!("stdout: {}", String::from_utf8_lossy(&output.stdout));
-        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
-        panic!("Zig compilation failed");
-    }
-    
+
+    let lib_dir = if full {
+        build_full(zeke_root, out_dir, zig_target, opt_level)?
+    } else {
+        build_minimal(zeke_root, out_dir, zig_target, opt_level)?
+    };
+
     // Verify the library was created
+    let lib_output = lib_dir.join(format!("lib{}.a", LIB_NAME));
     if !lib_output.exists() {
-        panic!("Failed to create Zig library at: {}", lib_output.display());
-    }
-    
-    println!("cargo:warning=Successfully built Zig FFI library: {}", lib_output.display());
-    
-    // Link the Zig library
-    println!("cargo:rustc-link-search=native={}", out_dir.display());
-    println!("cargo:rustc-link-lib=static=zeke_ffi");
-    
-    // Link system libraries that Zig needs
-    #[cfg(target_os = "linux")]
-    {
-        println!("cargo:rustc-link-lib=pthread");
-        println!("cargo:rustc-link-lib=dl");
-        println!("cargo:rustc-link-lib=m");
+        return Err(BuildError(format!(
+            "Zig reported success but {} was not created",
+            lib_output.display()
+        )));
     }
-    
-    #[cfg(target_os = "macos")]
-    {
-        println!("cargo:rustc-link-lib=framework=System");
-        println!("cargo:rustc-link-lib=pthread");
+
+    println!("cargo:rustc-link-search=native={}", lib_dir.display());
+    println!("cargo:rustc-link-lib=static={}", LIB_NAME);
+    link_system_libs();
+    Ok(())
+}
+
+/// Check that a usable Zig is on the PATH
+fn check_zig_version(full: bool) -> Result<()> {
+    let output = Command::new("zig").arg("version").output().map_err(|_| {
+        BuildError(
+            "no prebuilt libzeke was found and the Zig compiler is not installed.\n\
+             Either:\n  \
+             - set ZEKE_LIB_DIR to a directory containing libzeke_ffi (see also ZEKE_STATIC),\n  \
+             - install libzeke so that `pkg-config zeke` finds it, or\n  \
+             - install Zig 0.15+ from https://ziglang.org/ to build it from source"
+                .to_string(),
+        )
+    })?;
+
+    if !output.status.success() {
+        return Err(BuildError(
+            "`zig version` failed. Please ensure Zig is properly installed.".to_string(),
+        ));
     }
-    
-    #[cfg(target_os = "windows")]
+
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let minor = version
+        .strip_prefix("0.")
+        .and_then(|rest| rest.split(['.', '-']).next())
+        .and_then(|minor| minor.parse::<u32>().ok());
+
+    let required = if full { 15 } else { 11 };
+    if let Some(minor) = minor
+        && minor < required
     {
-        println!("cargo:rustc-link-lib=ws2_32");
-        println!("cargo:rustc-link-lib=kernel32");
-        println!("cargo:rustc-link-lib=ntdll");
+        return Err(BuildError(format!(
+            "Zig {} is too old{}. Please install Zig 0.{}+",
+            version,
+            if full { " for the `full` feature" } else { "" },
+            required
+        )));
     }
-    
-    // Generate Rust bindings using bindgen
-    println!("cargo:warning=Generating Rust bindings with bindgen");
-    
-    let header_path = zeke_root.join("src/ffi/zeke_ffi_minimal.h");
-    let bindings = bindgen::Builder::default()
+
+    println!("cargo:warning=Building with Zig version: {}", version);
+    Ok(())
+}
+
+/// Build `src/ffi/zeke_ffi.zig` through the `ffi` step of the repository's
+/// build.zig, which wires in the `zeke` module and its dependencies
+///
+/// Returns the directory holding `libzeke_ffi.a`.
+fn build_full(zeke_root: &Path, out_dir: &Path, zig_target: &str, opt_level: &str) -> Result<PathBuf> {
+    let prefix = out_dir.join("zig-out");
+
+    let mut zig_build = Command::new("zig");
+    zig_build
+        .current_dir(zeke_root)
+        .arg("build")
+        .arg("ffi")
+        .arg(format!("-Doptimize={}", opt_level))
+        .arg("--prefix")
+        .arg(&prefix)
+        .arg("--cache-dir")
+        .arg(out_dir.join("zig-cache"));
+    if zig_target != "native" {
+        zig_build.arg(format!("-Dtarget={}", zig_target));
+    }
+
+    run_zig(zig_build)?;
+    Ok(prefix.join("lib"))
+}
+
+/// Build the dependency-free `src/ffi/zeke_ffi_minimal.zig` directly
+///
+/// Returns the directory holding `libzeke_ffi.a`.
+fn build_minimal(zeke_root: &Path, out_dir: &Path, zig_target: &str, opt_level: &str) -> Result<PathBuf> {
+    let zig_source = zeke_root.join("src/ffi/zeke_ffi_minimal.zig");
+    let lib_output = out_dir.join(format!("lib{}.a", LIB_NAME));
+
+    let mut zig_build = Command::new("zig");
+    zig_build
+        .arg("build-lib")
+        .arg(&zig_source)
+        .args(["-target", zig_target])
+        .arg(format!("-O{}", opt_level))
+        .args(["--name", LIB_NAME])
+        .arg(format!("-femit-bin={}", lib_output.display()))
+        .arg("-fno-emit-h") // We provide our own header
+        .arg("-lc"); // Link with C standard library
+
+    run_zig(zig_build)?;
+    Ok(out_dir.to_path_buf())
+}
+
+fn run_zig(mut command: Command) -> Result<()> {
+    let output = command
+        .output()
+        .map_err(|e| BuildError(format!("failed to run the Zig compiler: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(BuildError(format!(
+            "Zig compilation failed:\n{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Bindings
+// ============================================================================
+
+/// Generate bindings from the header and check them against the checked-in copy
+#[cfg(feature = "bindgen")]
+fn write_bindings(header_path: &Path, checked_in: &Path, bindings_path: &Path, dynamic: bool) -> Result<()> {
+    println!("cargo:rerun-if-changed={}", header_path.display());
+
+    let mut builder = bindgen::Builder::default();
+    if dynamic {
+        // Every function becomes a field of `ZekeLibrary`; missing symbols stay
+        // as errors in their fields instead of failing the whole load
+        builder = builder
+            .dynamic_library_name("ZekeLibrary")
+            .dynamic_link_require_all(false);
+    }
+
+    let bindings = builder
         .header(header_path.to_str().unwrap())
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
-        
+
+        // Emit `unsafe extern` blocks as edition 2024 requires
+        .rust_edition(bindgen::RustEdition::Edition2024)
+
         // Generate comments from header documentation
         .generate_comments(true)
-        
+
         // Use core instead of std for no_std compatibility
         .use_core()
         .ctypes_prefix("libc")
-        
+
         // Allowlist the Zeke functions and types
         .allowlist_function("zeke_.*")
         .allowlist_type("Zeke.*")
         .allowlist_var("ZEKE_.*")
-        
+
         // Customize enum generation
-        .default_enum_style(bindgen::EnumVariation::Rust { 
-            non_exhaustive: false 
+        .default_enum_style(bindgen::EnumVariation::Rust {
+            non_exhaustive: false
         })
         .prepend_enum_name(false)
-        
+
         // Derive common traits
         .derive_debug(true)
         .derive_default(true)
@@ -172,70 +409,65 @@ fn main() {
         .derive_ord(true)
         .derive_partialord(true)
         .derive_hash(true)
-        
+
         // Layout tests for struct sizes
         .layout_tests(true)
-        .size_t_is_usize(true)
-        
+
         // Custom type replacements
         .blocklist_type("size_t")
-        
+
         .generate()
-        .expect("Unable to generate Rust bindings");
-    
-    let bindings_path = out_dir.join("bindings.rs");
-    bindings
-        .write_to_file(&bindings_path)
-        .expect("Couldn't write bindings!");
-    
-    println!("cargo:warning=Generated Rust bindings at: {}", bindings_path.display());
-    
-    // Create a convenience script for manual testing
-    let test_script = out_dir.join("test_ffi.sh");
-    let script_content = format!(r#"#!/bin/bash
-set -e
-echo "Testing Zeke FFI library..."
-echo "Library path: {}"
-echo "Bindings path: {}"
-
-# Check if library exists and is readable
-if [ -f "{}" ]; then
-    echo "✓ FFI library exists"
-    file "{}" 
-    nm -D "{}" | head -10 || nm "{}" | head -10
-else
-    echo "✗ FFI library not found"
-    exit 1
-fi
-
-# Check bindings
-if [ -f "{}" ]; then
-    echo "✓ Rust bindings generated"
-    wc -l "{}"
-else
-    echo "✗ Rust bindings not found"
-    exit 1
-fi
-
-echo "FFI build successful!"
-"#, 
-        lib_output.display(),       // Library path echo
-        bindings_path.display(),    // Bindings path echo
-        lib_output.display(),       // if [ -f "..." check
-        lib_output.display(),       // file command
-        lib_output.display(),       // nm -D command
-        lib_output.display(),       // nm command (fallback)
-        bindings_path.display(),    // bindings if [ -f "..." check
-        bindings_path.display()     // wc -l command
-    );
-    std::fs::write(&test_script, script_content).expect("Failed to write test script");
-    
-    // Make script executable on Unix
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&test_script).unwrap().permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&test_script, perms).unwrap();
+        .map_err(|e| BuildError(format!(
+            "bindgen failed on {}: {}\n\
+             bindgen needs libclang; build without the `bindgen` feature to use the checked-in bindings",
+            header_path.display(),
+            e
+        )))?;
+
+    let generated = bindings.to_string();
+    std::fs::write(bindings_path, &generated)
+        .map_err(|e| BuildError(format!("couldn't write {}: {}", bindings_path.display(), e)))?;
+
+    let current = std::fs::read_to_string(checked_in).unwrap_or_default();
+    if env::var_os("ZEKE_UPDATE_BINDINGS").is_some() {
+        std::fs::write(checked_in, &generated)
+            .map_err(|e| BuildError(format!("couldn't write {}: {}", checked_in.display(), e)))?;
+        println!("cargo:warning=Updated {}", checked_in.display());
+    } else if normalized(&current) != normalized(&generated) {
+        println!(
+            "cargo:warning={} is out of date with {}; refresh it with ZEKE_UPDATE_BINDINGS=1",
+            checked_in.display(),
+            header_path.display()
+        );
     }
-}
\ No newline at end of file
+    Ok(())
+}
+
+/// The tokens of generated bindings, without comments or layout
+///
+/// Line wrapping, line endings and the version banner change with the bindgen
+/// and libclang in use, so only a change to the code itself counts as drift.
+#[cfg(feature = "bindgen")]
+fn normalized(bindings: &str) -> Vec<&str> {
+    bindings
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("//") && !line.starts_with("/*"))
+        .flat_map(str::split_whitespace)
+        .collect()
+}
+
+/// Use the checked-in bindings
+#[cfg(not(feature = "bindgen"))]
+fn write_bindings(_header_path: &Path, checked_in: &Path, bindings_path: &Path, _dynamic: bool) -> Result<()> {
+    println!("cargo:rerun-if-changed={}", checked_in.display());
+
+    std::fs::copy(checked_in, bindings_path).map_err(|e| {
+        BuildError(format!(
+            "couldn't copy pre-generated bindings {}: {}",
+            checked_in.display(),
+            e
+        ))
+    })?;
+    Ok(())
+}
@@ -0,0 +1,398 @@
+// Pre-generated bindings for src/ffi/zeke_ffi.h, used when the `bindgen`
+// feature is disabled. Refresh with:
+//
+//     ZEKE_UPDATE_BINDINGS=1 cargo build -p zeke-sys
+
+pub const ZEKE_ABI_VERSION: u32 = 4;
+pub const ZEKE_MAX_STRUCT_FIELDS: u32 = 16;
+pub const ZEKE_ERROR_MODEL_LEN: u32 = 128;
+pub const ZEKE_ERROR_MESSAGE_LEN: u32 = 512;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeHandle {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeConfigHandle {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeAuthHandle {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeProviderHandle {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeStreamHandle {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeGhostLLMHandle {
+    _unused: [u8; 0],
+}
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ZekeErrorCode {
+    ZEKE_SUCCESS = 0,
+    ZEKE_INITIALIZATION_FAILED = -1,
+    ZEKE_AUTHENTICATION_FAILED = -2,
+    ZEKE_CONFIG_LOAD_FAILED = -3,
+    ZEKE_NETWORK_ERROR = -4,
+    ZEKE_INVALID_MODEL = -5,
+    ZEKE_TOKEN_EXCHANGE_FAILED = -6,
+    ZEKE_UNEXPECTED_RESPONSE = -7,
+    ZEKE_MEMORY_ERROR = -8,
+    ZEKE_INVALID_PARAMETER = -9,
+    ZEKE_PROVIDER_UNAVAILABLE = -10,
+    ZEKE_STREAMING_FAILED = -11,
+    ZEKE_RATE_LIMITED = -12,
+}
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ZekeProvider {
+    ZEKE_PROVIDER_COPILOT = 0,
+    ZEKE_PROVIDER_CLAUDE = 1,
+    ZEKE_PROVIDER_OPENAI = 2,
+    ZEKE_PROVIDER_OLLAMA = 3,
+    ZEKE_PROVIDER_GHOSTLLM = 4,
+    ZEKE_PROVIDER_XAI = 5,
+    ZEKE_PROVIDER_GOOGLE = 6,
+    ZEKE_PROVIDER_AZURE = 7,
+    ZEKE_PROVIDER_OMEN = 8,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+pub struct ZekeConfig {
+    pub base_url: *const libc::c_char,
+    pub api_key: *const libc::c_char,
+    pub provider: libc::c_int,
+    pub model_name: *const libc::c_char,
+    pub temperature: f32,
+    pub max_tokens: u32,
+    pub stream: bool,
+    pub enable_gpu: bool,
+    pub enable_fallback: bool,
+    pub timeout_ms: u32,
+}
+impl Default for ZekeConfig {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ZekeResponse {
+    pub content: *const libc::c_char,
+    pub provider_used: libc::c_int,
+    pub tokens_used: u32,
+    pub response_time_ms: u32,
+    pub error_code: ZekeErrorCode,
+    pub error_message: *const libc::c_char,
+}
+impl Default for ZekeResponse {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ZekeStreamChunk {
+    pub content: *const libc::c_char,
+    pub is_final: bool,
+    pub chunk_index: u32,
+    pub total_chunks: u32,
+}
+impl Default for ZekeStreamChunk {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ZekeGpuInfo {
+    pub device_name: *const libc::c_char,
+    pub memory_used_mb: u64,
+    pub memory_total_mb: u64,
+    pub utilization_percent: u8,
+    pub temperature_celsius: u8,
+    pub power_watts: u32,
+}
+impl Default for ZekeGpuInfo {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialOrd, PartialEq)]
+pub struct ZekeProviderStatus {
+    pub provider: libc::c_int,
+    pub is_healthy: bool,
+    pub response_time_ms: u32,
+    pub error_rate: f32,
+    pub requests_per_minute: u32,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ZekeErrorDetail {
+    pub code: ZekeErrorCode,
+    pub http_status: u16,
+    pub provider: libc::c_int,
+    pub retry_after_ms: u32,
+    pub model: [libc::c_char; 128usize],
+    pub message: [libc::c_char; 512usize],
+}
+impl Default for ZekeErrorDetail {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ZekeStructId {
+    ZEKE_STRUCT_CONFIG = 0,
+    ZEKE_STRUCT_RESPONSE = 1,
+    ZEKE_STRUCT_STREAM_CHUNK = 2,
+    ZEKE_STRUCT_GPU_INFO = 3,
+    ZEKE_STRUCT_PROVIDER_STATUS = 4,
+    ZEKE_STRUCT_ERROR_DETAIL = 5,
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ZekeStructLayout {
+    pub size: usize,
+    pub alignment: usize,
+    pub field_count: usize,
+    pub offsets: [usize; 16usize],
+}
+pub type ZekeStreamCallback = ::core::option::Option<
+    unsafe extern "C" fn(chunk: *const ZekeStreamChunk, user_data: *mut libc::c_void) -> bool,
+>;
+pub type ZekeAsyncCallback = ::core::option::Option<
+    unsafe extern "C" fn(response: *const ZekeResponse, user_data: *mut libc::c_void),
+>;
+unsafe extern "C" {
+    /// Initialize a new Zeke instance with the given configuration
+    /// @param config Configuration parameters for Zeke
+    /// @return Handle to Zeke instance or NULL on failure
+    pub fn zeke_init(config: *const ZekeConfig) -> *mut ZekeHandle;
+}
+unsafe extern "C" {
+    /// Clean up and destroy a Zeke instance
+    /// @param handle Zeke instance handle
+    pub fn zeke_destroy(handle: *mut ZekeHandle);
+}
+unsafe extern "C" {
+    /// Get the version string of Zeke
+    /// @return Version string
+    pub fn zeke_version() -> *const libc::c_char;
+}
+unsafe extern "C" {
+    /// Get the ABI revision the library was built with
+    /// @return ZEKE_ABI_VERSION from the library's copy of this header
+    pub fn zeke_abi_version() -> u32;
+}
+unsafe extern "C" {
+    /// Describe how the library lays out one of the public structures
+    /// @param id Structure to describe
+    /// @param layout_out Output layout
+    /// @return Error code (ZEKE_INVALID_PARAMETER for an unknown structure)
+    pub fn zeke_struct_layout(id: ZekeStructId, layout_out: *mut ZekeStructLayout) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Send a chat message and get a response
+    pub fn zeke_chat(
+        handle: *mut ZekeHandle,
+        message: *const libc::c_char,
+        response_out: *mut ZekeResponse,
+    ) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Send a streaming chat message with callback for chunks
+    /// @param handle Zeke instance handle
+    /// @param message Input message
+    /// @param callback Callback function for stream chunks; returning false cancels the
+    /// stream, no further chunks are delivered and the call returns ZEKE_SUCCESS
+    /// @param user_data User data passed to callback
+    /// @return Error code
+    pub fn zeke_chat_stream(
+        handle: *mut ZekeHandle,
+        message: *const libc::c_char,
+        callback: ZekeStreamCallback,
+        user_data: *mut libc::c_void,
+    ) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Free memory allocated for a ZekeResponse
+    /// @param response Response to free
+    pub fn zeke_free_response(response: *mut ZekeResponse);
+}
+unsafe extern "C" {
+    /// Set authentication token for a provider
+    /// @param handle Zeke instance handle
+    /// @param provider Provider type
+    /// @param token Authentication token
+    /// @return Error code; ZEKE_INVALID_PARAMETER for GhostLLM, xAI and OMEN,
+    /// which have no credential slot in the core
+    pub fn zeke_set_auth_token(
+        handle: *mut ZekeHandle,
+        provider: libc::c_int,
+        token: *const libc::c_char,
+    ) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Test authentication for a provider
+    /// @param handle Zeke instance handle
+    /// @param provider Provider type
+    /// @return Error code (ZEKE_SUCCESS if authenticated); ZEKE_INVALID_PARAMETER
+    /// for GhostLLM, xAI and OMEN, which have no credential slot in the core
+    pub fn zeke_test_auth(handle: *mut ZekeHandle, provider: libc::c_int) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Switch to a different provider
+    /// @param handle Zeke instance handle
+    /// @param provider Provider to switch to
+    /// @return Error code; ZEKE_INVALID_PARAMETER for GhostLLM and OMEN, which the
+    /// core has no API client for
+    pub fn zeke_switch_provider(handle: *mut ZekeHandle, provider: libc::c_int) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Get status of all providers
+    /// @param handle Zeke instance handle
+    /// @param status_array Array to fill with status information
+    /// @param array_size Size of the array
+    /// @param actual_count Actual number of providers
+    /// @return Error code
+    pub fn zeke_get_provider_status(
+        handle: *mut ZekeHandle,
+        status_array: *mut ZekeProviderStatus,
+        array_size: usize,
+        actual_count: *mut usize,
+    ) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Initialize GhostLLM GPU client
+    /// @param handle Zeke instance handle
+    /// @param base_url GhostLLM server URL
+    /// @param enable_gpu Whether to enable GPU acceleration
+    /// @return Error code; ZEKE_PROVIDER_UNAVAILABLE, the core has no GhostLLM client
+    pub fn zeke_ghostllm_init(
+        handle: *mut ZekeHandle,
+        base_url: *const libc::c_char,
+        enable_gpu: bool,
+    ) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Get GPU information from GhostLLM
+    /// @param handle Zeke instance handle
+    /// @param gpu_info Output GPU information, zeroed
+    /// @return Error code; ZEKE_PROVIDER_UNAVAILABLE, the core has no GhostLLM client
+    pub fn zeke_ghostllm_get_gpu_info(
+        handle: *mut ZekeHandle,
+        gpu_info: *mut ZekeGpuInfo,
+    ) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Free GPU info memory
+    /// @param gpu_info GPU info to free
+    pub fn zeke_free_gpu_info(gpu_info: *mut ZekeGpuInfo);
+}
+unsafe extern "C" {
+    /// Run GhostLLM benchmark
+    /// @param handle Zeke instance handle
+    /// @param model_name Model to benchmark
+    /// @param batch_size Batch size for benchmark
+    /// @return Error code; ZEKE_PROVIDER_UNAVAILABLE, the core has no GhostLLM client
+    pub fn zeke_ghostllm_benchmark(
+        handle: *mut ZekeHandle,
+        model_name: *const libc::c_char,
+        batch_size: u32,
+    ) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Load configuration from file
+    /// @param config_path Path to configuration file; `.toml` files are read like the CLI reads zeke.toml
+    /// @return Configuration handle or NULL on failure
+    pub fn zeke_load_config(config_path: *const libc::c_char) -> *mut ZekeConfigHandle;
+}
+unsafe extern "C" {
+    /// Create a configuration handle from instance settings
+    /// @param config Settings to store; strings are copied
+    /// @return Configuration handle or NULL on failure
+    pub fn zeke_config_from(config: *const ZekeConfig) -> *mut ZekeConfigHandle;
+}
+unsafe extern "C" {
+    /// Read the instance settings of a configuration handle
+    /// @param config_handle Configuration handle
+    /// @param config_out Receives the settings; unset strings are empty, and all
+    ///                   strings stay valid until the handle is freed
+    /// @return Error code
+    pub fn zeke_config_get(
+        config_handle: *mut ZekeConfigHandle,
+        config_out: *mut ZekeConfig,
+    ) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Save configuration to file
+    /// @param config_handle Configuration handle
+    /// @param config_path Path to save configuration
+    /// @return Error code
+    pub fn zeke_save_config(
+        config_handle: *mut ZekeConfigHandle,
+        config_path: *const libc::c_char,
+    ) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Free configuration handle
+    /// @param config_handle Configuration handle to free
+    pub fn zeke_free_config(config_handle: *mut ZekeConfigHandle);
+}
+unsafe extern "C" {
+    /// Get last error message (thread-local)
+    /// Any call on the thread may overwrite it; prefer zeke_get_error_detail for calls on a handle
+    /// @return Error message string
+    pub fn zeke_get_last_error() -> *const libc::c_char;
+}
+unsafe extern "C" {
+    /// Get the outcome of the most recent call on a handle
+    /// Every call taking the handle replaces it, so read it before making another
+    /// @param handle Zeke instance handle
+    /// @param detail_out Receives the outcome
+    /// @return Error code
+    pub fn zeke_get_error_detail(
+        handle: *const ZekeHandle,
+        detail_out: *mut ZekeErrorDetail,
+    ) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    /// Check if Zeke instance is healthy
+    /// @param handle Zeke instance handle
+    /// @return Error code (ZEKE_SUCCESS if healthy)
+    pub fn zeke_health_check(handle: *mut ZekeHandle) -> ZekeErrorCode;
+}
@@ -0,0 +1,453 @@
+// Pre-generated bindings for src/ffi/zeke_ffi.h with runtime loading, used
+// by the `dynamic` feature when the `bindgen` feature is disabled. Refresh with:
+//
+//     ZEKE_UPDATE_BINDINGS=1 cargo build -p zeke-sys --features dynamic
+
+pub const ZEKE_ABI_VERSION: u32 = 4;
+pub const ZEKE_MAX_STRUCT_FIELDS: u32 = 16;
+pub const ZEKE_ERROR_MODEL_LEN: u32 = 128;
+pub const ZEKE_ERROR_MESSAGE_LEN: u32 = 512;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeHandle {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeConfigHandle {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeAuthHandle {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeProviderHandle {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeStreamHandle {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeGhostLLMHandle {
+    _unused: [u8; 0],
+}
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ZekeErrorCode {
+    ZEKE_SUCCESS = 0,
+    ZEKE_INITIALIZATION_FAILED = -1,
+    ZEKE_AUTHENTICATION_FAILED = -2,
+    ZEKE_CONFIG_LOAD_FAILED = -3,
+    ZEKE_NETWORK_ERROR = -4,
+    ZEKE_INVALID_MODEL = -5,
+    ZEKE_TOKEN_EXCHANGE_FAILED = -6,
+    ZEKE_UNEXPECTED_RESPONSE = -7,
+    ZEKE_MEMORY_ERROR = -8,
+    ZEKE_INVALID_PARAMETER = -9,
+    ZEKE_PROVIDER_UNAVAILABLE = -10,
+    ZEKE_STREAMING_FAILED = -11,
+    ZEKE_RATE_LIMITED = -12,
+}
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ZekeProvider {
+    ZEKE_PROVIDER_COPILOT = 0,
+    ZEKE_PROVIDER_CLAUDE = 1,
+    ZEKE_PROVIDER_OPENAI = 2,
+    ZEKE_PROVIDER_OLLAMA = 3,
+    ZEKE_PROVIDER_GHOSTLLM = 4,
+    ZEKE_PROVIDER_XAI = 5,
+    ZEKE_PROVIDER_GOOGLE = 6,
+    ZEKE_PROVIDER_AZURE = 7,
+    ZEKE_PROVIDER_OMEN = 8,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+pub struct ZekeConfig {
+    pub base_url: *const libc::c_char,
+    pub api_key: *const libc::c_char,
+    pub provider: libc::c_int,
+    pub model_name: *const libc::c_char,
+    pub temperature: f32,
+    pub max_tokens: u32,
+    pub stream: bool,
+    pub enable_gpu: bool,
+    pub enable_fallback: bool,
+    pub timeout_ms: u32,
+}
+impl Default for ZekeConfig {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ZekeResponse {
+    pub content: *const libc::c_char,
+    pub provider_used: libc::c_int,
+    pub tokens_used: u32,
+    pub response_time_ms: u32,
+    pub error_code: ZekeErrorCode,
+    pub error_message: *const libc::c_char,
+}
+impl Default for ZekeResponse {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ZekeStreamChunk {
+    pub content: *const libc::c_char,
+    pub is_final: bool,
+    pub chunk_index: u32,
+    pub total_chunks: u32,
+}
+impl Default for ZekeStreamChunk {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ZekeGpuInfo {
+    pub device_name: *const libc::c_char,
+    pub memory_used_mb: u64,
+    pub memory_total_mb: u64,
+    pub utilization_percent: u8,
+    pub temperature_celsius: u8,
+    pub power_watts: u32,
+}
+impl Default for ZekeGpuInfo {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialOrd, PartialEq)]
+pub struct ZekeProviderStatus {
+    pub provider: libc::c_int,
+    pub is_healthy: bool,
+    pub response_time_ms: u32,
+    pub error_rate: f32,
+    pub requests_per_minute: u32,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ZekeErrorDetail {
+    pub code: ZekeErrorCode,
+    pub http_status: u16,
+    pub provider: libc::c_int,
+    pub retry_after_ms: u32,
+    pub model: [libc::c_char; 128usize],
+    pub message: [libc::c_char; 512usize],
+}
+impl Default for ZekeErrorDetail {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ZekeStructId {
+    ZEKE_STRUCT_CONFIG = 0,
+    ZEKE_STRUCT_RESPONSE = 1,
+    ZEKE_STRUCT_STREAM_CHUNK = 2,
+    ZEKE_STRUCT_GPU_INFO = 3,
+    ZEKE_STRUCT_PROVIDER_STATUS = 4,
+    ZEKE_STRUCT_ERROR_DETAIL = 5,
+}
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ZekeStructLayout {
+    pub size: usize,
+    pub alignment: usize,
+    pub field_count: usize,
+    pub offsets: [usize; 16usize],
+}
+pub type ZekeStreamCallback = ::core::option::Option<
+    unsafe extern "C" fn(chunk: *const ZekeStreamChunk, user_data: *mut libc::c_void) -> bool,
+>;
+pub type ZekeAsyncCallback = ::core::option::Option<
+    unsafe extern "C" fn(response: *const ZekeResponse, user_data: *mut libc::c_void),
+>;
+pub struct ZekeLibrary {
+    __library: ::libloading::Library,
+    pub zeke_init: Result<unsafe extern "C" fn(config: *const ZekeConfig) -> *mut ZekeHandle, ::libloading::Error>,
+    pub zeke_destroy: Result<unsafe extern "C" fn(handle: *mut ZekeHandle), ::libloading::Error>,
+    pub zeke_version: Result<unsafe extern "C" fn() -> *const libc::c_char, ::libloading::Error>,
+    pub zeke_abi_version: Result<unsafe extern "C" fn() -> u32, ::libloading::Error>,
+    pub zeke_struct_layout: Result<unsafe extern "C" fn(id: ZekeStructId, layout_out: *mut ZekeStructLayout) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_chat: Result<unsafe extern "C" fn(handle: *mut ZekeHandle, message: *const libc::c_char, response_out: *mut ZekeResponse) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_chat_stream: Result<unsafe extern "C" fn(handle: *mut ZekeHandle, message: *const libc::c_char, callback: ZekeStreamCallback, user_data: *mut libc::c_void) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_free_response: Result<unsafe extern "C" fn(response: *mut ZekeResponse), ::libloading::Error>,
+    pub zeke_set_auth_token: Result<unsafe extern "C" fn(handle: *mut ZekeHandle, provider: libc::c_int, token: *const libc::c_char) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_test_auth: Result<unsafe extern "C" fn(handle: *mut ZekeHandle, provider: libc::c_int) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_switch_provider: Result<unsafe extern "C" fn(handle: *mut ZekeHandle, provider: libc::c_int) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_get_provider_status: Result<unsafe extern "C" fn(handle: *mut ZekeHandle, status_array: *mut ZekeProviderStatus, array_size: usize, actual_count: *mut usize) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_ghostllm_init: Result<unsafe extern "C" fn(handle: *mut ZekeHandle, base_url: *const libc::c_char, enable_gpu: bool) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_ghostllm_get_gpu_info: Result<unsafe extern "C" fn(handle: *mut ZekeHandle, gpu_info: *mut ZekeGpuInfo) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_free_gpu_info: Result<unsafe extern "C" fn(gpu_info: *mut ZekeGpuInfo), ::libloading::Error>,
+    pub zeke_ghostllm_benchmark: Result<unsafe extern "C" fn(handle: *mut ZekeHandle, model_name: *const libc::c_char, batch_size: u32) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_load_config: Result<unsafe extern "C" fn(config_path: *const libc::c_char) -> *mut ZekeConfigHandle, ::libloading::Error>,
+    pub zeke_config_from: Result<unsafe extern "C" fn(config: *const ZekeConfig) -> *mut ZekeConfigHandle, ::libloading::Error>,
+    pub zeke_config_get: Result<unsafe extern "C" fn(config_handle: *mut ZekeConfigHandle, config_out: *mut ZekeConfig) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_save_config: Result<unsafe extern "C" fn(config_handle: *mut ZekeConfigHandle, config_path: *const libc::c_char) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_free_config: Result<unsafe extern "C" fn(config_handle: *mut ZekeConfigHandle), ::libloading::Error>,
+    pub zeke_get_last_error: Result<unsafe extern "C" fn() -> *const libc::c_char, ::libloading::Error>,
+    pub zeke_get_error_detail: Result<unsafe extern "C" fn(handle: *const ZekeHandle, detail_out: *mut ZekeErrorDetail) -> ZekeErrorCode, ::libloading::Error>,
+    pub zeke_health_check: Result<unsafe extern "C" fn(handle: *mut ZekeHandle) -> ZekeErrorCode, ::libloading::Error>,
+}
+impl ZekeLibrary {
+    pub unsafe fn new<P>(path: P) -> Result<Self, ::libloading::Error>
+    where
+        P: AsRef<::std::ffi::OsStr>,
+    {
+        let library = unsafe { ::libloading::Library::new(path) }?;
+        unsafe { Self::from_library(library) }
+    }
+    pub unsafe fn from_library<L>(library: L) -> Result<Self, ::libloading::Error>
+    where
+        L: Into<::libloading::Library>,
+    {
+        let __library = library.into();
+        let zeke_init = unsafe { __library.get(b"zeke_init\0") }.map(|sym| *sym);
+        let zeke_destroy = unsafe { __library.get(b"zeke_destroy\0") }.map(|sym| *sym);
+        let zeke_version = unsafe { __library.get(b"zeke_version\0") }.map(|sym| *sym);
+        let zeke_abi_version = unsafe { __library.get(b"zeke_abi_version\0") }.map(|sym| *sym);
+        let zeke_struct_layout = unsafe { __library.get(b"zeke_struct_layout\0") }.map(|sym| *sym);
+        let zeke_chat = unsafe { __library.get(b"zeke_chat\0") }.map(|sym| *sym);
+        let zeke_chat_stream = unsafe { __library.get(b"zeke_chat_stream\0") }.map(|sym| *sym);
+        let zeke_free_response = unsafe { __library.get(b"zeke_free_response\0") }.map(|sym| *sym);
+        let zeke_set_auth_token = unsafe { __library.get(b"zeke_set_auth_token\0") }.map(|sym| *sym);
+        let zeke_test_auth = unsafe { __library.get(b"zeke_test_auth\0") }.map(|sym| *sym);
+        let zeke_switch_provider = unsafe { __library.get(b"zeke_switch_provider\0") }.map(|sym| *sym);
+        let zeke_get_provider_status = unsafe { __library.get(b"zeke_get_provider_status\0") }.map(|sym| *sym);
+        let zeke_ghostllm_init = unsafe { __library.get(b"zeke_ghostllm_init\0") }.map(|sym| *sym);
+        let zeke_ghostllm_get_gpu_info = unsafe { __library.get(b"zeke_ghostllm_get_gpu_info\0") }.map(|sym| *sym);
+        let zeke_free_gpu_info = unsafe { __library.get(b"zeke_free_gpu_info\0") }.map(|sym| *sym);
+        let zeke_ghostllm_benchmark = unsafe { __library.get(b"zeke_ghostllm_benchmark\0") }.map(|sym| *sym);
+        let zeke_load_config = unsafe { __library.get(b"zeke_load_config\0") }.map(|sym| *sym);
+        let zeke_config_from = unsafe { __library.get(b"zeke_config_from\0") }.map(|sym| *sym);
+        let zeke_config_get = unsafe { __library.get(b"zeke_config_get\0") }.map(|sym| *sym);
+        let zeke_save_config = unsafe { __library.get(b"zeke_save_config\0") }.map(|sym| *sym);
+        let zeke_free_config = unsafe { __library.get(b"zeke_free_config\0") }.map(|sym| *sym);
+        let zeke_get_last_error = unsafe { __library.get(b"zeke_get_last_error\0") }.map(|sym| *sym);
+        let zeke_get_error_detail = unsafe { __library.get(b"zeke_get_error_detail\0") }.map(|sym| *sym);
+        let zeke_health_check = unsafe { __library.get(b"zeke_health_check\0") }.map(|sym| *sym);
+        Ok(ZekeLibrary {
+            __library,
+            zeke_init,
+            zeke_destroy,
+            zeke_version,
+            zeke_abi_version,
+            zeke_struct_layout,
+            zeke_chat,
+            zeke_chat_stream,
+            zeke_free_response,
+            zeke_set_auth_token,
+            zeke_test_auth,
+            zeke_switch_provider,
+            zeke_get_provider_status,
+            zeke_ghostllm_init,
+            zeke_ghostllm_get_gpu_info,
+            zeke_free_gpu_info,
+            zeke_ghostllm_benchmark,
+            zeke_load_config,
+            zeke_config_from,
+            zeke_config_get,
+            zeke_save_config,
+            zeke_free_config,
+            zeke_get_last_error,
+            zeke_get_error_detail,
+            zeke_health_check,
+        })
+    }
+    /// Initialize a new Zeke instance with the given configuration
+    /// @param config Configuration parameters for Zeke
+    /// @return Handle to Zeke instance or NULL on failure
+    pub unsafe fn zeke_init(&self, config: *const ZekeConfig) -> *mut ZekeHandle {
+        unsafe { (self.zeke_init.as_ref().expect("Expected function, got error."))(config) }
+    }
+    /// Clean up and destroy a Zeke instance
+    /// @param handle Zeke instance handle
+    pub unsafe fn zeke_destroy(&self, handle: *mut ZekeHandle) {
+        unsafe { (self.zeke_destroy.as_ref().expect("Expected function, got error."))(handle) }
+    }
+    /// Get the version string of Zeke
+    /// @return Version string
+    pub unsafe fn zeke_version(&self) -> *const libc::c_char {
+        unsafe { (self.zeke_version.as_ref().expect("Expected function, got error."))() }
+    }
+    /// Get the ABI revision the library was built with
+    /// @return ZEKE_ABI_VERSION from the library's copy of this header
+    pub unsafe fn zeke_abi_version(&self) -> u32 {
+        unsafe { (self.zeke_abi_version.as_ref().expect("Expected function, got error."))() }
+    }
+    /// Describe how the library lays out one of the public structures
+    /// @param id Structure to describe
+    /// @param layout_out Output layout
+    /// @return Error code (ZEKE_INVALID_PARAMETER for an unknown structure)
+    pub unsafe fn zeke_struct_layout(&self, id: ZekeStructId, layout_out: *mut ZekeStructLayout) -> ZekeErrorCode {
+        unsafe { (self.zeke_struct_layout.as_ref().expect("Expected function, got error."))(id, layout_out) }
+    }
+    /// Send a chat message and get a response
+    pub unsafe fn zeke_chat(&self, handle: *mut ZekeHandle, message: *const libc::c_char, response_out: *mut ZekeResponse) -> ZekeErrorCode {
+        unsafe { (self.zeke_chat.as_ref().expect("Expected function, got error."))(handle, message, response_out) }
+    }
+    /// Send a streaming chat message with callback for chunks
+    /// @param handle Zeke instance handle
+    /// @param message Input message
+    /// @param callback Callback function for stream chunks; returning false cancels the
+    /// stream, no further chunks are delivered and the call returns ZEKE_SUCCESS
+    /// @param user_data User data passed to callback
+    /// @return Error code
+    pub unsafe fn zeke_chat_stream(&self, handle: *mut ZekeHandle, message: *const libc::c_char, callback: ZekeStreamCallback, user_data: *mut libc::c_void) -> ZekeErrorCode {
+        unsafe { (self.zeke_chat_stream.as_ref().expect("Expected function, got error."))(handle, message, callback, user_data) }
+    }
+    /// Free memory allocated for a ZekeResponse
+    /// @param response Response to free
+    pub unsafe fn zeke_free_response(&self, response: *mut ZekeResponse) {
+        unsafe { (self.zeke_free_response.as_ref().expect("Expected function, got error."))(response) }
+    }
+    /// Set authentication token for a provider
+    /// @param handle Zeke instance handle
+    /// @param provider Provider type
+    /// @param token Authentication token
+    /// @return Error code; ZEKE_INVALID_PARAMETER for GhostLLM, xAI and OMEN,
+    /// which have no credential slot in the core
+    pub unsafe fn zeke_set_auth_token(&self, handle: *mut ZekeHandle, provider: libc::c_int, token: *const libc::c_char) -> ZekeErrorCode {
+        unsafe { (self.zeke_set_auth_token.as_ref().expect("Expected function, got error."))(handle, provider, token) }
+    }
+    /// Test authentication for a provider
+    /// @param handle Zeke instance handle
+    /// @param provider Provider type
+    /// @return Error code (ZEKE_SUCCESS if authenticated); ZEKE_INVALID_PARAMETER
+    /// for GhostLLM, xAI and OMEN, which have no credential slot in the core
+    pub unsafe fn zeke_test_auth(&self, handle: *mut ZekeHandle, provider: libc::c_int) -> ZekeErrorCode {
+        unsafe { (self.zeke_test_auth.as_ref().expect("Expected function, got error."))(handle, provider) }
+    }
+    /// Switch to a different provider
+    /// @param handle Zeke instance handle
+    /// @param provider Provider to switch to
+    /// @return Error code; ZEKE_INVALID_PARAMETER for GhostLLM and OMEN, which the
+    /// core has no API client for
+    pub unsafe fn zeke_switch_provider(&self, handle: *mut ZekeHandle, provider: libc::c_int) -> ZekeErrorCode {
+        unsafe { (self.zeke_switch_provider.as_ref().expect("Expected function, got error."))(handle, provider) }
+    }
+    /// Get status of all providers
+    /// @param handle Zeke instance handle
+    /// @param status_array Array to fill with status information
+    /// @param array_size Size of the array
+    /// @param actual_count Actual number of providers
+    /// @return Error code
+    pub unsafe fn zeke_get_provider_status(&self, handle: *mut ZekeHandle, status_array: *mut ZekeProviderStatus, array_size: usize, actual_count: *mut usize) -> ZekeErrorCode {
+        unsafe { (self.zeke_get_provider_status.as_ref().expect("Expected function, got error."))(handle, status_array, array_size, actual_count) }
+    }
+    /// Initialize GhostLLM GPU client
+    /// @param handle Zeke instance handle
+    /// @param base_url GhostLLM server URL
+    /// @param enable_gpu Whether to enable GPU acceleration
+    /// @return Error code; ZEKE_PROVIDER_UNAVAILABLE, the core has no GhostLLM client
+    pub unsafe fn zeke_ghostllm_init(&self, handle: *mut ZekeHandle, base_url: *const libc::c_char, enable_gpu: bool) -> ZekeErrorCode {
+        unsafe { (self.zeke_ghostllm_init.as_ref().expect("Expected function, got error."))(handle, base_url, enable_gpu) }
+    }
+    /// Get GPU information from GhostLLM
+    /// @param handle Zeke instance handle
+    /// @param gpu_info Output GPU information, zeroed
+    /// @return Error code; ZEKE_PROVIDER_UNAVAILABLE, the core has no GhostLLM client
+    pub unsafe fn zeke_ghostllm_get_gpu_info(&self, handle: *mut ZekeHandle, gpu_info: *mut ZekeGpuInfo) -> ZekeErrorCode {
+        unsafe { (self.zeke_ghostllm_get_gpu_info.as_ref().expect("Expected function, got error."))(handle, gpu_info) }
+    }
+    /// Free GPU info memory
+    /// @param gpu_info GPU info to free
+    pub unsafe fn zeke_free_gpu_info(&self, gpu_info: *mut ZekeGpuInfo) {
+        unsafe { (self.zeke_free_gpu_info.as_ref().expect("Expected function, got error."))(gpu_info) }
+    }
+    /// Run GhostLLM benchmark
+    /// @param handle Zeke instance handle
+    /// @param model_name Model to benchmark
+    /// @param batch_size Batch size for benchmark
+    /// @return Error code; ZEKE_PROVIDER_UNAVAILABLE, the core has no GhostLLM client
+    pub unsafe fn zeke_ghostllm_benchmark(&self, handle: *mut ZekeHandle, model_name: *const libc::c_char, batch_size: u32) -> ZekeErrorCode {
+        unsafe { (self.zeke_ghostllm_benchmark.as_ref().expect("Expected function, got error."))(handle, model_name, batch_size) }
+    }
+    /// Load configuration from file
+    /// @param config_path Path to configuration file; `.toml` files are read like the CLI reads zeke.toml
+    /// @return Configuration handle or NULL on failure
+    pub unsafe fn zeke_load_config(&self, config_path: *const libc::c_char) -> *mut ZekeConfigHandle {
+        unsafe { (self.zeke_load_config.as_ref().expect("Expected function, got error."))(config_path) }
+    }
+    /// Create a configuration handle from instance settings
+    /// @param config Settings to store; strings are copied
+    /// @return Configuration handle or NULL on failure
+    pub unsafe fn zeke_config_from(&self, config: *const ZekeConfig) -> *mut ZekeConfigHandle {
+        unsafe { (self.zeke_config_from.as_ref().expect("Expected function, got error."))(config) }
+    }
+    /// Read the instance settings of a configuration handle
+    /// @param config_handle Configuration handle
+    /// @param config_out Receives the settings; unset strings are empty, and all
+    ///                   strings stay valid until the handle is freed
+    /// @return Error code
+    pub unsafe fn zeke_config_get(&self, config_handle: *mut ZekeConfigHandle, config_out: *mut ZekeConfig) -> ZekeErrorCode {
+        unsafe { (self.zeke_config_get.as_ref().expect("Expected function, got error."))(config_handle, config_out) }
+    }
+    /// Save configuration to file
+    /// @param config_handle Configuration handle
+    /// @param config_path Path to save configuration
+    /// @return Error code
+    pub unsafe fn zeke_save_config(&self, config_handle: *mut ZekeConfigHandle, config_path: *const libc::c_char) -> ZekeErrorCode {
+        unsafe { (self.zeke_save_config.as_ref().expect("Expected function, got error."))(config_handle, config_path) }
+    }
+    /// Free configuration handle
+    /// @param config_handle Configuration handle to free
+    pub unsafe fn zeke_free_config(&self, config_handle: *mut ZekeConfigHandle) {
+        unsafe { (self.zeke_free_config.as_ref().expect("Expected function, got error."))(config_handle) }
+    }
+    /// Get last error message (thread-local)
+    /// Any call on the thread may overwrite it; prefer zeke_get_error_detail for calls on a handle
+    /// @return Error message string
+    pub unsafe fn zeke_get_last_error(&self) -> *const libc::c_char {
+        unsafe { (self.zeke_get_last_error.as_ref().expect("Expected function, got error."))() }
+    }
+    /// Get the outcome of the most recent call on a handle
+    /// Every call taking the handle replaces it, so read it before making another
+    /// @param handle Zeke instance handle
+    /// @param detail_out Receives the outcome
+    /// @return Error code
+    pub unsafe fn zeke_get_error_detail(&self, handle: *const ZekeHandle, detail_out: *mut ZekeErrorDetail) -> ZekeErrorCode {
+        unsafe { (self.zeke_get_error_detail.as_ref().expect("Expected function, got error."))(handle, detail_out) }
+    }
+    /// Check if Zeke instance is healthy
+    /// @param handle Zeke instance handle
+    /// @return Error code (ZEKE_SUCCESS if healthy)
+    pub unsafe fn zeke_health_check(&self, handle: *mut ZekeHandle) -> ZekeErrorCode {
+        unsafe { (self.zeke_health_check.as_ref().expect("Expected function, got error."))(handle) }
+    }
+}
@@ -0,0 +1,95 @@
+// Pre-generated bindings for src/ffi/zeke_ffi_minimal.h, used when the
+// `bindgen` feature is disabled. Refresh with:
+//
+//     ZEKE_UPDATE_BINDINGS=1 cargo build -p zeke-sys --no-default-features --features bindgen
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeHandle {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZekeConfigHandle {
+    _unused: [u8; 0],
+}
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ZekeErrorCode {
+    ZEKE_SUCCESS = 0,
+    ZEKE_INITIALIZATION_FAILED = -1,
+    ZEKE_AUTHENTICATION_FAILED = -2,
+    ZEKE_NETWORK_ERROR = -4,
+    ZEKE_MEMORY_ERROR = -8,
+    ZEKE_INVALID_PARAMETER = -9,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+pub struct ZekeConfig {
+    pub base_url: *const libc::c_char,
+    pub api_key: *const libc::c_char,
+    pub provider: i32,
+    pub model_name: *const libc::c_char,
+    pub temperature: f32,
+    pub max_tokens: u32,
+    pub stream: bool,
+    pub enable_gpu: bool,
+    pub enable_fallback: bool,
+    pub timeout_ms: u32,
+}
+impl Default for ZekeConfig {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ZekeResponse {
+    pub content: *const libc::c_char,
+    pub provider_used: i32,
+    pub tokens_used: u32,
+    pub response_time_ms: u32,
+    pub error_code: ZekeErrorCode,
+    pub error_message: *const libc::c_char,
+}
+impl Default for ZekeResponse {
+    fn default() -> Self {
+        let mut s = ::core::mem::MaybeUninit::<Self>::uninit();
+        unsafe {
+            ::core::ptr::write_bytes(s.as_mut_ptr(), 0, 1);
+            s.assume_init()
+        }
+    }
+}
+unsafe extern "C" {
+    pub fn zeke_init(config: *const ZekeConfig) -> *mut ZekeHandle;
+}
+unsafe extern "C" {
+    pub fn zeke_chat(
+        handle: *mut ZekeHandle,
+        message: *const libc::c_char,
+        response_out: *mut ZekeResponse,
+    ) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    pub fn zeke_test_auth(handle: *mut ZekeHandle, provider: i32) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    pub fn zeke_free_response(response: *mut ZekeResponse);
+}
+unsafe extern "C" {
+    pub fn zeke_destroy(handle: *mut ZekeHandle);
+}
+unsafe extern "C" {
+    pub fn zeke_version() -> *const libc::c_char;
+}
+unsafe extern "C" {
+    pub fn zeke_health_check(handle: *mut ZekeHandle) -> ZekeErrorCode;
+}
+unsafe extern "C" {
+    pub fn zeke_get_last_error() -> *const libc::c_char;
+}
@@ -1,39 +1,270 @@
-//! Low-level Rust bindings for Zeke AI development companion (Minimal Version)
-//! 
-//! This is a minimal test version that only includes basic FFI functionality.
+//! Low-level Rust bindings for Zeke AI development companion
+//!
+//! This crate provides unsafe, low-level bindings to the Zeke Zig library.
 //! For a safe, high-level interface, use the `zeke` crate instead.
-//! 
+//!
+//! The bindings are generated by bindgen from the C header of whichever
+//! library the build compiles, so the Rust declarations always match it.
+//!
+//! # Features
+//!
+//! - `full` (default): Build `src/ffi/zeke_ffi.zig` and bind `zeke_ffi.h`.
+//!   Without it only the dependency-free minimal stub (`zeke_ffi_minimal.zig`)
+//!   is built, which covers instance management and plain chat.
+//! - `ghostllm`: Bind the GhostLLM GPU acceleration functions. Unsupported:
+//!   the core has no GhostLLM client, so they return `ZEKE_PROVIDER_UNAVAILABLE`.
+//! - `streaming`: Enable streaming response features
+//! - `dynamic`: Load libzeke at runtime instead of linking it. The `zeke_*`
+//!   functions then live in a [`ZekeLibrary`] function table; call them through
+//!   [`zeke_call!`] so the same code works in both modes.
+//!
 //! # Safety
-//! 
+//!
 //! All functions in this crate are unsafe and require careful memory management.
+//! Improper use can lead to:
+//!
+//! - Memory leaks
+//! - Use-after-free bugs
+//! - Buffer overflows
+//! - Segmentation faults
+//!
+//! # Example
+//!
+//! ```no_run
+//! use zeke_sys::*;
+//! use std::ffi::CString;
+//!
+//! unsafe {
+//!     let base_url = CString::new("https://api.openai.com/v1").unwrap();
+//!     let api_key = CString::new("your-api-key").unwrap();
+//!     let model = CString::new("gpt-4").unwrap();
+//!
+//!     // Initialize configuration
+//!     let config = ZekeConfig {
+//!         base_url: base_url.as_ptr(),
+//!         api_key: api_key.as_ptr(),
+//!         model_name: model.as_ptr(),
+//!         ..default_config()
+//!     };
+//!
+//!     // Initialize Zeke
+//!     let handle = zeke_init(&config);
+//!     if handle.is_null() {
+//!         panic!("Failed to initialize Zeke");
+//!     }
+//!
+//!     // Send a chat message
+//!     let message = CString::new("Hello, AI!").unwrap();
+//!     let mut response = std::mem::zeroed::<ZekeResponse>();
+//!
+//!     let result = zeke_chat(handle, message.as_ptr(), &mut response);
+//!     if result == ZEKE_SUCCESS {
+//!         let response_text = std::ffi::CStr::from_ptr(response.content)
+//!             .to_string_lossy();
+//!         println!("AI Response: {}", response_text);
+//!
+//!         // Free the response
+//!         zeke_free_response(&mut response);
+//!     }
+//!
+//!     // Cleanup
+//!     zeke_destroy(handle);
+//! }
+//! ```
 
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 #![allow(clippy::upper_case_acronyms)]
+// The generated `ZekeLibrary` methods carry the header's docs, not `# Safety`
+#![cfg_attr(feature = "dynamic", allow(clippy::missing_safety_doc))]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
-use libc::c_char;
+use libc::{c_char, c_int};
+use std::fmt;
 
-// Include manual bindings (Rust 2024 compatible)
-mod bindings_manual;
-pub use bindings_manual::*;
+// Include the generated bindings
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+// Re-export common types for convenience
+pub use ZekeErrorCode::*;
+#[cfg(feature = "full")]
+pub use ZekeProvider::*;
+#[cfg(feature = "full")]
+pub use ZekeStructId::*;
+
+// ============================================================================
+// Library loading
+// ============================================================================
+
+/// Why a `zeke_*` function could not be called
+///
+/// Only produced with the `dynamic` feature; a linked library always resolves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// No library could be opened from any of the candidate paths
+    NotFound { tried: Vec<String>, message: String },
+    /// The library was opened but does not export this function
+    MissingSymbol { symbol: &'static str },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::NotFound { tried, message } => {
+                write!(f, "libzeke not found (tried {}): {}", tried.join(", "), message)
+            }
+            LoadError::MissingSymbol { symbol } => {
+                write!(f, "libzeke does not export {}", symbol)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Call a `zeke_*` function, yielding `Result<R, LoadError>`
+///
+/// Must be used inside an `unsafe` block. With a linked library this is a
+/// direct call that always succeeds.
+///
+/// ```ignore
+/// let version = unsafe { zeke_call!(zeke_version()) }?;
+/// ```
+#[cfg(not(feature = "dynamic"))]
+#[macro_export]
+macro_rules! zeke_call {
+    ($name:ident($($arg:expr),* $(,)?)) => {
+        ::core::result::Result::Ok::<_, $crate::LoadError>($crate::$name($($arg),*))
+    };
+}
+
+/// Call a `zeke_*` function, yielding `Result<R, LoadError>`
+///
+/// Must be used inside an `unsafe` block. The library is loaded on first use,
+/// and a function it does not export yields [`LoadError::MissingSymbol`].
+///
+/// ```ignore
+/// let version = unsafe { zeke_call!(zeke_version()) }?;
+/// ```
+#[cfg(feature = "dynamic")]
+#[macro_export]
+macro_rules! zeke_call {
+    ($name:ident($($arg:expr),* $(,)?)) => {
+        match $crate::dynamic::library() {
+            ::core::result::Result::Ok(lib) => match &lib.$name {
+                ::core::result::Result::Ok(f) => ::core::result::Result::Ok((*f)($($arg),*)),
+                ::core::result::Result::Err(_) => ::core::result::Result::Err(
+                    $crate::LoadError::MissingSymbol { symbol: stringify!($name) },
+                ),
+            },
+            ::core::result::Result::Err(err) => ::core::result::Result::Err(err),
+        }
+    };
+}
+
+/// Whether a `zeke_*` function can be called
+///
+/// Always true with a linked library; with the `dynamic` feature, true when
+/// the library loads and exports the function.
+#[cfg(not(feature = "dynamic"))]
+#[macro_export]
+macro_rules! zeke_has {
+    ($name:ident) => {{
+        let _ = $crate::$name;
+        true
+    }};
+}
+
+/// Whether a `zeke_*` function can be called
+///
+/// Always true with a linked library; with the `dynamic` feature, true when
+/// the library loads and exports the function.
+#[cfg(feature = "dynamic")]
+#[macro_export]
+macro_rules! zeke_has {
+    ($name:ident) => {
+        $crate::dynamic::library()
+            .map(|lib| lib.$name.is_ok())
+            .unwrap_or(false)
+    };
+}
+
+#[cfg(feature = "dynamic")]
+#[cfg_attr(docsrs, doc(cfg(feature = "dynamic")))]
+pub mod dynamic {
+    //! Runtime loading of libzeke
+    //!
+    //! The library is opened on the first [`zeke_call!`](crate::zeke_call) from
+    //! `ZEKE_LIBRARY` if set, otherwise from the platform's default search path.
+    //! Call [`load`] first to use a specific file instead.
+
+    use super::{LoadError, ZekeLibrary};
+    use std::sync::OnceLock;
+
+    /// Environment variable naming the library file to load
+    pub const LIBRARY_ENV: &str = "ZEKE_LIBRARY";
+
+    static LIBRARY: OnceLock<Result<ZekeLibrary, LoadError>> = OnceLock::new();
+
+    /// The loaded library, opening it on first use
+    pub fn library() -> Result<&'static ZekeLibrary, LoadError> {
+        LIBRARY.get_or_init(|| open(&candidates())).as_ref().map_err(Clone::clone)
+    }
+
+    /// Load the library from `path`
+    ///
+    /// Has no effect if a library was already loaded (or failed to load); the
+    /// existing result is returned in that case.
+    pub fn load(path: &str) -> Result<&'static ZekeLibrary, LoadError> {
+        LIBRARY.get_or_init(|| open(&[path.to_string()])).as_ref().map_err(Clone::clone)
+    }
+
+    /// Whether a library has been loaded successfully
+    pub fn is_loaded() -> bool {
+        matches!(LIBRARY.get(), Some(Ok(_)))
+    }
+
+    fn candidates() -> Vec<String> {
+        let mut paths = Vec::new();
+        if let Ok(path) = std::env::var(LIBRARY_ENV) {
+            paths.push(path);
+        }
+        for name in ["zeke_ffi", "zeke"] {
+            paths.push(libloading::library_filename(name).to_string_lossy().into_owned());
+        }
+        paths
+    }
+
+    fn open(paths: &[String]) -> Result<ZekeLibrary, LoadError> {
+        let mut message = String::new();
+        for path in paths {
+            // Loading runs the library's initializers; libzeke has none that
+            // depend on the caller
+            match unsafe { ZekeLibrary::new(path) } {
+                Ok(library) => return Ok(library),
+                Err(err) => message = err.to_string(),
+            }
+        }
+        Err(LoadError::NotFound { tried: paths.to_vec(), message })
+    }
+}
 
 // ============================================================================
 // Safe wrapper utilities
 // ============================================================================
 
 /// Convert a C string pointer to a Rust string slice
-/// 
+///
 /// # Safety
-/// 
+///
 /// The pointer must be valid and point to a null-terminated C string.
 /// The string must be valid UTF-8.
 pub unsafe fn c_ptr_to_str<'a>(ptr: *const c_char) -> Option<&'a str> {
     if ptr.is_null() {
         return None;
     }
-    
+
     unsafe {
         let c_str = std::ffi::CStr::from_ptr(ptr);
         c_str.to_str().ok()
@@ -41,50 +272,73 @@ pub unsafe fn c_ptr_to_str<'a>(ptr: *const c_char) -> Option<&'a str> {
 }
 
 /// Convert a C string pointer to an owned Rust String
-/// 
+///
 /// # Safety
-/// 
+///
 /// The pointer must be valid and point to a null-terminated C string.
 /// The string must be valid UTF-8.
 pub unsafe fn c_ptr_to_string(ptr: *const c_char) -> Option<String> {
     unsafe { c_ptr_to_str(ptr).map(|s| s.to_owned()) }
 }
 
+/// Read a null-terminated string stored inline in a fixed-size buffer
+///
+/// Returns `None` if the buffer has no terminator or is not valid UTF-8.
+pub fn c_buf_to_str(buf: &[c_char]) -> Option<&str> {
+    // Safety: c_char and u8 have the same size and alignment
+    let bytes = unsafe { std::slice::from_raw_parts(buf.as_ptr().cast::<u8>(), buf.len()) };
+    std::ffi::CStr::from_bytes_until_nul(bytes).ok()?.to_str().ok()
+}
+
 // ============================================================================
-// Error handling utilities  
+// Error handling utilities
 // ============================================================================
 
 /// Check if a Zeke error code indicates success
 pub fn is_success(code: ZekeErrorCode) -> bool {
-    matches!(code, ZekeErrorCode::ZEKE_SUCCESS)
+    code == ZEKE_SUCCESS
 }
 
 /// Check if a Zeke error code indicates failure
 pub fn is_error(code: ZekeErrorCode) -> bool {
-    !matches!(code, ZekeErrorCode::ZEKE_SUCCESS)
+    code != ZEKE_SUCCESS
 }
 
 /// Convert a Zeke error code to a human-readable string
 pub fn error_to_string(code: ZekeErrorCode) -> &'static str {
     match code {
-        ZekeErrorCode::ZEKE_SUCCESS => "Success",
-        ZekeErrorCode::ZEKE_INITIALIZATION_FAILED => "Initialization failed",
-        ZekeErrorCode::ZEKE_AUTHENTICATION_FAILED => "Authentication failed", 
-        ZekeErrorCode::ZEKE_NETWORK_ERROR => "Network error",
-        ZekeErrorCode::ZEKE_MEMORY_ERROR => "Memory error",
-        ZekeErrorCode::ZEKE_INVALID_PARAMETER => "Invalid parameter",
+        ZEKE_SUCCESS => "Success",
+        ZEKE_INITIALIZATION_FAILED => "Initialization failed",
+        ZEKE_AUTHENTICATION_FAILED => "Authentication failed",
+        #[cfg(feature = "full")]
+        ZEKE_CONFIG_LOAD_FAILED => "Configuration load failed",
+        ZEKE_NETWORK_ERROR => "Network error",
+        #[cfg(feature = "full")]
+        ZEKE_INVALID_MODEL => "Invalid model",
+        #[cfg(feature = "full")]
+        ZEKE_TOKEN_EXCHANGE_FAILED => "Token exchange failed",
+        #[cfg(feature = "full")]
+        ZEKE_UNEXPECTED_RESPONSE => "Unexpected response",
+        ZEKE_MEMORY_ERROR => "Memory error",
+        ZEKE_INVALID_PARAMETER => "Invalid parameter",
+        #[cfg(feature = "full")]
+        ZEKE_PROVIDER_UNAVAILABLE => "Provider unavailable",
+        #[cfg(feature = "full")]
+        ZEKE_STREAMING_FAILED => "Streaming failed",
+        #[cfg(feature = "full")]
+        ZEKE_RATE_LIMITED => "Rate limited",
     }
 }
 
 /// Get the last error message from Zeke
-/// 
+///
 /// # Safety
-/// 
+///
 /// This function is safe to call, but the returned string pointer
 /// may become invalid after subsequent Zeke API calls.
 pub fn get_last_error() -> Option<String> {
     unsafe {
-        let ptr = zeke_get_last_error();
+        let ptr = zeke_call!(zeke_get_last_error()).ok()?;
         c_ptr_to_string(ptr)
     }
 }
@@ -93,15 +347,18 @@ pub fn get_last_error() -> Option<String> {
 // Configuration helpers
 // ============================================================================
 
-/// Create a default Zeke configuration for testing
+/// Provider value used by [`default_config`] (OpenAI)
+pub const DEFAULT_PROVIDER: c_int = 2;
+
+/// Create a default Zeke configuration
 pub fn default_config() -> ZekeConfig {
     ZekeConfig {
         base_url: std::ptr::null(),
         api_key: std::ptr::null(),
-        provider: 0, // Default provider
+        provider: DEFAULT_PROVIDER,
         model_name: std::ptr::null(),
         temperature: 0.7,
-        max_tokens: 100,
+        max_tokens: 2048,
         stream: false,
         enable_gpu: false,
         enable_fallback: true,
@@ -109,41 +366,303 @@ pub fn default_config() -> ZekeConfig {
     }
 }
 
+/// Convert a provider enum to its integer representation
+#[cfg(feature = "full")]
+#[cfg_attr(docsrs, doc(cfg(feature = "full")))]
+pub fn provider_to_int(provider: ZekeProvider) -> c_int {
+    provider as c_int
+}
+
+/// Convert an integer to a provider enum
+#[cfg(feature = "full")]
+#[cfg_attr(docsrs, doc(cfg(feature = "full")))]
+pub fn int_to_provider(value: c_int) -> Option<ZekeProvider> {
+    match value {
+        x if x == ZEKE_PROVIDER_COPILOT as c_int => Some(ZEKE_PROVIDER_COPILOT),
+        x if x == ZEKE_PROVIDER_CLAUDE as c_int => Some(ZEKE_PROVIDER_CLAUDE),
+        x if x == ZEKE_PROVIDER_OPENAI as c_int => Some(ZEKE_PROVIDER_OPENAI),
+        x if x == ZEKE_PROVIDER_OLLAMA as c_int => Some(ZEKE_PROVIDER_OLLAMA),
+        x if x == ZEKE_PROVIDER_GHOSTLLM as c_int => Some(ZEKE_PROVIDER_GHOSTLLM),
+        x if x == ZEKE_PROVIDER_XAI as c_int => Some(ZEKE_PROVIDER_XAI),
+        x if x == ZEKE_PROVIDER_GOOGLE as c_int => Some(ZEKE_PROVIDER_GOOGLE),
+        x if x == ZEKE_PROVIDER_AZURE as c_int => Some(ZEKE_PROVIDER_AZURE),
+        x if x == ZEKE_PROVIDER_OMEN as c_int => Some(ZEKE_PROVIDER_OMEN),
+        _ => None,
+    }
+}
+
 // ============================================================================
-// Extern function declarations (safe wrappers)
+// Safe wrappers
 // ============================================================================
 
-// Note: The actual extern functions are included from the generated bindings
-// but we need to wrap them in unsafe blocks for Rust 2024
-
 /// Safe wrapper for zeke_init
 pub fn init(config: &ZekeConfig) -> Option<*mut ZekeHandle> {
     unsafe {
-        let handle = zeke_init(config as *const ZekeConfig);
+        let handle = zeke_call!(zeke_init(config as *const ZekeConfig)).ok()?;
         if handle.is_null() { None } else { Some(handle) }
     }
 }
 
 /// Safe wrapper for zeke_chat
+#[allow(clippy::not_unsafe_ptr_arg_deref)] // kept safe for the integration test; the handle comes from `init`
 pub fn chat(handle: *mut ZekeHandle, message: &str, response: &mut ZekeResponse) -> ZekeErrorCode {
     let c_message = std::ffi::CString::new(message).unwrap();
     unsafe {
-        zeke_chat(handle, c_message.as_ptr(), response as *mut ZekeResponse)
+        zeke_call!(zeke_chat(handle, c_message.as_ptr(), response as *mut ZekeResponse))
+            .unwrap_or(ZEKE_INITIALIZATION_FAILED)
     }
 }
 
 /// Safe wrapper for zeke_destroy
+#[allow(clippy::not_unsafe_ptr_arg_deref)] // kept safe for the integration test; the handle comes from `init`
 pub fn destroy(handle: *mut ZekeHandle) {
     unsafe {
-        zeke_destroy(handle);
+        let _ = zeke_call!(zeke_destroy(handle));
     }
 }
 
 /// Safe wrapper for zeke_version
 pub fn version() -> String {
     unsafe {
-        let ptr = zeke_version();
-        c_ptr_to_string(ptr).unwrap_or_else(|| "unknown".to_string())
+        zeke_call!(zeke_version())
+            .ok()
+            .and_then(|ptr| c_ptr_to_string(ptr))
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+// ============================================================================
+// ABI layout
+// ============================================================================
+
+#[cfg(feature = "full")]
+#[cfg_attr(docsrs, doc(cfg(feature = "full")))]
+pub mod abi {
+    //! Layouts of the public structures as these bindings declare them
+    //!
+    //! Compare with what the library reports through `zeke_struct_layout` to
+    //! catch a library built from a different header before any struct
+    //! crosses the boundary.
+
+    use super::*;
+    use std::mem::{align_of, offset_of, size_of};
+
+    /// A structure whose layout the library reports
+    #[derive(Debug, Clone, Copy)]
+    pub struct StructInfo {
+        /// Identifier passed to `zeke_struct_layout`
+        pub id: ZekeStructId,
+        /// C name of the structure
+        pub name: &'static str,
+        /// Field names in declaration order
+        pub fields: &'static [&'static str],
+        /// Layout these bindings were compiled with
+        pub layout: ZekeStructLayout,
+    }
+
+    macro_rules! struct_info {
+        ($id:ident, $ty:ident { $($field:ident),+ $(,)? }) => {{
+            let fields: &'static [&'static str] = &[$(stringify!($field)),+];
+            let mut layout = ZekeStructLayout {
+                size: size_of::<$ty>(),
+                alignment: align_of::<$ty>(),
+                field_count: fields.len(),
+                ..Default::default()
+            };
+            for (slot, offset) in layout.offsets.iter_mut().zip([$(offset_of!($ty, $field)),+]) {
+                *slot = offset;
+            }
+            StructInfo { id: $id, name: stringify!($ty), fields, layout }
+        }};
+    }
+
+    /// Every structure covered by the layout check
+    pub fn structs() -> [StructInfo; 6] {
+        [
+            struct_info!(ZEKE_STRUCT_CONFIG, ZekeConfig {
+                base_url, api_key, provider, model_name, temperature,
+                max_tokens, stream, enable_gpu, enable_fallback, timeout_ms,
+            }),
+            struct_info!(ZEKE_STRUCT_RESPONSE, ZekeResponse {
+                content, provider_used, tokens_used, response_time_ms,
+                error_code, error_message,
+            }),
+            struct_info!(ZEKE_STRUCT_STREAM_CHUNK, ZekeStreamChunk {
+                content, is_final, chunk_index, total_chunks,
+            }),
+            struct_info!(ZEKE_STRUCT_GPU_INFO, ZekeGpuInfo {
+                device_name, memory_used_mb, memory_total_mb,
+                utilization_percent, temperature_celsius, power_watts,
+            }),
+            struct_info!(ZEKE_STRUCT_PROVIDER_STATUS, ZekeProviderStatus {
+                provider, is_healthy, response_time_ms, error_rate,
+                requests_per_minute,
+            }),
+            struct_info!(ZEKE_STRUCT_ERROR_DETAIL, ZekeErrorDetail {
+                code, http_status, provider, retry_after_ms, model, message,
+            }),
+        ]
+    }
+
+    /// Layout of one structure as these bindings declare it
+    pub fn expected_layout(id: ZekeStructId) -> ZekeStructLayout {
+        structs()
+            .into_iter()
+            .find(|info| info.id == id)
+            .map(|info| info.layout)
+            .unwrap_or_default()
+    }
+}
+
+// ============================================================================
+// Feature-gated exports
+// ============================================================================
+
+#[cfg(feature = "ghostllm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ghostllm")))]
+pub mod ghostllm {
+    //! GhostLLM GPU acceleration features
+    //!
+    //! This module is only available when the `ghostllm` feature is enabled.
+    //! It is unsupported: the core has no GhostLLM client yet, so every call
+    //! fails with `ZEKE_PROVIDER_UNAVAILABLE`.
+
+    use super::*;
+
+    /// Initialize GhostLLM with default GPU settings
+    ///
+    /// # Safety
+    ///
+    /// The handle must be a valid Zeke instance.
+    pub unsafe fn init_default(handle: *mut ZekeHandle) -> ZekeErrorCode {
+        let url = std::ffi::CString::new("http://localhost:8080").unwrap();
+        unsafe {
+            zeke_call!(zeke_ghostllm_init(handle, url.as_ptr(), true))
+                .unwrap_or(ZEKE_PROVIDER_UNAVAILABLE)
+        }
+    }
+
+    /// Get GPU memory usage percentage
+    ///
+    /// # Safety
+    ///
+    /// The handle must be a valid Zeke instance with GhostLLM initialized.
+    pub unsafe fn get_gpu_memory_usage(handle: *mut ZekeHandle) -> Option<f32> {
+        unsafe {
+            let mut gpu_info = std::mem::zeroed::<ZekeGpuInfo>();
+            let result = zeke_call!(zeke_ghostllm_get_gpu_info(handle, &mut gpu_info)).ok()?;
+
+            if is_success(result) {
+                let usage = gpu_info.memory_used_mb as f32 / gpu_info.memory_total_mb as f32;
+                let _ = zeke_call!(zeke_free_gpu_info(&mut gpu_info));
+                Some(usage * 100.0)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+#[cfg(feature = "streaming")]
+#[cfg_attr(docsrs, doc(cfg(feature = "streaming")))]
+pub mod streaming {
+    //! Streaming response features
+    //!
+    //! This module is only available when the `streaming` feature is enabled.
+
+    use super::*;
+    use libc::c_void;
+    use std::any::Any;
+    use std::panic::{self, AssertUnwindSafe};
+
+    /// A safe wrapper around streaming callbacks
+    ///
+    /// A panic in the callback is caught before it can unwind into the
+    /// library; the stream is stopped and the panic is kept in
+    /// [`panic_message`](Self::panic_message).
+    pub struct StreamHandler<F>
+    where
+        F: FnMut(&str, bool, u32, u32),
+    {
+        callback: F,
+        panic_message: Option<String>,
+    }
+
+    impl<F> StreamHandler<F>
+    where
+        F: FnMut(&str, bool, u32, u32),
+    {
+        pub fn new(callback: F) -> Self {
+            Self {
+                callback,
+                panic_message: None,
+            }
+        }
+
+        /// Message of the panic that stopped the stream, if the callback panicked
+        pub fn panic_message(&self) -> Option<&str> {
+            self.panic_message.as_deref()
+        }
+
+        /// Get the raw C callback function
+        ///
+        /// # Safety
+        ///
+        /// This returns a function pointer that must only be used
+        /// with the associated StreamHandler instance as user_data.
+        pub unsafe fn get_c_callback() -> ZekeStreamCallback {
+            Some(Self::c_callback)
+        }
+
+        /// Internal C callback wrapper; returns false to stop the stream
+        unsafe extern "C" fn c_callback(
+            chunk: *const ZekeStreamChunk,
+            user_data: *mut c_void
+        ) -> bool {
+            if chunk.is_null() || user_data.is_null() {
+                return false;
+            }
+
+            unsafe {
+                let handler = &mut *(user_data as *mut Self);
+                if handler.panic_message.is_some() {
+                    return false;
+                }
+                let chunk_ref = &*chunk;
+
+                let Some(content) = c_ptr_to_str(chunk_ref.content) else {
+                    return true;
+                };
+                let callback = &mut handler.callback;
+                let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                    callback(
+                        content,
+                        chunk_ref.is_final,
+                        chunk_ref.chunk_index,
+                        chunk_ref.total_chunks,
+                    )
+                }));
+
+                match result {
+                    Ok(()) => true,
+                    Err(payload) => {
+                        handler.panic_message = Some(panic_payload_message(payload.as_ref()));
+                        false
+                    }
+                }
+            }
+        }
+    }
+
+    /// The message a panic was raised with
+    fn panic_payload_message(payload: &(dyn Any + Send)) -> String {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        }
     }
 }
 
@@ -154,38 +673,151 @@ pub fn version() -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[test]
     fn test_error_codes() {
-        assert!(is_success(ZekeErrorCode::ZEKE_SUCCESS));
-        assert!(!is_success(ZekeErrorCode::ZEKE_NETWORK_ERROR));
-        assert!(is_error(ZekeErrorCode::ZEKE_NETWORK_ERROR));
-        assert!(!is_error(ZekeErrorCode::ZEKE_SUCCESS));
+        assert!(is_success(ZEKE_SUCCESS));
+        assert!(!is_success(ZEKE_NETWORK_ERROR));
+        assert!(is_error(ZEKE_NETWORK_ERROR));
+        assert!(!is_error(ZEKE_SUCCESS));
+    }
+
+    #[cfg(feature = "full")]
+    #[test]
+    fn test_provider_conversion() {
+        assert_eq!(provider_to_int(ZEKE_PROVIDER_OPENAI), ZEKE_PROVIDER_OPENAI as i32);
+        assert_eq!(int_to_provider(ZEKE_PROVIDER_OPENAI as i32), Some(ZEKE_PROVIDER_OPENAI));
+        assert_eq!(int_to_provider(DEFAULT_PROVIDER), Some(ZEKE_PROVIDER_OPENAI));
+        assert_eq!(int_to_provider(-1), None);
     }
-    
+
+    #[test]
+    fn test_load_error_display() {
+        let err = LoadError::MissingSymbol { symbol: "zeke_ghostllm_init" };
+        assert_eq!(err.to_string(), "libzeke does not export zeke_ghostllm_init");
+
+        let err = LoadError::NotFound {
+            tried: vec!["libzeke_ffi.so".into(), "libzeke.so".into()],
+            message: "no such file".into(),
+        };
+        assert!(err.to_string().contains("libzeke_ffi.so, libzeke.so"));
+    }
+
+    #[cfg(feature = "dynamic")]
+    #[test]
+    fn test_dynamic_missing_library() {
+        // No libzeke is installed in the test environment
+        if dynamic::library().is_err() {
+            assert!(!zeke_has!(zeke_init));
+            assert!(matches!(
+                unsafe { zeke_call!(zeke_version()) },
+                Err(LoadError::NotFound { .. })
+            ));
+            assert_eq!(version(), "unknown");
+        }
+    }
+
+    #[cfg(feature = "streaming")]
+    #[test]
+    fn test_stream_handler_contains_panics() {
+        use streaming::StreamHandler;
+
+        fn c_callback_of<F: FnMut(&str, bool, u32, u32)>(_: &StreamHandler<F>) -> ZekeStreamCallback {
+            unsafe { StreamHandler::<F>::get_c_callback() }
+        }
+
+        let content = std::ffi::CString::new("chunk").unwrap();
+        let chunk = ZekeStreamChunk {
+            content: content.as_ptr(),
+            is_final: false,
+            chunk_index: 0,
+            total_chunks: 2,
+        };
+
+        let mut calls = 0;
+        let mut handler = StreamHandler::new(|text: &str, _, _, _| {
+            calls += 1;
+            panic!("bad chunk: {}", text);
+        });
+        let callback = c_callback_of(&handler).unwrap();
+        let user_data = &mut handler as *mut _ as *mut libc::c_void;
+
+        // The panic stops the stream instead of unwinding into the caller
+        assert!(!unsafe { callback(&chunk, user_data) });
+        assert!(!unsafe { callback(&chunk, user_data) });
+        assert_eq!(handler.panic_message(), Some("bad chunk: chunk"));
+        drop(handler);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_c_buf_to_str() {
+        let buf: [c_char; 8] = [b'g' as c_char, b'p' as c_char, b't' as c_char, 0, b'x' as c_char, 0, 0, 0];
+        assert_eq!(c_buf_to_str(&buf), Some("gpt"));
+        assert_eq!(c_buf_to_str(&[0; 4]), Some(""));
+        assert_eq!(c_buf_to_str(&[b'a' as c_char; 4]), None);
+    }
+
     #[test]
     fn test_error_messages() {
-        assert_eq!(error_to_string(ZekeErrorCode::ZEKE_SUCCESS), "Success");
-        assert_eq!(error_to_string(ZekeErrorCode::ZEKE_NETWORK_ERROR), "Network error");
+        assert_eq!(error_to_string(ZEKE_SUCCESS), "Success");
+        assert_eq!(error_to_string(ZEKE_NETWORK_ERROR), "Network error");
     }
-    
+
     #[test]
     fn test_default_config() {
         let config = default_config();
-        assert_eq!(config.provider, 0);
+        assert_eq!(config.provider, DEFAULT_PROVIDER);
         assert_eq!(config.temperature, 0.7);
-        assert_eq!(config.max_tokens, 100);
+        assert_eq!(config.max_tokens, 2048);
         assert!(!config.stream);
         assert!(!config.enable_gpu);
         assert!(config.enable_fallback);
     }
-    
-    #[test]  
+
+    #[test]
     fn test_struct_sizes() {
         // Ensure structs have expected sizes for ABI compatibility
         use std::mem::size_of;
-        
+
         assert!(size_of::<ZekeConfig>() > 0);
         assert!(size_of::<ZekeResponse>() > 0);
+        #[cfg(feature = "full")]
+        {
+            assert!(size_of::<ZekeStreamChunk>() > 0);
+            assert!(size_of::<ZekeGpuInfo>() > 0);
+            assert!(size_of::<ZekeProviderStatus>() > 0);
+        }
     }
-}
\ No newline at end of file
+
+    #[cfg(all(feature = "full", target_pointer_width = "64"))]
+    #[test]
+    fn test_struct_layouts() {
+        // C layouts on LP64 targets, matching zeke_ffi.h
+        let expected: [(ZekeStructId, usize, &[usize]); 6] = [
+            (ZEKE_STRUCT_CONFIG, 48, &[0, 8, 16, 24, 32, 36, 40, 41, 42, 44]),
+            (ZEKE_STRUCT_RESPONSE, 32, &[0, 8, 12, 16, 20, 24]),
+            (ZEKE_STRUCT_STREAM_CHUNK, 24, &[0, 8, 12, 16]),
+            (ZEKE_STRUCT_GPU_INFO, 32, &[0, 8, 16, 24, 25, 28]),
+            (ZEKE_STRUCT_PROVIDER_STATUS, 20, &[0, 4, 8, 12, 16]),
+            (ZEKE_STRUCT_ERROR_DETAIL, 656, &[0, 4, 8, 12, 16, 144]),
+        ];
+
+        for (id, size, offsets) in expected {
+            let layout = abi::expected_layout(id);
+            assert_eq!(layout.size, size, "{:?}", id);
+            assert_eq!(layout.field_count, offsets.len(), "{:?}", id);
+            assert_eq!(&layout.offsets[..offsets.len()], offsets, "{:?}", id);
+            assert!(layout.offsets[offsets.len()..].iter().all(|&o| o == 0));
+        }
+    }
+
+    #[cfg(feature = "full")]
+    #[test]
+    fn test_struct_info_matches_fields() {
+        for info in abi::structs() {
+            assert_eq!(info.fields.len(), info.layout.field_count, "{}", info.name);
+            assert!(info.fields.len() <= ZEKE_MAX_STRUCT_FIELDS as usize);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Benchmarks for reading response text out of the Zig core
+//!
+//! A real `zeke_chat` needs a provider, so these run each API's own code on
+//! C buffers like the ones the core hands back, through the hooks in
+//! `zeke::bench`:
+//!
+//! - `chat_response`: [`Zeke::chat`](zeke::Zeke::chat) copies the buffer into
+//!   a `ChatResponse` ([`RawResponse::to_response`]),
+//!   [`Zeke::chat_raw`](zeke::Zeke::chat_raw) only checks it
+//!   ([`RawResponse::as_str`]).
+//! - `stream`: the chunks are fed to the stream callbacks the way
+//!   `zeke_chat_stream` does. `chat_stream_callback` allocates a `String` per
+//!   chunk that the caller then joins, `chat_stream_arena` appends each chunk
+//!   to a [`ChunkArena`].
+//!
+//! Run with `cargo bench -p zeke --bench response --features bench`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::ffi::CString;
+use zeke::bench::{collect_arena, collect_strings, with_core_buffer};
+use zeke::{ChunkArena, RawResponse};
+
+const SIZES: [usize; 3] = [4 * 1024, 64 * 1024, 512 * 1024];
+
+/// Roughly the size of a streamed token
+const CHUNK_SIZE: usize = 16;
+
+/// Generated Rust source, the kind of output these APIs are for
+fn code_output(size: usize) -> String {
+    let line = "    let value = compute(input, \"résumé\")?; // step\n";
+    line.chars().cycle().take(size).collect()
+}
+
+fn chunked(text: &str) -> Vec<CString> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut end = CHUNK_SIZE.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end += 1;
+        }
+        chunks.push(CString::new(&rest[..end]).unwrap());
+        rest = &rest[end..];
+    }
+    chunks
+}
+
+fn bench_chat_response(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chat_response");
+
+    for size in SIZES {
+        let buffer = CString::new(code_output(size)).unwrap();
+        group.throughput(Throughput::Bytes(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("copy", size), &buffer, |b, buffer| {
+            b.iter(|| with_core_buffer(buffer, |raw: &RawResponse| black_box(raw.to_response().unwrap())))
+        });
+
+        group.bench_with_input(BenchmarkId::new("raw", size), &buffer, |b, buffer| {
+            b.iter(|| with_core_buffer(buffer, |raw: &RawResponse| black_box(raw.as_str().unwrap().len())))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_stream(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stream");
+
+    for size in SIZES {
+        let chunks = chunked(&code_output(size));
+        group.throughput(Throughput::Bytes(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("string_per_chunk", size), &chunks, |b, chunks| {
+            b.iter(|| {
+                let received = collect_strings(chunks).unwrap();
+                black_box(received.concat())
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("arena", size), &chunks, |b, chunks| {
+            b.iter(|| {
+                let mut arena = ChunkArena::new();
+                collect_arena(&mut arena, chunks).unwrap();
+                black_box(arena.into_string())
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("arena_reused", size), &chunks, |b, chunks| {
+            let mut arena = ChunkArena::with_capacity(size);
+            b.iter(|| {
+                arena.clear();
+                collect_arena(&mut arena, chunks).unwrap();
+                black_box(arena.len())
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_chat_response, bench_stream);
+criterion_main!(benches);
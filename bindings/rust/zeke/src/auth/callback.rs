@@ -0,0 +1,179 @@
+//! Loopback server receiving the authorization redirect
+
+use crate::{Error, Result};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::debug;
+
+/// Path the redirect is expected on
+const CALLBACK_PATH: &str = "/callback";
+
+/// Largest request head read from the browser
+const MAX_REQUEST: usize = 16 * 1024;
+
+const SUCCESS_PAGE: &str = "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Zeke</title></head>\
+<body><h1>Signed in to Zeke</h1><p>You can close this window.</p></body></html>";
+
+/// One-shot HTTP server on `127.0.0.1` that captures the authorization code
+#[derive(Debug)]
+pub struct CallbackServer {
+    listener: TcpListener,
+    port: u16,
+}
+
+impl CallbackServer {
+    /// Listen on a free loopback port
+    pub async fn bind() -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let port = listener.local_addr()?.port();
+        Ok(Self { listener, port })
+    }
+
+    /// Port the server listens on
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Redirect URI to register with the authorization request
+    pub fn redirect_uri(&self) -> String {
+        format!("http://127.0.0.1:{}{}", self.port, CALLBACK_PATH)
+    }
+
+    /// Wait for the redirect and return its authorization code
+    ///
+    /// Requests for other paths (such as `/favicon.ico`) are answered with 404
+    /// and ignored. A redirect carrying a different `state` than the one
+    /// sent, or an `error` parameter, fails the flow.
+    pub async fn wait_for_code(&self, state: &str, timeout: Duration) -> Result<String> {
+        tokio::time::timeout(timeout, self.accept_code(state))
+            .await
+            .map_err(|_| Error::authentication("oauth", "timed out waiting for the authorization redirect"))?
+    }
+
+    async fn accept_code(&self, state: &str) -> Result<String> {
+        loop {
+            let (mut stream, peer) = self.listener.accept().await?;
+            debug!("OAuth callback connection from {}", peer);
+
+            let Some(target) = read_request_target(&mut stream).await? else {
+                continue;
+            };
+            let Ok(url) = url::Url::parse(&format!("http://127.0.0.1{}", target)) else {
+                respond(&mut stream, "400 Bad Request", "Bad request").await;
+                continue;
+            };
+            if url.path() != CALLBACK_PATH {
+                respond(&mut stream, "404 Not Found", "Not found").await;
+                continue;
+            }
+
+            let param = |name: &str| {
+                url.query_pairs()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.into_owned())
+            };
+
+            let result = if let Some(error) = param("error") {
+                let message = match param("error_description") {
+                    Some(description) => format!("{}: {}", error, description),
+                    None => error,
+                };
+                Err(Error::authentication("oauth", message.as_str()))
+            } else if param("state").as_deref() != Some(state) {
+                Err(Error::authentication("oauth", "authorization redirect has the wrong state"))
+            } else {
+                param("code").ok_or_else(|| {
+                    Error::authentication("oauth", "authorization redirect has no code")
+                })
+            };
+
+            match &result {
+                Ok(_) => respond(&mut stream, "200 OK", SUCCESS_PAGE).await,
+                Err(e) => respond(&mut stream, "400 Bad Request", &format!("Sign-in failed: {}", e)).await,
+            }
+            return result;
+        }
+    }
+}
+
+/// Read the request head and return the request target of a GET
+async fn read_request_target(stream: &mut TcpStream) -> Result<Option<String>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 || buffer.len() + read > MAX_REQUEST {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    let head = String::from_utf8_lossy(&buffer);
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => Ok(Some(target.to_string())),
+        _ => {
+            respond(stream, "405 Method Not Allowed", "Method not allowed").await;
+            Ok(None)
+        }
+    }
+}
+
+/// Best-effort response; the browser may already have gone away
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get(url: &str) -> u16 {
+        reqwest::get(url).await.unwrap().status().as_u16()
+    }
+
+    #[tokio::test]
+    async fn test_callback_returns_code() {
+        let server = CallbackServer::bind().await.unwrap();
+        let uri = server.redirect_uri();
+
+        let browser = tokio::spawn(async move {
+            let favicon = uri.replace(CALLBACK_PATH, "/favicon.ico");
+            assert_eq!(get(&favicon).await, 404);
+            get(&format!("{}?code=abc%2B1&state=xyz", uri)).await
+        });
+
+        let code = server.wait_for_code("xyz", Duration::from_secs(10)).await.unwrap();
+        assert_eq!(code, "abc+1");
+        assert_eq!(browser.await.unwrap(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_callback_rejects_wrong_state_and_errors() {
+        let server = CallbackServer::bind().await.unwrap();
+        let uri = server.redirect_uri();
+        let browser = tokio::spawn(async move { get(&format!("{}?code=abc&state=forged", uri)).await });
+        assert!(server.wait_for_code("xyz", Duration::from_secs(10)).await.is_err());
+        assert_eq!(browser.await.unwrap(), 400);
+
+        let uri = server.redirect_uri();
+        tokio::spawn(async move { get(&format!("{}?error=access_denied&state=xyz", uri)).await });
+        let err = server.wait_for_code("xyz", Duration::from_secs(10)).await.unwrap_err();
+        assert!(err.to_string().contains("access_denied"));
+    }
+
+    #[tokio::test]
+    async fn test_callback_timeout() {
+        let server = CallbackServer::bind().await.unwrap();
+        let err = server.wait_for_code("xyz", Duration::from_millis(50)).await.unwrap_err();
+        assert!(err.is_auth_error());
+    }
+}
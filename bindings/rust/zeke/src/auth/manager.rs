@@ -0,0 +1,319 @@
+//! Keeping OAuth access tokens fresh
+
+use super::{OAuthClient, OAuthTokens};
+use crate::credentials::CredentialStore;
+use crate::{Error, Result};
+use secrecy::{ExposeSecret, Secret};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{broadcast, Mutex};
+use tracing::{debug, info, warn};
+
+/// How long before expiry tokens are refreshed by default
+pub const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Something that happened to the tokens a [`TokenManager`] holds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenEvent {
+    /// A new access token was obtained
+    Refreshed {
+        /// Credential name of the provider
+        provider: String,
+        /// When the new token expires, if known
+        expires_at: Option<SystemTime>,
+    },
+    /// Refreshing failed for a reason that may go away, such as the network
+    RefreshFailed {
+        /// Credential name of the provider
+        provider: String,
+        /// Error message
+        message: String,
+    },
+    /// The user has to sign in again
+    ReloginRequired {
+        /// Credential name of the provider
+        provider: String,
+        /// Why the tokens can no longer be used
+        reason: String,
+    },
+}
+
+/// Holds OAuth tokens and refreshes them before, or when, they expire
+///
+/// Refreshes are serialized, so concurrent callers share a single refresh.
+/// Refreshed tokens are saved to the credential store, if one is set.
+pub struct TokenManager {
+    client: OAuthClient,
+    store: Option<Arc<dyn CredentialStore>>,
+    tokens: Mutex<OAuthTokens>,
+    margin: Duration,
+    events: broadcast::Sender<TokenEvent>,
+}
+
+impl fmt::Debug for TokenManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenManager")
+            .field("provider", &self.client.provider().name)
+            .field("store", &self.store.as_ref().map(|s| s.name().to_string()))
+            .field("margin", &self.margin)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TokenManager {
+    /// Manage tokens obtained from a sign-in flow
+    pub fn new(client: OAuthClient, tokens: OAuthTokens) -> Self {
+        let (events, _) = broadcast::channel(16);
+        Self {
+            client,
+            store: None,
+            tokens: Mutex::new(tokens),
+            margin: DEFAULT_REFRESH_MARGIN,
+            events,
+        }
+    }
+
+    /// Manage tokens saved in a credential store under the provider's name
+    ///
+    /// Returns `None` if no tokens are saved, meaning the user has to sign in.
+    pub fn from_store(client: OAuthClient, store: Arc<dyn CredentialStore>) -> Result<Option<Self>> {
+        let Some(tokens) = OAuthTokens::load(store.as_ref(), &client.provider().name)? else {
+            return Ok(None);
+        };
+        Ok(Some(Self::new(client, tokens).with_store(store)))
+    }
+
+    /// Save refreshed tokens to a credential store
+    pub fn with_store(mut self, store: Arc<dyn CredentialStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Refresh tokens this long before they expire
+    pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Receive [`TokenEvent`]s from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<TokenEvent> {
+        self.events.subscribe()
+    }
+
+    /// Replace the tokens, e.g. after the user signed in again
+    pub async fn set_tokens(&self, tokens: OAuthTokens) -> Result<()> {
+        self.save(&tokens)?;
+        *self.tokens.lock().await = tokens;
+        Ok(())
+    }
+
+    /// When the current access token expires, if known
+    pub async fn expires_at(&self) -> Option<SystemTime> {
+        self.tokens.lock().await.expires_at
+    }
+
+    /// A usable access token, refreshed first if it expires within the margin
+    pub async fn access_token(&self) -> Result<Secret<String>> {
+        let mut tokens = self.tokens.lock().await;
+        if tokens.expires_within(self.margin) {
+            debug!("{} access token is about to expire", self.client.provider().name);
+            self.refresh_locked(&mut tokens).await?;
+        }
+        Ok(tokens.access_token.clone())
+    }
+
+    /// Refresh now, regardless of expiry
+    pub async fn refresh(&self) -> Result<Secret<String>> {
+        let mut tokens = self.tokens.lock().await;
+        self.refresh_locked(&mut tokens).await?;
+        Ok(tokens.access_token.clone())
+    }
+
+    /// Run `operation` with an access token, refreshing and retrying once on auth errors
+    ///
+    /// An error for which [`Error::is_auth_error`] is true triggers one refresh
+    /// and a second attempt; any other error is returned as is.
+    pub async fn call<T, F, Fut>(&self, mut operation: F) -> Result<T>
+    where
+        F: FnMut(Secret<String>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let token = self.access_token().await?;
+        match operation(token.clone()).await {
+            Err(e) if e.is_auth_error() => {
+                warn!(
+                    "{} rejected the access token ({}); refreshing and retrying",
+                    self.client.provider().name,
+                    e.category()
+                );
+                let token = self.refresh_rejected(&token).await?;
+                operation(token).await
+            }
+            result => result,
+        }
+    }
+
+    /// Refresh after `rejected` failed, unless another caller already did
+    async fn refresh_rejected(&self, rejected: &Secret<String>) -> Result<Secret<String>> {
+        let mut tokens = self.tokens.lock().await;
+        if tokens.access_token.expose_secret() == rejected.expose_secret() {
+            self.refresh_locked(&mut tokens).await?;
+        }
+        Ok(tokens.access_token.clone())
+    }
+
+    async fn refresh_locked(&self, tokens: &mut OAuthTokens) -> Result<()> {
+        let provider = self.client.provider().name.clone();
+        let Some(refresh_token) = tokens.refresh_token.clone() else {
+            return Err(self.relogin(provider, "no refresh token"));
+        };
+
+        match self.client.refresh(&refresh_token).await {
+            Ok(refreshed) => {
+                self.save(&refreshed)?;
+                info!("Refreshed {} access token", provider);
+                let _ = self.events.send(TokenEvent::Refreshed {
+                    provider,
+                    expires_at: refreshed.expires_at,
+                });
+                *tokens = refreshed;
+                Ok(())
+            }
+            // The token endpoint refused the refresh token itself
+            Err(e @ (Error::TokenExchangeFailed { .. } | Error::AuthenticationFailed { .. })) => {
+                Err(self.relogin(provider, &e.to_string()))
+            }
+            Err(e) => {
+                warn!("Failed to refresh {} access token: {}", provider, e);
+                let _ = self.events.send(TokenEvent::RefreshFailed {
+                    provider,
+                    message: e.to_string(),
+                });
+                Err(e)
+            }
+        }
+    }
+
+    fn relogin(&self, provider: String, reason: &str) -> Error {
+        warn!("{} sign-in required: {}", provider, reason);
+        let _ = self.events.send(TokenEvent::ReloginRequired {
+            provider: provider.clone(),
+            reason: reason.to_string(),
+        });
+        Error::authentication(provider, format!("sign in again: {}", reason))
+    }
+
+    fn save(&self, tokens: &OAuthTokens) -> Result<()> {
+        match &self.store {
+            Some(store) => tokens.save(store.as_ref(), &self.client.provider().name),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::tests::{stub_provider, stub_server, MemoryStore};
+    use crate::auth::TokenFormat;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn tokens(access: &str, refresh: Option<&str>, expires_in: Option<u64>) -> OAuthTokens {
+        OAuthTokens {
+            access_token: Secret::new(access.to_string()),
+            refresh_token: refresh.map(|r| Secret::new(r.to_string())),
+            token_type: "Bearer".to_string(),
+            expires_at: expires_in.map(|secs| SystemTime::now() + Duration::from_secs(secs)),
+            scope: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refreshes_before_expiry_and_saves() {
+        let (url, bodies) = stub_server(vec![(
+            200,
+            r#"{"access_token": "fresh-access", "refresh_token": "fresh-refresh", "expires_in": 3600}"#,
+        )])
+        .await;
+        let client = OAuthClient::new(stub_provider(&url, TokenFormat::Form)).unwrap();
+        let store = Arc::new(MemoryStore::default());
+        let manager = TokenManager::new(client, tokens("old-access", Some("old-refresh"), Some(30)))
+            .with_store(store.clone());
+        let mut events = manager.subscribe();
+
+        let token = manager.access_token().await.unwrap();
+        assert_eq!(token.expose_secret(), "fresh-access");
+        assert!(bodies.lock().unwrap()[0].contains("refresh_token=old-refresh"));
+        assert!(matches!(events.try_recv().unwrap(), TokenEvent::Refreshed { .. }));
+
+        // Saved tokens are picked up by a new manager
+        let client = OAuthClient::new(stub_provider(&url, TokenFormat::Form)).unwrap();
+        let reloaded = TokenManager::from_store(client, store).unwrap().unwrap();
+        assert_eq!(reloaded.access_token().await.unwrap().expose_secret(), "fresh-access");
+    }
+
+    #[tokio::test]
+    async fn test_call_retries_once_after_auth_error() {
+        let (url, _) = stub_server(vec![(200, r#"{"access_token": "fresh-access", "expires_in": 3600}"#)]).await;
+        let client = OAuthClient::new(stub_provider(&url, TokenFormat::Form)).unwrap();
+        let manager = TokenManager::new(client, tokens("stale-access", Some("refresh"), None));
+
+        let attempts = AtomicUsize::new(0);
+        let result = manager
+            .call(|token| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if token.expose_secret() == "stale-access" {
+                        Err(Error::authentication("stub", "token expired"))
+                    } else {
+                        Ok(token.expose_secret().len())
+                    }
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), "fresh-access".len());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        // Other errors are not retried
+        let attempts = AtomicUsize::new(0);
+        let result: Result<()> = manager
+            .call(|_| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err(Error::network("down")) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_relogin_required_when_refresh_is_rejected() {
+        let (url, _) = stub_server(vec![(400, r#"{"error": "invalid_grant"}"#)]).await;
+        let client = OAuthClient::new(stub_provider(&url, TokenFormat::Form)).unwrap();
+        let manager = TokenManager::new(client, tokens("access", Some("revoked"), Some(0)));
+        let mut events = manager.subscribe();
+
+        let err = manager.access_token().await.unwrap_err();
+        assert!(err.is_auth_error());
+        assert!(matches!(events.try_recv().unwrap(), TokenEvent::ReloginRequired { .. }));
+
+        let client = OAuthClient::new(stub_provider(&url, TokenFormat::Form)).unwrap();
+        let manager = TokenManager::new(client, tokens("access", None, Some(0)));
+        assert!(manager.access_token().await.unwrap_err().is_auth_error());
+    }
+
+    #[tokio::test]
+    async fn test_tokens_stay_out_of_debug_output() {
+        let client = OAuthClient::new(stub_provider("http://127.0.0.1:9", TokenFormat::Form)).unwrap();
+        let held = tokens("sk-access-secret", Some("sk-refresh-secret"), Some(3600));
+        let debug = format!("{:?}", held);
+        let manager = TokenManager::new(client, held);
+
+        for output in [debug, format!("{:?}", manager)] {
+            assert!(!output.contains("sk-access-secret"), "{}", output);
+            assert!(!output.contains("sk-refresh-secret"), "{}", output);
+        }
+    }
+}
@@ -0,0 +1,785 @@
+//! OAuth 2.0 sign-in
+//!
+//! Runs the authorization-code flow with PKCE, receiving the redirect on a
+//! loopback [`CallbackServer`], and the device-code flow used by GitHub
+//! Copilot. Tokens are saved to a [`CredentialStore`] under the same names
+//! the Zig CLI uses, so either side can reuse a sign-in. A [`TokenManager`]
+//! refreshes them before they expire.
+//!
+//! ```rust,no_run
+//! use zeke::auth::{OAuthClient, OAuthProvider};
+//! use zeke::SecretServiceStore;
+//!
+//! # async fn example() -> zeke::Result<()> {
+//! let client = OAuthClient::new(OAuthProvider::github())?;
+//! let tokens = client
+//!     .login_with_device_code(|device| {
+//!         println!("Visit {} and enter {}", device.verification_uri, device.user_code);
+//!     })
+//!     .await?;
+//! tokens.save(&SecretServiceStore::new(), &client.provider().name)?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod callback;
+mod manager;
+mod pkce;
+
+pub use callback::CallbackServer;
+pub use manager::{TokenEvent, TokenManager, DEFAULT_REFRESH_MARGIN};
+pub use pkce::Pkce;
+
+use crate::backend::http::transport_error;
+use crate::credentials::CredentialStore;
+use crate::{Error, Provider, Result};
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info};
+use url::Url;
+
+/// How long [`OAuthClient::login_with_pkce`] waits for the browser by default
+pub const DEFAULT_CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Encoding of token endpoint requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenFormat {
+    /// `application/x-www-form-urlencoded`, as in RFC 6749
+    Form,
+    /// A JSON object with the same parameters
+    Json,
+}
+
+/// OAuth client settings and endpoints for one provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthProvider {
+    /// Credential name the tokens are saved under
+    pub name: String,
+    /// Public client identifier
+    pub client_id: String,
+    /// Authorization endpoint for the PKCE flow
+    pub authorize_url: Option<String>,
+    /// Token endpoint
+    pub token_url: String,
+    /// Device authorization endpoint for the device-code flow
+    pub device_code_url: Option<String>,
+    /// Requested scopes
+    pub scopes: Vec<String>,
+    /// Fixed redirect URI; `None` uses the loopback callback server
+    pub redirect_uri: Option<String>,
+    /// Extra query parameters for the authorization request
+    pub authorize_params: Vec<(String, String)>,
+    /// Encoding of token requests
+    pub token_format: TokenFormat,
+}
+
+impl OAuthProvider {
+    /// Anthropic console sign-in
+    ///
+    /// Anthropic only accepts its own hosted redirect page, which shows a
+    /// `code#state` value to paste back; see [`OAuthClient::exchange_pasted_code`].
+    pub fn anthropic() -> Self {
+        Self {
+            name: Provider::Claude.credential_name().to_string(),
+            client_id: "9d1c250a-e61b-44d9-88ed-5944d1962f5e".to_string(),
+            authorize_url: Some("https://console.anthropic.com/oauth/authorize".to_string()),
+            token_url: "https://console.anthropic.com/v1/oauth/token".to_string(),
+            device_code_url: None,
+            scopes: vec![
+                "org:create_api_key".to_string(),
+                "user:profile".to_string(),
+                "user:inference".to_string(),
+            ],
+            redirect_uri: Some("https://console.anthropic.com/oauth/code/callback".to_string()),
+            authorize_params: vec![("code".to_string(), "true".to_string())],
+            token_format: TokenFormat::Json,
+        }
+    }
+
+    /// GitHub sign-in for Copilot, using the device-code flow
+    pub fn github() -> Self {
+        Self {
+            name: Provider::Copilot.credential_name().to_string(),
+            client_id: "Iv1.b507a08c87ecfe98".to_string(),
+            authorize_url: Some("https://github.com/login/oauth/authorize".to_string()),
+            token_url: "https://github.com/login/oauth/access_token".to_string(),
+            device_code_url: Some("https://github.com/login/device/code".to_string()),
+            scopes: vec!["read:user".to_string()],
+            redirect_uri: None,
+            authorize_params: Vec::new(),
+            token_format: TokenFormat::Form,
+        }
+    }
+
+    /// Settings for a provider that signs in with OAuth
+    pub fn for_provider(provider: Provider) -> Option<Self> {
+        match provider {
+            Provider::Claude => Some(Self::anthropic()),
+            Provider::Copilot => Some(Self::github()),
+            _ => None,
+        }
+    }
+}
+
+/// Tokens returned by a token endpoint
+#[derive(Debug, Clone)]
+pub struct OAuthTokens {
+    /// Bearer token for API requests
+    pub access_token: Secret<String>,
+    /// Token used to obtain a new access token
+    pub refresh_token: Option<Secret<String>>,
+    /// Token type, usually `Bearer`
+    pub token_type: String,
+    /// When the access token stops working, if the server said
+    pub expires_at: Option<SystemTime>,
+    /// Granted scopes
+    pub scope: Option<String>,
+}
+
+impl OAuthTokens {
+    /// Check whether the access token has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_within(Duration::ZERO)
+    }
+
+    /// Check whether the access token expires within `margin`
+    pub fn expires_within(&self, margin: Duration) -> bool {
+        self.expires_at
+            .is_some_and(|at| at <= SystemTime::now() + margin)
+    }
+
+    /// Save the tokens under `name`, `<name>_refresh` and `<name>_expires`
+    pub fn save(&self, store: &dyn CredentialStore, name: &str) -> Result<()> {
+        store.set(name, self.access_token.expose_secret())?;
+        match &self.refresh_token {
+            Some(token) => store.set(&refresh_name(name), token.expose_secret())?,
+            None => store.delete(&refresh_name(name))?,
+        }
+        match self.expires_at.and_then(|at| at.duration_since(UNIX_EPOCH).ok()) {
+            Some(at) => store.set(&expires_name(name), &at.as_secs().to_string())?,
+            None => store.delete(&expires_name(name))?,
+        }
+        Ok(())
+    }
+
+    /// Load tokens saved under `name`
+    pub fn load(store: &dyn CredentialStore, name: &str) -> Result<Option<Self>> {
+        let Some(access_token) = store.get(name)? else {
+            return Ok(None);
+        };
+        let expires_at = store
+            .get(&expires_name(name))?
+            .and_then(|secs| secs.expose_secret().trim().parse::<u64>().ok())
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+
+        Ok(Some(Self {
+            access_token,
+            refresh_token: store.get(&refresh_name(name))?,
+            token_type: "Bearer".to_string(),
+            expires_at,
+            scope: None,
+        }))
+    }
+
+    /// Remove tokens saved under `name`
+    pub fn delete(store: &dyn CredentialStore, name: &str) -> Result<()> {
+        store.delete(name)?;
+        store.delete(&refresh_name(name))?;
+        store.delete(&expires_name(name))
+    }
+}
+
+fn refresh_name(name: &str) -> String {
+    format!("{}_refresh", name)
+}
+
+fn expires_name(name: &str) -> String {
+    format!("{}_expires", name)
+}
+
+/// A prepared authorization-code request
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+    /// URL to open in the user's browser
+    pub url: Url,
+    /// Redirect URI included in the request
+    pub redirect_uri: String,
+    /// Anti-forgery value the redirect must echo
+    pub state: String,
+    pkce: Pkce,
+}
+
+/// Codes returned by a device authorization endpoint
+#[derive(Debug, Clone)]
+pub struct DeviceAuthorization {
+    /// Code the user enters at the verification page
+    pub user_code: String,
+    /// Page where the user enters the code
+    pub verification_uri: String,
+    /// How long the codes stay valid
+    pub expires_in: Duration,
+    /// Minimum time between polls
+    pub interval: Duration,
+    device_code: Secret<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    expires_in: u64,
+    #[serde(default)]
+    interval: Option<u64>,
+}
+
+/// Token endpoint response, successful or not
+#[derive(Debug, Default, Deserialize)]
+struct TokenResponse {
+    #[serde(default)]
+    access_token: Option<String>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    token_type: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    scope: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+impl TokenResponse {
+    fn error_message(&self) -> Option<String> {
+        let error = self.error.as_deref()?;
+        Some(match &self.error_description {
+            Some(description) => format!("{}: {}", error, description),
+            None => error.to_string(),
+        })
+    }
+
+    fn into_tokens(self) -> Result<OAuthTokens> {
+        if let Some(message) = self.error_message() {
+            return Err(Error::TokenExchangeFailed { message });
+        }
+        let access_token = self.access_token.ok_or_else(|| Error::TokenExchangeFailed {
+            message: "token response has no access_token".to_string(),
+        })?;
+
+        Ok(OAuthTokens {
+            access_token: Secret::new(access_token),
+            refresh_token: self.refresh_token.map(Secret::new),
+            token_type: self.token_type.unwrap_or_else(|| "Bearer".to_string()),
+            expires_at: self
+                .expires_in
+                .map(|secs| SystemTime::now() + Duration::from_secs(secs)),
+            scope: self.scope,
+        })
+    }
+}
+
+/// Runs OAuth flows against one provider
+#[derive(Debug, Clone)]
+pub struct OAuthClient {
+    provider: OAuthProvider,
+    http: reqwest::Client,
+    callback_timeout: Duration,
+}
+
+impl OAuthClient {
+    /// Create a client for a provider's endpoints
+    pub fn new(provider: OAuthProvider) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(transport_error)?;
+
+        Ok(Self {
+            provider,
+            http,
+            callback_timeout: DEFAULT_CALLBACK_TIMEOUT,
+        })
+    }
+
+    /// Set how long to wait for the browser redirect
+    pub fn with_callback_timeout(mut self, timeout: Duration) -> Self {
+        self.callback_timeout = timeout;
+        self
+    }
+
+    /// The provider settings
+    pub fn provider(&self) -> &OAuthProvider {
+        &self.provider
+    }
+
+    /// Build an authorization-code request with a fresh PKCE verifier and state
+    pub fn authorization_request(&self, redirect_uri: &str) -> Result<AuthorizationRequest> {
+        let authorize_url = self.provider.authorize_url.as_deref().ok_or_else(|| {
+            Error::ConfigError {
+                message: format!("OAuth provider '{}' has no authorization endpoint", self.provider.name),
+            }
+        })?;
+        let mut url = Url::parse(authorize_url)?;
+        let pkce = Pkce::new()?;
+        let state = pkce::random_token(16)?;
+
+        {
+            let mut query = url.query_pairs_mut();
+            for (key, value) in &self.provider.authorize_params {
+                query.append_pair(key, value);
+            }
+            query
+                .append_pair("response_type", "code")
+                .append_pair("client_id", &self.provider.client_id)
+                .append_pair("redirect_uri", redirect_uri)
+                .append_pair("scope", &self.provider.scopes.join(" "))
+                .append_pair("code_challenge", pkce.challenge())
+                .append_pair("code_challenge_method", pkce.method())
+                .append_pair("state", &state);
+        }
+
+        Ok(AuthorizationRequest {
+            url,
+            redirect_uri: redirect_uri.to_string(),
+            state,
+            pkce,
+        })
+    }
+
+    /// Sign in through the browser with the authorization-code flow and PKCE
+    ///
+    /// `open` receives the authorization URL; pass [`open_browser`] or show
+    /// the URL to the user. The redirect is received on a loopback server.
+    pub async fn login_with_pkce<F>(&self, open: F) -> Result<OAuthTokens>
+    where
+        F: FnOnce(&Url),
+    {
+        if let Some(redirect_uri) = &self.provider.redirect_uri {
+            return Err(Error::ConfigError {
+                message: format!(
+                    "OAuth provider '{}' redirects to {}; use exchange_pasted_code instead",
+                    self.provider.name, redirect_uri
+                ),
+            });
+        }
+
+        let server = CallbackServer::bind().await?;
+        let request = self.authorization_request(&server.redirect_uri())?;
+        info!("Waiting for {} sign-in on {}", self.provider.name, request.redirect_uri);
+        open(&request.url);
+
+        let code = server.wait_for_code(&request.state, self.callback_timeout).await?;
+        self.exchange_code(&request, &code).await
+    }
+
+    /// Exchange an authorization code for tokens
+    pub async fn exchange_code(&self, request: &AuthorizationRequest, code: &str) -> Result<OAuthTokens> {
+        debug!("Exchanging {} authorization code", self.provider.name);
+        let mut params = vec![
+            ("grant_type", "authorization_code".to_string()),
+            ("code", code.to_string()),
+            ("redirect_uri", request.redirect_uri.clone()),
+            ("client_id", self.provider.client_id.clone()),
+            ("code_verifier", request.pkce.verifier().to_string()),
+        ];
+        if self.provider.token_format == TokenFormat::Json {
+            params.push(("state", request.state.clone()));
+        }
+        self.token_request(&params).await?.into_tokens()
+    }
+
+    /// Exchange a `code#state` value copied from a hosted redirect page
+    pub async fn exchange_pasted_code(&self, request: &AuthorizationRequest, pasted: &str) -> Result<OAuthTokens> {
+        let pasted = pasted.trim();
+        let (code, state) = pasted.split_once('#').unwrap_or((pasted, request.state.as_str()));
+        if state != request.state {
+            return Err(Error::authentication(
+                self.provider.name.as_str(),
+                "pasted code belongs to a different sign-in",
+            ));
+        }
+        self.exchange_code(request, code).await
+    }
+
+    /// Start the device-code flow
+    pub async fn request_device_code(&self) -> Result<DeviceAuthorization> {
+        let url = self.provider.device_code_url.as_deref().ok_or_else(|| Error::ConfigError {
+            message: format!("OAuth provider '{}' has no device authorization endpoint", self.provider.name),
+        })?;
+
+        let response = self
+            .http
+            .post(url)
+            .header("Accept", "application/json")
+            .form(&[
+                ("client_id", self.provider.client_id.as_str()),
+                ("scope", &self.provider.scopes.join(" ")),
+            ])
+            .send()
+            .await
+            .map_err(transport_error)?;
+        let status = response.status();
+        let body = response.text().await.map_err(transport_error)?;
+        if !status.is_success() {
+            return Err(Error::authentication(
+                self.provider.name.clone(),
+                format!("device code request failed with HTTP {}", status),
+            ));
+        }
+
+        let device: DeviceCodeResponse = serde_json::from_str(&body)?;
+        Ok(DeviceAuthorization {
+            user_code: device.user_code,
+            verification_uri: device.verification_uri,
+            expires_in: Duration::from_secs(device.expires_in),
+            interval: Duration::from_secs(device.interval.unwrap_or(5)),
+            device_code: Secret::new(device.device_code),
+        })
+    }
+
+    /// Poll until the user approves or denies the device, or the codes expire
+    pub async fn poll_device_code(&self, device: &DeviceAuthorization) -> Result<OAuthTokens> {
+        let deadline = tokio::time::Instant::now() + device.expires_in;
+        let mut interval = device.interval;
+
+        loop {
+            tokio::time::sleep(interval).await;
+            if tokio::time::Instant::now() >= deadline {
+                return Err(Error::authentication(self.provider.name.as_str(), "device code expired"));
+            }
+
+            let response = self
+                .token_request(&[
+                    ("grant_type", "urn:ietf:params:oauth:grant-type:device_code".to_string()),
+                    ("device_code", device.device_code.expose_secret().clone()),
+                    ("client_id", self.provider.client_id.clone()),
+                ])
+                .await?;
+
+            match response.error.as_deref() {
+                Some("authorization_pending") => continue,
+                Some("slow_down") => {
+                    interval += Duration::from_secs(5);
+                    continue;
+                }
+                Some("access_denied") | Some("expired_token") => {
+                    let message = response.error_message().unwrap_or_default();
+                    return Err(Error::authentication(self.provider.name.clone(), message));
+                }
+                _ => return response.into_tokens(),
+            }
+        }
+    }
+
+    /// Sign in with the device-code flow
+    ///
+    /// `prompt` receives the code and page to show the user before polling starts.
+    pub async fn login_with_device_code<F>(&self, prompt: F) -> Result<OAuthTokens>
+    where
+        F: FnOnce(&DeviceAuthorization),
+    {
+        let device = self.request_device_code().await?;
+        prompt(&device);
+        self.poll_device_code(&device).await
+    }
+
+    /// Obtain a new access token with a refresh token
+    ///
+    /// The old refresh token is kept if the server does not issue a new one.
+    pub async fn refresh(&self, refresh_token: &Secret<String>) -> Result<OAuthTokens> {
+        debug!("Refreshing {} access token", self.provider.name);
+        let mut tokens = self
+            .token_request(&[
+                ("grant_type", "refresh_token".to_string()),
+                ("refresh_token", refresh_token.expose_secret().clone()),
+                ("client_id", self.provider.client_id.clone()),
+            ])
+            .await?
+            .into_tokens()?;
+        if tokens.refresh_token.is_none() {
+            tokens.refresh_token = Some(refresh_token.clone());
+        }
+        Ok(tokens)
+    }
+
+    /// POST to the token endpoint and decode the body, whatever the status
+    async fn token_request(&self, params: &[(&str, String)]) -> Result<TokenResponse> {
+        let request = self
+            .http
+            .post(&self.provider.token_url)
+            .header("Accept", "application/json");
+        let request = match self.provider.token_format {
+            TokenFormat::Form => request.form(params),
+            TokenFormat::Json => request.json(
+                &params
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), serde_json::Value::from(v.as_str())))
+                    .collect::<serde_json::Map<_, _>>(),
+            ),
+        };
+
+        let response = request.send().await.map_err(transport_error)?;
+        let status = response.status();
+        let body = response.text().await.map_err(transport_error)?;
+
+        match serde_json::from_str::<TokenResponse>(&body) {
+            Ok(parsed) if status.is_success() || parsed.error.is_some() => Ok(parsed),
+            _ => Err(Error::TokenExchangeFailed {
+                message: format!("token endpoint returned HTTP {}", status),
+            }),
+        }
+    }
+}
+
+/// Open a URL in the system browser
+pub fn open_browser(url: &Url) -> Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/c", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+
+    let status = command.arg(url.as_str()).status()?;
+    if !status.success() {
+        return Err(Error::custom(format!("Failed to open browser for {}", url)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Minimal authorization server answering each request with the next scripted reply
+    pub(super) async fn stub_server(replies: Vec<(u16, &'static str)>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let seen = bodies.clone();
+
+        tokio::spawn(async move {
+            for (status, reply) in replies {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let body = loop {
+                    let read = stream.read(&mut chunk).await.unwrap();
+                    request.extend_from_slice(&chunk[..read]);
+                    let text = String::from_utf8_lossy(&request).into_owned();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            break body.to_string();
+                        }
+                    }
+                };
+                seen.lock().unwrap().push(body);
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    reply.len(),
+                    reply
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (url, bodies)
+    }
+
+    pub(super) fn stub_provider(url: &str, format: TokenFormat) -> OAuthProvider {
+        OAuthProvider {
+            name: "stub".to_string(),
+            client_id: "client-1".to_string(),
+            authorize_url: Some(format!("{}/authorize", url)),
+            token_url: format!("{}/token", url),
+            device_code_url: Some(format!("{}/device", url)),
+            scopes: vec!["read".to_string(), "write".to_string()],
+            redirect_uri: None,
+            authorize_params: Vec::new(),
+            token_format: format,
+        }
+    }
+
+    fn form(body: &str) -> HashMap<String, String> {
+        url::form_urlencoded::parse(body.as_bytes()).into_owned().collect()
+    }
+
+    #[tokio::test]
+    async fn test_pkce_login_with_loopback_redirect() {
+        let (url, bodies) = stub_server(vec![(
+            200,
+            r#"{"access_token": "at-1", "refresh_token": "rt-1", "token_type": "Bearer", "expires_in": 3600}"#,
+        )])
+        .await;
+        let client = OAuthClient::new(stub_provider(&url, TokenFormat::Form)).unwrap();
+
+        let challenge = Arc::new(Mutex::new(String::new()));
+        let seen_challenge = challenge.clone();
+        let tokens = client
+            .login_with_pkce(|authorize| {
+                let params: HashMap<_, _> = authorize.query_pairs().into_owned().collect();
+                assert_eq!(params["scope"], "read write");
+                assert_eq!(params["code_challenge_method"], "S256");
+                *seen_challenge.lock().unwrap() = params["code_challenge"].clone();
+
+                let redirect = format!("{}?code=code-1&state={}", params["redirect_uri"], params["state"]);
+                tokio::spawn(async move { reqwest::get(redirect).await.unwrap() });
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(tokens.access_token.expose_secret(), "at-1");
+        assert!(!tokens.is_expired());
+        assert!(tokens.expires_within(Duration::from_secs(7200)));
+
+        let request = form(&bodies.lock().unwrap()[0]);
+        assert_eq!(request["grant_type"], "authorization_code");
+        assert_eq!(request["code"], "code-1");
+        let verifier = Pkce::from_verifier(request["code_verifier"].as_str());
+        assert_eq!(verifier.challenge(), challenge.lock().unwrap().as_str());
+    }
+
+    #[tokio::test]
+    async fn test_pasted_code_uses_json_and_checks_state() {
+        let (url, bodies) = stub_server(vec![(200, r#"{"access_token": "at-2"}"#)]).await;
+        let mut provider = stub_provider(&url, TokenFormat::Json);
+        provider.redirect_uri = Some("https://example.com/callback".to_string());
+        let client = OAuthClient::new(provider).unwrap();
+
+        assert!(client.login_with_pkce(|_| {}).await.is_err());
+
+        let request = client.authorization_request("https://example.com/callback").unwrap();
+        assert!(client.exchange_pasted_code(&request, "code-2#forged").await.is_err());
+        let pasted = format!("code-2#{}\n", request.state);
+        let tokens = client.exchange_pasted_code(&request, &pasted).await.unwrap();
+        assert_eq!(tokens.access_token.expose_secret(), "at-2");
+        assert_eq!(tokens.expires_at, None);
+
+        let body: serde_json::Value = serde_json::from_str(&bodies.lock().unwrap()[0]).unwrap();
+        assert_eq!(body["code"], "code-2");
+        assert_eq!(body["state"], request.state.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_device_code_flow_polls_until_approved() {
+        let (url, bodies) = stub_server(vec![
+            (
+                200,
+                r#"{"device_code": "dc-1", "user_code": "ABCD-1234", "verification_uri": "https://example.com/device", "expires_in": 60, "interval": 0}"#,
+            ),
+            (400, r#"{"error": "authorization_pending"}"#),
+            (200, r#"{"access_token": "at-3", "token_type": "bearer", "scope": "read"}"#),
+        ])
+        .await;
+        let client = OAuthClient::new(stub_provider(&url, TokenFormat::Form)).unwrap();
+
+        let mut prompted = None;
+        let tokens = client
+            .login_with_device_code(|device| prompted = Some(device.user_code.clone()))
+            .await
+            .unwrap();
+        assert_eq!(prompted.as_deref(), Some("ABCD-1234"));
+        assert_eq!(tokens.access_token.expose_secret(), "at-3");
+        assert_eq!(tokens.scope.as_deref(), Some("read"));
+
+        let bodies = bodies.lock().unwrap();
+        assert_eq!(form(&bodies[0])["client_id"], "client-1");
+        assert_eq!(form(&bodies[2])["device_code"], "dc-1");
+    }
+
+    #[tokio::test]
+    async fn test_device_code_denied() {
+        let (url, _) = stub_server(vec![
+            (
+                200,
+                r#"{"device_code": "dc-1", "user_code": "ABCD", "verification_uri": "https://example.com/device", "expires_in": 60, "interval": 0}"#,
+            ),
+            (400, r#"{"error": "access_denied", "error_description": "user said no"}"#),
+        ])
+        .await;
+        let client = OAuthClient::new(stub_provider(&url, TokenFormat::Form)).unwrap();
+
+        let err = client.login_with_device_code(|_| {}).await.unwrap_err();
+        assert!(err.is_auth_error());
+        assert!(err.to_string().contains("user said no"));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_refresh_token() {
+        let (url, _) = stub_server(vec![(200, r#"{"access_token": "at-4", "expires_in": 60}"#)]).await;
+        let client = OAuthClient::new(stub_provider(&url, TokenFormat::Form)).unwrap();
+
+        let tokens = client.refresh(&Secret::new("rt-1".to_string())).await.unwrap();
+        assert_eq!(tokens.access_token.expose_secret(), "at-4");
+        assert_eq!(tokens.refresh_token.unwrap().expose_secret(), "rt-1");
+    }
+
+    #[derive(Debug, Default)]
+    pub(super) struct MemoryStore(Mutex<HashMap<String, String>>);
+
+    impl CredentialStore for MemoryStore {
+        fn name(&self) -> &str {
+            "memory"
+        }
+
+        fn get(&self, name: &str) -> Result<Option<Secret<String>>> {
+            Ok(self.0.lock().unwrap().get(name).cloned().map(Secret::new))
+        }
+
+        fn set(&self, name: &str, secret: &str) -> Result<()> {
+            self.0.lock().unwrap().insert(name.to_string(), secret.to_string());
+            Ok(())
+        }
+
+        fn delete(&self, name: &str) -> Result<()> {
+            self.0.lock().unwrap().remove(name);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_tokens_round_trip_through_store() {
+        let store = MemoryStore::default();
+        let expires_at = UNIX_EPOCH + Duration::from_secs(4_000_000_000);
+        let tokens = OAuthTokens {
+            access_token: Secret::new("at".to_string()),
+            refresh_token: Some(Secret::new("rt".to_string())),
+            token_type: "Bearer".to_string(),
+            expires_at: Some(expires_at),
+            scope: None,
+        };
+        tokens.save(&store, "anthropic").unwrap();
+        assert_eq!(store.0.lock().unwrap()["anthropic_refresh"], "rt");
+
+        let loaded = OAuthTokens::load(&store, "anthropic").unwrap().unwrap();
+        assert_eq!(loaded.access_token.expose_secret(), "at");
+        assert_eq!(loaded.expires_at, Some(expires_at));
+
+        OAuthTokens::delete(&store, "anthropic").unwrap();
+        assert!(OAuthTokens::load(&store, "anthropic").unwrap().is_none());
+        assert!(store.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_builtin_providers() {
+        assert_eq!(OAuthProvider::for_provider(Provider::Copilot).unwrap().name, "github");
+        assert_eq!(OAuthProvider::for_provider(Provider::Claude).unwrap().name, "anthropic");
+        assert!(OAuthProvider::for_provider(Provider::OpenAI).is_none());
+    }
+}
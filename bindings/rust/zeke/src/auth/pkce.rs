@@ -0,0 +1,73 @@
+//! Proof Key for Code Exchange (RFC 7636)
+
+use crate::{Error, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use secrecy::{ExposeSecret, Secret};
+use sha2::{Digest, Sha256};
+
+/// A PKCE verifier and its S256 challenge
+#[derive(Debug, Clone)]
+pub struct Pkce {
+    verifier: Secret<String>,
+    challenge: String,
+}
+
+impl Pkce {
+    /// Generate a fresh verifier from 32 random bytes
+    pub fn new() -> Result<Self> {
+        Ok(Self::from_verifier(random_token(32)?))
+    }
+
+    /// Use an existing verifier
+    pub fn from_verifier<S: Into<String>>(verifier: S) -> Self {
+        let verifier = verifier.into();
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Self {
+            verifier: Secret::new(verifier),
+            challenge,
+        }
+    }
+
+    /// The verifier, sent with the token request
+    pub fn verifier(&self) -> &str {
+        self.verifier.expose_secret()
+    }
+
+    /// The challenge, sent with the authorization request
+    pub fn challenge(&self) -> &str {
+        &self.challenge
+    }
+
+    /// The challenge method, always `S256`
+    pub fn method(&self) -> &'static str {
+        "S256"
+    }
+}
+
+/// URL-safe random string encoding `bytes` random bytes
+pub(crate) fn random_token(bytes: usize) -> Result<String> {
+    let mut buffer = vec![0u8; bytes];
+    getrandom::getrandom(&mut buffer)
+        .map_err(|e| Error::custom(format!("Failed to generate random bytes: {}", e)))?;
+    Ok(URL_SAFE_NO_PAD.encode(buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc7636_example() {
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+        assert_eq!(pkce.challenge(), "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+    }
+
+    #[test]
+    fn test_generated_verifier() {
+        let a = Pkce::new().unwrap();
+        let b = Pkce::new().unwrap();
+        assert_eq!(a.verifier().len(), 43);
+        assert_eq!(a.challenge().len(), 43);
+        assert_ne!(a.verifier(), b.verifier());
+    }
+}
@@ -0,0 +1,959 @@
+//! Native Anthropic Messages API backend
+//!
+//! Supports system prompts, `text`/`tool_use`/`tool_result` content blocks and
+//! the server-sent event stream of `POST /v1/messages`.
+
+use super::http::{check_status, line_stream, sse_data, transport_error};
+use super::{AccessToken, ChatBackend, ChatRequest, ChunkStream, Role};
+use crate::{
+    keys::{KeyLease, KeyPool},
+    response::{ChatResponse, ChunkMetadata, ResponseMetadata, StreamChunk},
+    Config, Error, Provider, Result,
+};
+use futures::future::BoxFuture;
+use futures::stream::{BoxStream, StreamExt};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+use uuid::Uuid;
+
+/// API version sent in the `anthropic-version` header
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// `anthropic-beta` flag that lets requests authenticate with an OAuth token
+pub const OAUTH_BETA: &str = "oauth-2025-04-20";
+
+/// Default `max_tokens` when a request does not set one (the API requires it)
+pub const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// Author of a Messages API message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageRole {
+    /// User turn (also carries tool results)
+    User,
+    /// Assistant turn
+    Assistant,
+}
+
+/// Content of a `tool_result` block: plain text or nested blocks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ToolResultContent {
+    /// Plain text result
+    Text(String),
+    /// Structured result
+    Blocks(Vec<ContentBlock>),
+}
+
+/// A content block in a message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    /// Plain text
+    Text {
+        /// The text
+        text: String,
+    },
+    /// A tool invocation requested by the model
+    ToolUse {
+        /// Identifier referenced by the matching `tool_result`
+        id: String,
+        /// Tool name
+        name: String,
+        /// Tool input as JSON
+        input: serde_json::Value,
+    },
+    /// The result of a tool invocation, sent back by the user
+    ToolResult {
+        /// Identifier of the `tool_use` block being answered
+        tool_use_id: String,
+        /// Result content
+        content: ToolResultContent,
+        /// Whether the tool failed
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        is_error: Option<bool>,
+    },
+}
+
+impl ContentBlock {
+    /// Create a text block
+    pub fn text<S: Into<String>>(text: S) -> Self {
+        ContentBlock::Text { text: text.into() }
+    }
+
+    /// Create a text tool result
+    pub fn tool_result<S: Into<String>>(tool_use_id: S, content: S) -> Self {
+        ContentBlock::ToolResult {
+            tool_use_id: tool_use_id.into(),
+            content: ToolResultContent::Text(content.into()),
+            is_error: None,
+        }
+    }
+
+    /// Text of this block, if it is a text block
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            ContentBlock::Text { text } => Some(text),
+            _ => None,
+        }
+    }
+}
+
+/// A message in a Messages API conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    /// Author of the message
+    pub role: MessageRole,
+    /// Content blocks
+    pub content: Vec<ContentBlock>,
+}
+
+impl Message {
+    /// Create a user message with a single text block
+    pub fn user<S: Into<String>>(text: S) -> Self {
+        Self {
+            role: MessageRole::User,
+            content: vec![ContentBlock::text(text)],
+        }
+    }
+
+    /// Create an assistant message with a single text block
+    pub fn assistant<S: Into<String>>(text: S) -> Self {
+        Self {
+            role: MessageRole::Assistant,
+            content: vec![ContentBlock::text(text)],
+        }
+    }
+}
+
+/// A tool the model may call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tool {
+    /// Tool name
+    pub name: String,
+    /// What the tool does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON schema of the tool input
+    pub input_schema: serde_json::Value,
+}
+
+/// Request body for `POST /v1/messages`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessagesRequest {
+    /// Model to use
+    pub model: String,
+    /// Maximum tokens to generate
+    pub max_tokens: u32,
+    /// Conversation, alternating user and assistant turns
+    pub messages: Vec<Message>,
+    /// System prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    /// Sampling temperature (0.0 to 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Custom stop sequences
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    /// Tools available to the model
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<Tool>,
+    /// Whether to stream the response
+    #[serde(default)]
+    pub stream: bool,
+}
+
+impl From<ChatRequest> for MessagesRequest {
+    fn from(request: ChatRequest) -> Self {
+        let system = request.system_prompt();
+        let messages = request
+            .messages
+            .into_iter()
+            .filter(|m| m.role != Role::System)
+            .map(|m| Message {
+                role: match m.role {
+                    Role::Assistant => MessageRole::Assistant,
+                    _ => MessageRole::User,
+                },
+                content: vec![ContentBlock::text(m.content)],
+            })
+            .collect();
+
+        Self {
+            model: request.model,
+            max_tokens: request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            messages,
+            system,
+            temperature: request.temperature,
+            stop_sequences: Vec::new(),
+            tools: Vec::new(),
+            stream: false,
+        }
+    }
+}
+
+/// Why the model stopped generating
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// Natural end of turn
+    EndTurn,
+    /// `max_tokens` was reached
+    MaxTokens,
+    /// A custom stop sequence was generated
+    StopSequence,
+    /// The model wants to call a tool
+    ToolUse,
+    /// A long-running turn was paused
+    PauseTurn,
+    /// The model declined to answer
+    Refusal,
+    /// A reason this crate does not know about yet
+    #[serde(other)]
+    Unknown,
+}
+
+impl StopReason {
+    /// Wire name of the stop reason
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StopReason::EndTurn => "end_turn",
+            StopReason::MaxTokens => "max_tokens",
+            StopReason::StopSequence => "stop_sequence",
+            StopReason::ToolUse => "tool_use",
+            StopReason::PauseTurn => "pause_turn",
+            StopReason::Refusal => "refusal",
+            StopReason::Unknown => "unknown",
+        }
+    }
+}
+
+/// Token usage reported by the API
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Input tokens
+    #[serde(default)]
+    pub input_tokens: u32,
+    /// Output tokens
+    #[serde(default)]
+    pub output_tokens: u32,
+    /// Input tokens written to the prompt cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_creation_input_tokens: Option<u32>,
+    /// Input tokens read from the prompt cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_input_tokens: Option<u32>,
+}
+
+/// Response body of `POST /v1/messages`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessagesResponse {
+    /// Message identifier
+    pub id: String,
+    /// Model that produced the message
+    pub model: String,
+    /// Always `assistant`
+    pub role: MessageRole,
+    /// Generated content blocks
+    #[serde(default)]
+    pub content: Vec<ContentBlock>,
+    /// Why generation stopped (absent in `message_start` events)
+    #[serde(default)]
+    pub stop_reason: Option<StopReason>,
+    /// Stop sequence that was hit, if any
+    #[serde(default)]
+    pub stop_sequence: Option<String>,
+    /// Token usage
+    #[serde(default)]
+    pub usage: Usage,
+}
+
+impl MessagesResponse {
+    /// Concatenated text of all text blocks
+    pub fn text(&self) -> String {
+        self.content.iter().filter_map(ContentBlock::as_text).collect()
+    }
+
+    /// All `tool_use` blocks
+    pub fn tool_uses(&self) -> impl Iterator<Item = &ContentBlock> {
+        self.content
+            .iter()
+            .filter(|block| matches!(block, ContentBlock::ToolUse { .. }))
+    }
+}
+
+/// Incremental update to a content block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentDelta {
+    /// More text for a text block
+    TextDelta {
+        /// Text fragment
+        text: String,
+    },
+    /// More JSON for a `tool_use` block's input
+    InputJsonDelta {
+        /// Partial JSON fragment
+        partial_json: String,
+    },
+    /// A delta type this crate does not know about yet
+    #[serde(other)]
+    Unknown,
+}
+
+/// Top-level fields changed by a `message_delta` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageDelta {
+    /// Why generation stopped
+    #[serde(default)]
+    pub stop_reason: Option<StopReason>,
+    /// Stop sequence that was hit, if any
+    #[serde(default)]
+    pub stop_sequence: Option<String>,
+}
+
+/// Error payload of an `error` event or error response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiError {
+    /// Error type, e.g. `overloaded_error`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Error message
+    pub message: String,
+}
+
+/// A server-sent event from a streaming Messages request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    /// Start of the message, with empty content and input usage
+    MessageStart {
+        /// The message so far
+        message: MessagesResponse,
+    },
+    /// A new content block begins
+    ContentBlockStart {
+        /// Block index
+        index: u32,
+        /// Initial block (empty text or a `tool_use` without input)
+        content_block: ContentBlock,
+    },
+    /// Incremental content for a block
+    ContentBlockDelta {
+        /// Block index
+        index: u32,
+        /// The delta
+        delta: ContentDelta,
+    },
+    /// A content block is complete
+    ContentBlockStop {
+        /// Block index
+        index: u32,
+    },
+    /// Stop reason and cumulative output usage
+    MessageDelta {
+        /// Changed top-level fields
+        delta: MessageDelta,
+        /// Cumulative usage
+        #[serde(default)]
+        usage: Usage,
+    },
+    /// End of the stream
+    MessageStop,
+    /// Keep-alive
+    Ping,
+    /// An error occurred mid-stream
+    Error {
+        /// Error details
+        error: ApiError,
+    },
+}
+
+/// Maps stream events onto [`StreamChunk`]s
+#[derive(Debug)]
+struct ChunkMapper {
+    stream_id: Uuid,
+    next_index: u32,
+}
+
+impl ChunkMapper {
+    fn new() -> Self {
+        Self {
+            stream_id: Uuid::new_v4(),
+            next_index: 0,
+        }
+    }
+
+    fn chunk(&mut self, event: &str, content: String, is_final: bool) -> StreamChunk {
+        let mut metadata = ChunkMetadata::default();
+        metadata.provider_data.insert("event".to_string(), event.into());
+
+        let chunk = StreamChunk::new(self.stream_id, content, self.next_index, is_final)
+            .with_metadata(metadata);
+        self.next_index += 1;
+        chunk
+    }
+
+    /// Convert an event into a chunk; `ping` and `content_block_stop` produce none
+    fn map(&mut self, event: StreamEvent) -> Option<Result<StreamChunk>> {
+        let chunk = match event {
+            StreamEvent::MessageStart { message } => {
+                let mut chunk = self.chunk("message_start", String::new(), false);
+                let data = &mut chunk.metadata.provider_data;
+                data.insert("message_id".to_string(), message.id.into());
+                data.insert("model".to_string(), message.model.into());
+                data.insert("input_tokens".to_string(), message.usage.input_tokens.into());
+                chunk
+            }
+            StreamEvent::ContentBlockStart { index, content_block } => {
+                let mut chunk = self.chunk("content_block_start", String::new(), false);
+                let data = &mut chunk.metadata.provider_data;
+                data.insert("index".to_string(), index.into());
+                if let ContentBlock::ToolUse { id, name, .. } = content_block {
+                    data.insert("tool_use_id".to_string(), id.into());
+                    data.insert("tool_name".to_string(), name.into());
+                }
+                chunk
+            }
+            StreamEvent::ContentBlockDelta { index, delta } => {
+                let (content, partial_json) = match delta {
+                    ContentDelta::TextDelta { text } => (text, None),
+                    ContentDelta::InputJsonDelta { partial_json } => (String::new(), Some(partial_json)),
+                    ContentDelta::Unknown => return None,
+                };
+                let mut chunk = self.chunk("content_block_delta", content, false);
+                let data = &mut chunk.metadata.provider_data;
+                data.insert("index".to_string(), index.into());
+                if let Some(json) = partial_json {
+                    data.insert("partial_json".to_string(), json.into());
+                }
+                chunk
+            }
+            StreamEvent::MessageDelta { delta, usage } => {
+                let mut chunk = self.chunk("message_delta", String::new(), false);
+                chunk.metadata.tokens = Some(usage.output_tokens);
+                if let Some(reason) = delta.stop_reason {
+                    chunk
+                        .metadata
+                        .provider_data
+                        .insert("stop_reason".to_string(), reason.as_str().into());
+                }
+                chunk
+            }
+            StreamEvent::MessageStop => self.chunk("message_stop", String::new(), true),
+            StreamEvent::Error { error } => return Some(Err(api_error(error))),
+            StreamEvent::ContentBlockStop { .. } | StreamEvent::Ping => return None,
+        };
+        Some(Ok(chunk))
+    }
+}
+
+fn api_error(error: ApiError) -> Error {
+    let provider = Provider::Claude.identifier();
+    match error.kind.as_str() {
+        "authentication_error" | "permission_error" => {
+            Error::authentication(provider, error.message.as_str())
+        }
+        "rate_limit_error" => Error::rate_limited(provider, error.message.as_str()),
+        "overloaded_error" | "api_error" => {
+            Error::provider_unavailable(provider, error.message.as_str())
+        }
+        _ => Error::unexpected_response(provider, error.message.as_str()),
+    }
+}
+
+/// The key a stream was sent with, held until the stream ends
+///
+/// Usage arrives spread over the stream: input tokens in `message_start`,
+/// cumulative output tokens in each `message_delta`. It is attributed to the
+/// key at `message_stop`, or when a stream cut short is dropped.
+#[derive(Debug)]
+struct StreamLease {
+    lease: KeyLease,
+    input_tokens: Option<u32>,
+    output_tokens: Option<u32>,
+    recorded: bool,
+}
+
+impl StreamLease {
+    fn new(lease: KeyLease) -> Self {
+        Self {
+            lease,
+            input_tokens: None,
+            output_tokens: None,
+            recorded: false,
+        }
+    }
+
+    fn observe(&mut self, event: &StreamEvent) {
+        match event {
+            StreamEvent::MessageStart { message } => self.input_tokens = Some(message.usage.input_tokens),
+            StreamEvent::MessageDelta { usage, .. } => {
+                self.output_tokens = Some(usage.output_tokens);
+                if usage.input_tokens > 0 {
+                    self.input_tokens = Some(usage.input_tokens);
+                }
+            }
+            StreamEvent::MessageStop => self.record_usage(),
+            StreamEvent::Error { error } => self.lease.record_error(&api_error(error.clone())),
+            _ => {}
+        }
+    }
+
+    fn record_usage(&mut self) {
+        if !self.recorded && (self.input_tokens.is_some() || self.output_tokens.is_some()) {
+            self.lease.record_usage(self.input_tokens, self.output_tokens);
+            self.recorded = true;
+        }
+    }
+}
+
+impl Drop for StreamLease {
+    fn drop(&mut self) {
+        self.record_usage();
+    }
+}
+
+/// Native client for the Anthropic Messages API
+#[derive(Debug, Clone)]
+pub struct AnthropicBackend {
+    client: reqwest::Client,
+    base_url: String,
+    keys: Arc<KeyPool>,
+    timeout: Duration,
+}
+
+impl AnthropicBackend {
+    /// Create a backend using the public API endpoint
+    pub fn new<S: Into<String>>(api_key: S) -> Result<Self> {
+        let keys = KeyPool::single(Provider::Claude.identifier(), Secret::new(api_key.into()));
+        Self::with_timeout(
+            Provider::Claude.default_base_url(),
+            keys,
+            Duration::from_secs(600),
+        )
+    }
+
+    /// `timeout` bounds connecting and each non-streaming request; a stream
+    /// runs for as long as the API keeps sending events
+    fn with_timeout(base_url: &str, keys: KeyPool, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(timeout)
+            .build()
+            .map_err(transport_error)?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            keys: Arc::new(keys),
+            timeout,
+        })
+    }
+
+    /// Create a backend from a config, honouring its base URL, API keys and timeout
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let keys = config.key_pool().ok_or_else(|| Error::ConfigError {
+            message: "API key is required for the Anthropic backend".to_string(),
+        })?;
+
+        Self::with_timeout(
+            &config.effective_base_url(),
+            keys,
+            Duration::from_millis(config.timeout_ms as u64),
+        )
+    }
+
+    /// Rotate requests through a shared pool of API keys
+    pub fn with_key_pool(mut self, keys: Arc<KeyPool>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// API keys requests are sent with
+    pub fn key_pool(&self) -> &Arc<KeyPool> {
+        &self.keys
+    }
+
+    /// Use a different API base URL (e.g. a proxy)
+    pub fn with_base_url<S: Into<String>>(mut self, base_url: S) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Base URL of the API
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Send a request with `token`, or else with the next key from the pool
+    async fn send(
+        &self,
+        request: &MessagesRequest,
+        token: Option<&AccessToken>,
+    ) -> Result<(reqwest::Response, Option<KeyLease>)> {
+        let lease = match token {
+            Some(_) => None,
+            None => Some(self.keys.acquire()?),
+        };
+        let mut builder = self
+            .client
+            .post(format!("{}/messages", self.base_url))
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(request);
+        if let Some(lease) = &lease {
+            builder = builder.header("x-api-key", lease.secret().expose_secret());
+        }
+        if let Some(token) = token {
+            builder = builder
+                .bearer_auth(token.secret().expose_secret())
+                .header("anthropic-beta", OAUTH_BETA);
+        }
+        if !request.stream {
+            builder = builder.timeout(self.timeout);
+        }
+
+        let response = match builder.send().await {
+            Ok(response) => check_status(Provider::Claude, response).await,
+            Err(e) => Err(transport_error(e)),
+        };
+
+        match response {
+            Ok(response) => Ok((response, lease)),
+            Err(e) => {
+                if let Some(lease) = &lease {
+                    lease.record_error(&e);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Send a Messages request and wait for the complete response
+    pub async fn messages(&self, request: &MessagesRequest) -> Result<MessagesResponse> {
+        self.leased_messages(request, None).await.map(|(raw, _)| raw)
+    }
+
+    async fn leased_messages(
+        &self,
+        request: &MessagesRequest,
+        token: Option<&AccessToken>,
+    ) -> Result<(MessagesResponse, Option<KeyLease>)> {
+        debug!("Anthropic messages request for model {}", request.model);
+
+        let mut request = request.clone();
+        request.stream = false;
+        let (response, lease) = self.send(&request, token).await?;
+        let raw: MessagesResponse = response.json().await.map_err(transport_error)?;
+        if let Some(lease) = &lease {
+            lease.record_usage(Some(raw.usage.input_tokens), Some(raw.usage.output_tokens));
+        }
+        Ok((raw, lease))
+    }
+
+    /// Send a Messages request and stream its server-sent events
+    ///
+    /// The key stays leased until the stream ends; the usage the stream
+    /// reports and any `error` event are recorded against it.
+    pub async fn messages_stream(
+        &self,
+        request: &MessagesRequest,
+    ) -> Result<BoxStream<'static, Result<StreamEvent>>> {
+        self.leased_stream(request, None).await
+    }
+
+    async fn leased_stream(
+        &self,
+        request: &MessagesRequest,
+        token: Option<&AccessToken>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent>>> {
+        debug!("Anthropic streaming request for model {}", request.model);
+
+        let mut request = request.clone();
+        request.stream = true;
+        let (response, lease) = self.send(&request, token).await?;
+        let mut lease = lease.map(StreamLease::new);
+
+        Ok(line_stream(response)
+            .filter_map(|line| {
+                futures::future::ready(match line {
+                    Ok(line) => sse_data(&line).map(|data| {
+                        serde_json::from_str::<StreamEvent>(data).map_err(Error::from)
+                    }),
+                    Err(e) => Some(Err(e)),
+                })
+            })
+            .map(move |event| {
+                if let (Some(lease), Ok(event)) = (&mut lease, &event) {
+                    lease.observe(event);
+                }
+                event
+            })
+            .boxed())
+    }
+
+    fn to_chat_response(raw: MessagesResponse, temperature: Option<f32>, response_time: Duration) -> ChatResponse {
+        let mut metadata = ResponseMetadata {
+            input_tokens: Some(raw.usage.input_tokens),
+            output_tokens: Some(raw.usage.output_tokens),
+            temperature,
+            stop_reason: raw.stop_reason.map(|r| r.as_str().to_string()),
+            ..Default::default()
+        };
+        metadata
+            .provider_data
+            .insert("message_id".to_string(), raw.id.clone().into());
+        if let Some(sequence) = &raw.stop_sequence {
+            metadata
+                .provider_data
+                .insert("stop_sequence".to_string(), sequence.clone().into());
+        }
+        if raw.tool_uses().next().is_some()
+            && let Ok(blocks) = serde_json::to_value(&raw.content)
+        {
+            metadata.provider_data.insert("content_blocks".to_string(), blocks);
+        }
+
+        ChatResponse::new(
+            raw.text(),
+            Provider::Claude,
+            raw.model,
+            Some(raw.usage.input_tokens + raw.usage.output_tokens),
+            response_time,
+        )
+        .with_metadata(metadata)
+    }
+}
+
+impl ChatBackend for AnthropicBackend {
+    fn provider(&self) -> Provider {
+        Provider::Claude
+    }
+
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+        Box::pin(async move {
+            let start_time = Instant::now();
+            let temperature = request.temperature;
+            let token = request.access_token.clone();
+            let (raw, lease) = self
+                .leased_messages(&MessagesRequest::from(request), token.as_ref())
+                .await?;
+            let mut response = Self::to_chat_response(raw, temperature, start_time.elapsed());
+            if let Some(lease) = lease {
+                response
+                    .metadata
+                    .provider_data
+                    .insert("key_id".to_string(), lease.id().into());
+            }
+            Ok(response)
+        })
+    }
+
+    fn chat_stream(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChunkStream>> {
+        Box::pin(async move {
+            let token = request.access_token.clone();
+            let events = self
+                .leased_stream(&MessagesRequest::from(request), token.as_ref())
+                .await?;
+            let mut mapper = ChunkMapper::new();
+
+            Ok(events
+                .filter_map(move |event| {
+                    futures::future::ready(match event {
+                        Ok(event) => mapper.map(event),
+                        Err(e) => Some(Err(e)),
+                    })
+                })
+                .boxed())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::http::testing::{slow_server, sse, sse_server};
+    use crate::backend::ChatMessage;
+
+    #[test]
+    fn test_request_from_chat_request() {
+        let request = ChatRequest::new("claude-3-5-sonnet-20241022")
+            .message(ChatMessage::system("You are terse."))
+            .message(ChatMessage::user("Hi"))
+            .message(ChatMessage::assistant("Hello"))
+            .temperature(0.3);
+
+        let body = serde_json::to_value(MessagesRequest::from(request)).unwrap();
+        assert_eq!(body["system"], "You are terse.");
+        assert_eq!(body["max_tokens"], DEFAULT_MAX_TOKENS);
+        assert_eq!(body["messages"].as_array().unwrap().len(), 2);
+        assert_eq!(body["messages"][0]["role"], "user");
+        assert_eq!(body["messages"][0]["content"][0]["type"], "text");
+        assert_eq!(body["messages"][1]["role"], "assistant");
+        assert!(body.get("tools").is_none());
+    }
+
+    #[test]
+    fn test_content_block_serialization() {
+        let tool_use: ContentBlock = serde_json::from_str(
+            r#"{"type":"tool_use","id":"toolu_1","name":"read_file","input":{"path":"src/lib.rs"}}"#,
+        )
+        .unwrap();
+        assert!(matches!(tool_use, ContentBlock::ToolUse { ref name, .. } if name == "read_file"));
+
+        let result = serde_json::to_value(ContentBlock::tool_result("toolu_1", "fn main() {}")).unwrap();
+        assert_eq!(result["type"], "tool_result");
+        assert_eq!(result["content"], "fn main() {}");
+        assert!(result.get("is_error").is_none());
+    }
+
+    #[test]
+    fn test_response_mapping() {
+        let raw: MessagesResponse = serde_json::from_str(
+            r#"{"id":"msg_1","type":"message","role":"assistant","model":"claude-3-5-sonnet-20241022",
+                "content":[{"type":"text","text":"Let me check."},
+                           {"type":"tool_use","id":"toolu_1","name":"ls","input":{}}],
+                "stop_reason":"tool_use","stop_sequence":null,
+                "usage":{"input_tokens":20,"output_tokens":7}}"#,
+        )
+        .unwrap();
+
+        let response = AnthropicBackend::to_chat_response(raw, Some(0.5), Duration::from_millis(5));
+        assert_eq!(response.content, "Let me check.");
+        assert_eq!(response.provider, Provider::Claude);
+        assert_eq!(response.tokens_used, Some(27));
+        assert_eq!(response.metadata.stop_reason.as_deref(), Some("tool_use"));
+        assert_eq!(response.metadata.input_tokens, Some(20));
+        assert!(response.metadata.provider_data.contains_key("content_blocks"));
+    }
+
+    #[test]
+    fn test_stream_event_mapping() {
+        let events = [
+            r#"{"type":"message_start","message":{"id":"msg_1","type":"message","role":"assistant","model":"claude","content":[],"stop_reason":null,"usage":{"input_tokens":9,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"ping"}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hello"}}"#,
+            r#"{"type":"content_block_stop","index":0}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"end_turn","stop_sequence":null},"usage":{"output_tokens":2}}"#,
+            r#"{"type":"message_stop"}"#,
+        ];
+
+        let mut mapper = ChunkMapper::new();
+        let chunks: Vec<StreamChunk> = events
+            .iter()
+            .map(|e| serde_json::from_str::<StreamEvent>(e).unwrap())
+            .filter_map(|e| mapper.map(e))
+            .map(|c| c.unwrap())
+            .collect();
+
+        assert_eq!(chunks.len(), 5);
+        assert_eq!(chunks[0].metadata.provider_data["input_tokens"], 9);
+        assert_eq!(chunks[2].content, "Hello");
+        assert_eq!(chunks[3].metadata.tokens, Some(2));
+        assert_eq!(chunks[3].metadata.provider_data["stop_reason"], "end_turn");
+        assert!(chunks[4].is_final);
+        assert_eq!(chunks[4].chunk_index, 4);
+    }
+
+    #[test]
+    fn test_stream_error_event() {
+        let event: StreamEvent = serde_json::from_str(
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        )
+        .unwrap();
+
+        let err = ChunkMapper::new().map(event).unwrap().unwrap_err();
+        assert!(matches!(err, Error::ProviderUnavailable { .. }));
+        assert!(err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_access_token_replaces_pool_key() {
+        let (url, requests) = sse_server(vec![sse(&[
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hi"}}"#,
+            r#"{"type":"message_stop"}"#,
+        ])])
+        .await;
+        let keys = Arc::new(KeyPool::new("claude", [Secret::new("sk-ant-1".to_string())]).unwrap());
+        let backend = AnthropicBackend::new("unused")
+            .unwrap()
+            .with_base_url(url)
+            .with_key_pool(Arc::clone(&keys));
+        let request = ChatRequest::new("claude")
+            .message(ChatMessage::user("Hi"))
+            .access_token(Secret::new("oauth-access".to_string()));
+
+        let chunks: Vec<_> = backend.chat_stream(request).await.unwrap().collect().await;
+        assert!(chunks.iter().all(Result::is_ok));
+
+        let head = requests.lock().unwrap()[0].to_lowercase();
+        assert!(head.contains("authorization: bearer oauth-access"));
+        assert!(head.contains(&format!("anthropic-beta: {}", OAUTH_BETA)));
+        assert!(!head.contains("x-api-key"));
+        assert_eq!(keys.usage()[0].requests, 0);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_spares_streams() {
+        const LINES: &[&str] = &[
+            r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hel"}}"#,
+            r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"lo"}}"#,
+            r#"data: {"type":"message_stop"}"#,
+        ];
+        let url = slow_server(LINES, Duration::from_millis(150)).await;
+        let keys = KeyPool::single("claude", Secret::new("sk-ant-1".to_string()));
+        let backend = AnthropicBackend::with_timeout(&url, keys, Duration::from_millis(200)).unwrap();
+        let request = || ChatRequest::new("claude").message(ChatMessage::user("Hi"));
+
+        // The stream takes longer than the timeout and still arrives whole
+        let chunks: Vec<_> = backend.chat_stream(request()).await.unwrap().collect().await;
+        let content: String = chunks.into_iter().map(|chunk| chunk.unwrap().content).collect();
+        assert_eq!(content, "Hello");
+
+        // A plain request waiting as long is cut off
+        let err = backend.chat(request()).await.unwrap_err();
+        assert!(matches!(err, Error::NetworkError { .. }), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_stream_records_usage_and_errors_on_pooled_key() {
+        let (url, _) = sse_server(vec![
+            sse(&[
+                r#"{"type":"message_start","message":{"id":"msg_1","type":"message","role":"assistant","model":"claude","content":[],"stop_reason":null,"usage":{"input_tokens":9,"output_tokens":1}}}"#,
+                r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hello"}}"#,
+                r#"{"type":"message_delta","delta":{"stop_reason":"end_turn","stop_sequence":null},"usage":{"output_tokens":4}}"#,
+                r#"{"type":"message_stop"}"#,
+            ]),
+            sse(&[r#"{"type":"error","error":{"type":"rate_limit_error","message":"Slow down"}}"#]),
+        ])
+        .await;
+        let keys = Arc::new(KeyPool::new("claude", [Secret::new("sk-ant-1".to_string())]).unwrap());
+        let backend = AnthropicBackend::new("unused")
+            .unwrap()
+            .with_base_url(url)
+            .with_key_pool(Arc::clone(&keys));
+        let request = || ChatRequest::new("claude").message(ChatMessage::user("Hi"));
+
+        let chunks: Vec<_> = backend.chat_stream(request()).await.unwrap().collect().await;
+        assert!(chunks.iter().all(Result::is_ok));
+        let usage = &keys.usage()[0];
+        assert_eq!((usage.input_tokens, usage.output_tokens), (9, 4));
+        assert_eq!(usage.failures, 0);
+
+        let chunks: Vec<_> = backend.chat_stream(request()).await.unwrap().collect().await;
+        assert!(chunks[0].as_ref().unwrap_err().is_rate_limit());
+        let usage = &keys.usage()[0];
+        assert_eq!(usage.failures, 1);
+        assert!(usage.quarantined);
+    }
+
+    #[test]
+    fn test_unknown_stop_reason() {
+        let reason: StopReason = serde_json::from_str("\"something_new\"").unwrap();
+        assert_eq!(reason, StopReason::Unknown);
+    }
+}
@@ -0,0 +1,292 @@
+//! HTTP helpers shared by the native backends
+
+use crate::{Error, Provider, Result};
+use futures::stream::{self, BoxStream, StreamExt};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Convert a transport-level `reqwest` failure into a Zeke error
+pub(crate) fn transport_error(err: reqwest::Error) -> Error {
+    // A timeout while reading the body also reports as a decode error
+    if err.is_decode() && !err.is_timeout() {
+        Error::custom(format!("Failed to decode response body: {}", err))
+    } else {
+        Error::network(err.to_string())
+    }
+}
+
+/// Map a non-success HTTP status and its body onto a Zeke error
+pub(crate) fn status_error(provider: Provider, status: reqwest::StatusCode, body: &str) -> Error {
+    let message = error_message(body).unwrap_or_else(|| format!("HTTP {}", status));
+    let provider = provider.identifier();
+
+    let error = match status.as_u16() {
+        401 | 403 => Error::authentication(provider, message.as_str()),
+        408 => Error::network(message),
+        429 => Error::rate_limited(provider, message.as_str()),
+        500..=599 => Error::provider_unavailable(provider, message.as_str()),
+        _ => Error::unexpected_response(provider, message.as_str()),
+    };
+    error.with_http_status(status.as_u16())
+}
+
+/// Read how long the provider asked to wait from the response headers
+///
+/// Understands `retry-after-ms` and a `Retry-After` given in seconds; the
+/// HTTP-date form of `Retry-After` is ignored.
+pub(crate) fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<f64>().ok();
+
+    let delay = header("retry-after-ms")
+        .map(|ms| ms / 1000.0)
+        .or_else(|| header("retry-after"))?;
+    Duration::try_from_secs_f64(delay).ok()
+}
+
+/// Extract a human-readable message from a JSON error body
+///
+/// Handles both `{"error": "..."}` and `{"error": {"message": "..."}}` shapes.
+pub(crate) fn error_message(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let error = value.get("error")?;
+
+    error
+        .as_str()
+        .or_else(|| error.get("message").and_then(|m| m.as_str()))
+        .map(str::to_owned)
+}
+
+/// Fail with a mapped error unless the response has a success status
+pub(crate) async fn check_status(
+    provider: Provider,
+    response: reqwest::Response,
+) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let retry_after = retry_after(response.headers());
+    let body = response.text().await.unwrap_or_default();
+    let error = status_error(provider, status, &body);
+    Err(match retry_after {
+        Some(delay) => error.with_retry_after(delay),
+        None => error,
+    })
+}
+
+/// Splits a byte stream into newline-terminated UTF-8 lines
+#[derive(Debug, Default)]
+pub(crate) struct LineBuffer {
+    buffer: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Feed bytes and return every line they complete (without the terminator)
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut lines = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let mut line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            lines.push(String::from_utf8_lossy(&line).into_owned());
+        }
+        lines
+    }
+
+    /// Return any trailing data that was not newline-terminated
+    pub fn finish(&mut self) -> Option<String> {
+        if self.buffer.is_empty() {
+            None
+        } else {
+            let rest = std::mem::take(&mut self.buffer);
+            Some(String::from_utf8_lossy(&rest).into_owned())
+        }
+    }
+}
+
+/// Payload of a server-sent event `data:` line, or `None` for any other line
+pub(crate) fn sse_data(line: &str) -> Option<&str> {
+    line.strip_prefix("data:").map(str::trim_start)
+}
+
+/// Turn a response body into a stream of its lines
+pub(crate) fn line_stream(response: reqwest::Response) -> BoxStream<'static, Result<String>> {
+    struct State {
+        response: reqwest::Response,
+        buffer: LineBuffer,
+        pending: VecDeque<String>,
+        done: bool,
+    }
+
+    let state = State {
+        response,
+        buffer: LineBuffer::default(),
+        pending: VecDeque::new(),
+        done: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(line) = state.pending.pop_front() {
+                return Some((Ok(line), state));
+            }
+            if state.done {
+                return None;
+            }
+
+            match state.response.chunk().await {
+                Ok(Some(bytes)) => state.pending.extend(state.buffer.push(&bytes)),
+                Ok(None) => {
+                    state.done = true;
+                    state.pending.extend(state.buffer.finish());
+                }
+                Err(e) => {
+                    state.done = true;
+                    return Some((Err(transport_error(e)), state));
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Local servers for backend tests
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Server answering each request with the next server-sent event body
+    ///
+    /// Also returns the requests received, as read in one go.
+    pub(crate) async fn sse_server(bodies: Vec<String>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        tokio::spawn(async move {
+            for body in bodies {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 8192];
+                let read = stream.read(&mut request).await.unwrap_or(0);
+                seen.lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&request[..read]).into_owned());
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    /// Server answering every request with `lines`, `delay` apart
+    pub(crate) async fn slow_server(lines: &'static [&'static str], delay: Duration) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut request = [0u8; 8192];
+                    let _ = stream.read(&mut request).await;
+                    let head = "HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n";
+                    if stream.write_all(head.as_bytes()).await.is_err() {
+                        return;
+                    }
+                    for line in lines {
+                        tokio::time::sleep(delay).await;
+                        // The client gave up; nothing left to send
+                        if stream.write_all(format!("{}\n", line).as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        url
+    }
+
+    /// Server-sent event body carrying each of `events` as data
+    pub(crate) fn sse(events: &[&str]) -> String {
+        events.iter().map(|event| format!("data: {}\n\n", event)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_buffer_splits_across_chunks() {
+        let mut buffer = LineBuffer::default();
+
+        assert!(buffer.push(b"{\"a\":").is_empty());
+        assert_eq!(buffer.push(b"1}\r\n{\"b\":2}\n{\"c\""), vec!["{\"a\":1}", "{\"b\":2}"]);
+        assert_eq!(buffer.finish().as_deref(), Some("{\"c\""));
+        assert_eq!(buffer.finish(), None);
+    }
+
+    #[test]
+    fn test_sse_data() {
+        assert_eq!(sse_data("data: {\"type\":\"ping\"}"), Some("{\"type\":\"ping\"}"));
+        assert_eq!(sse_data("data:{}"), Some("{}"));
+        assert_eq!(sse_data("event: ping"), None);
+        assert_eq!(sse_data(""), None);
+    }
+
+    #[test]
+    fn test_error_message_shapes() {
+        assert_eq!(error_message(r#"{"error":"model not found"}"#).as_deref(), Some("model not found"));
+        assert_eq!(
+            error_message(r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#).as_deref(),
+            Some("Overloaded")
+        );
+        assert_eq!(error_message("not json"), None);
+    }
+
+    #[test]
+    fn test_status_error_mapping() {
+        use reqwest::StatusCode;
+
+        let err = status_error(Provider::Ollama, StatusCode::UNAUTHORIZED, "");
+        assert!(err.is_auth_error());
+
+        let err = status_error(Provider::Ollama, StatusCode::SERVICE_UNAVAILABLE, r#"{"error":"busy"}"#);
+        assert!(matches!(err, Error::ProviderUnavailable { ref message, .. } if message == "busy"));
+
+        let err = status_error(Provider::Ollama, StatusCode::TOO_MANY_REQUESTS, "");
+        assert!(err.is_rate_limit() && err.is_retryable());
+
+        let err = status_error(Provider::Ollama, StatusCode::BAD_REQUEST, "");
+        assert!(matches!(err, Error::UnexpectedResponse { .. }));
+        assert_eq!(err.http_status(), Some(400));
+    }
+
+    #[test]
+    fn test_retry_after_headers() {
+        use reqwest::header::{HeaderMap, HeaderValue};
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert("retry-after", HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        // The millisecond header is more precise and wins
+        headers.insert("retry-after-ms", HeaderValue::from_static("1500"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(1500)));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert_eq!(retry_after(&headers), None);
+    }
+}
@@ -0,0 +1,250 @@
+//! Native Rust backends for AI providers
+//!
+//! By default every request goes through the Zig core over FFI. A backend
+//! talks to a provider's HTTP API directly from Rust and can be plugged into
+//! a [`Zeke`](crate::Zeke) instance with [`Zeke::with_backend`](crate::Zeke::with_backend).
+
+use crate::{
+    response::{ChatResponse, StreamChunk},
+    Config, Provider, Result,
+};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[cfg(feature = "reqwest")]
+pub(crate) mod http;
+
+#[cfg(feature = "ollama")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ollama")))]
+pub mod ollama;
+
+#[cfg(feature = "anthropic")]
+#[cfg_attr(docsrs, doc(cfg(feature = "anthropic")))]
+pub mod anthropic;
+
+#[cfg(feature = "openai")]
+#[cfg_attr(docsrs, doc(cfg(feature = "openai")))]
+pub mod openai;
+
+/// Stream of chunks produced by a streaming backend call
+pub type ChunkStream = BoxStream<'static, Result<StreamChunk>>;
+
+/// Role of a message author in a conversation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// System instructions
+    System,
+    /// End-user input
+    User,
+    /// Model output
+    Assistant,
+    /// Output of a tool invocation
+    Tool,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::System => write!(f, "system"),
+            Role::User => write!(f, "user"),
+            Role::Assistant => write!(f, "assistant"),
+            Role::Tool => write!(f, "tool"),
+        }
+    }
+}
+
+/// A single message in a chat conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// Author of the message
+    pub role: Role,
+    /// Text content of the message
+    pub content: String,
+}
+
+impl ChatMessage {
+    /// Create a message with the given role
+    pub fn new<S: Into<String>>(role: Role, content: S) -> Self {
+        Self {
+            role,
+            content: content.into(),
+        }
+    }
+
+    /// Create a system message
+    pub fn system<S: Into<String>>(content: S) -> Self {
+        Self::new(Role::System, content)
+    }
+
+    /// Create a user message
+    pub fn user<S: Into<String>>(content: S) -> Self {
+        Self::new(Role::User, content)
+    }
+
+    /// Create an assistant message
+    pub fn assistant<S: Into<String>>(content: S) -> Self {
+        Self::new(Role::Assistant, content)
+    }
+}
+
+/// OAuth access token a [`ChatRequest`] is authenticated with
+///
+/// Never serialized; two tokens are equal when their secrets are.
+#[derive(Debug, Clone)]
+pub struct AccessToken(Secret<String>);
+
+impl AccessToken {
+    /// Wrap an access token
+    pub fn new(token: Secret<String>) -> Self {
+        Self(token)
+    }
+
+    /// The token itself
+    pub fn secret(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+impl PartialEq for AccessToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+/// Provider-agnostic chat request handed to a backend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatRequest {
+    /// Model to use
+    pub model: String,
+    /// Conversation so far, oldest message first
+    pub messages: Vec<ChatMessage>,
+    /// Sampling temperature
+    pub temperature: Option<f32>,
+    /// Maximum number of tokens to generate
+    pub max_tokens: Option<u32>,
+    /// OAuth access token sent instead of the backend's API keys
+    ///
+    /// Backends for servers without authentication ignore it.
+    #[serde(skip)]
+    pub access_token: Option<AccessToken>,
+}
+
+impl ChatRequest {
+    /// Create an empty request for a model
+    pub fn new<S: Into<String>>(model: S) -> Self {
+        Self {
+            model: model.into(),
+            messages: Vec::new(),
+            temperature: None,
+            max_tokens: None,
+            access_token: None,
+        }
+    }
+
+    /// Create a single-prompt request using the model and sampling settings of a config
+    pub fn from_config(config: &Config, prompt: &str) -> Self {
+        Self::new(config.model.clone())
+            .message(ChatMessage::user(prompt))
+            .temperature(config.temperature)
+            .max_tokens(config.max_tokens)
+    }
+
+    /// Append a message
+    pub fn message(mut self, message: ChatMessage) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Set the temperature
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Set the maximum tokens
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Authenticate with an OAuth access token
+    pub fn access_token(mut self, token: Secret<String>) -> Self {
+        self.access_token = Some(AccessToken::new(token));
+        self
+    }
+
+    /// Concatenated content of all system messages, if any
+    pub fn system_prompt(&self) -> Option<String> {
+        let parts: Vec<&str> = self
+            .messages
+            .iter()
+            .filter(|m| m.role == Role::System)
+            .map(|m| m.content.as_str())
+            .collect();
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join("\n\n"))
+        }
+    }
+}
+
+/// A native chat backend for a single provider
+///
+/// Implementations must be cheap to share between tasks; [`Zeke`](crate::Zeke)
+/// holds them behind an `Arc`.
+pub trait ChatBackend: Send + Sync + fmt::Debug {
+    /// Provider this backend talks to
+    fn provider(&self) -> Provider;
+
+    /// Send a request and wait for the complete response
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>>;
+
+    /// Send a request and stream the response as it is generated
+    fn chat_stream(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChunkStream>>;
+
+    /// Check that the provider can serve requests
+    ///
+    /// Backends without a cheap probe report healthy.
+    fn health_check(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_from_config() {
+        let config = Config::default();
+        let request = ChatRequest::from_config(&config, "Hello");
+
+        assert_eq!(request.model, config.model);
+        assert_eq!(request.messages, vec![ChatMessage::user("Hello")]);
+        assert_eq!(request.temperature, Some(config.temperature));
+        assert_eq!(request.max_tokens, Some(config.max_tokens));
+    }
+
+    #[test]
+    fn test_system_prompt() {
+        let request = ChatRequest::new("m")
+            .message(ChatMessage::system("Be brief."))
+            .message(ChatMessage::user("Hi"))
+            .message(ChatMessage::system("Use Rust."));
+
+        assert_eq!(request.system_prompt().as_deref(), Some("Be brief.\n\nUse Rust."));
+        assert_eq!(ChatRequest::new("m").system_prompt(), None);
+    }
+
+    #[test]
+    fn test_role_serialization() {
+        assert_eq!(serde_json::to_string(&Role::Assistant).unwrap(), "\"assistant\"");
+        assert_eq!(Role::Tool.to_string(), "tool");
+    }
+}
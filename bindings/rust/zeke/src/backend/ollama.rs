@@ -0,0 +1,655 @@
+//! Native Ollama backend
+//!
+//! Talks to the Ollama REST API (`/api/chat`, `/api/generate`, `/api/tags`,
+//! `/api/show`, `/api/pull` and `/api/embeddings`) without going through the
+//! Zig core.
+
+use super::http::{check_status, line_stream, transport_error};
+use super::{ChatBackend, ChatMessage, ChatRequest, ChunkStream};
+use crate::{
+    response::{ChatResponse, ResponseMetadata, StreamChunk},
+    Config, Error, Provider, Result,
+};
+use futures::future::BoxFuture;
+use futures::stream::{BoxStream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::debug;
+use uuid::Uuid;
+
+/// Output format requested from Ollama
+#[derive(Debug, Clone, PartialEq)]
+pub enum OllamaFormat {
+    /// Constrain output to valid JSON (`format: "json"`)
+    Json,
+    /// Constrain output to a JSON schema
+    Schema(serde_json::Value),
+}
+
+impl OllamaFormat {
+    /// Parse a format from a config value (`"json"` or a schema object)
+    pub fn from_value(value: serde_json::Value) -> Option<Self> {
+        match value {
+            serde_json::Value::String(s) if s.eq_ignore_ascii_case("json") => Some(Self::Json),
+            serde_json::Value::Object(_) => Some(Self::Schema(value)),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for OllamaFormat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            OllamaFormat::Json => serializer.serialize_str("json"),
+            OllamaFormat::Schema(schema) => schema.serialize(serializer),
+        }
+    }
+}
+
+/// Ollama-specific request options
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OllamaOptions {
+    /// Context window size in tokens (`num_ctx`)
+    pub num_ctx: Option<u32>,
+    /// Number of layers to offload to the GPU (`num_gpu`)
+    pub num_gpu: Option<i32>,
+    /// How long the model stays loaded after a request, e.g. `"5m"` or `"-1"`
+    pub keep_alive: Option<String>,
+    /// Structured output format
+    pub format: Option<OllamaFormat>,
+}
+
+impl OllamaOptions {
+    /// Read options from the `ollama.*` provider settings of a config
+    ///
+    /// Recognised keys are `num_ctx`, `num_gpu`, `keep_alive` and `format`.
+    pub fn from_config(config: &Config) -> Self {
+        let setting = |key: &str| config.provider_settings.get(&format!("ollama.{}", key)).cloned();
+
+        Self {
+            num_ctx: setting("num_ctx").and_then(|v| serde_json::from_value(v).ok()),
+            num_gpu: setting("num_gpu").and_then(|v| serde_json::from_value(v).ok()),
+            keep_alive: setting("keep_alive").and_then(|v| match v {
+                serde_json::Value::String(s) => Some(s),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            }),
+            format: setting("format").and_then(OllamaFormat::from_value),
+        }
+    }
+
+    /// Request JSON-only output
+    pub fn json(mut self) -> Self {
+        self.format = Some(OllamaFormat::Json);
+        self
+    }
+
+    /// Set the context window size
+    pub fn num_ctx(mut self, num_ctx: u32) -> Self {
+        self.num_ctx = Some(num_ctx);
+        self
+    }
+
+    /// Set the keep-alive duration
+    pub fn keep_alive<S: Into<String>>(mut self, keep_alive: S) -> Self {
+        self.keep_alive = Some(keep_alive.into());
+        self
+    }
+}
+
+/// Model runtime options as sent in the `options` object
+#[derive(Debug, Default, Serialize)]
+struct WireOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_ctx: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_gpu: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_predict: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatBody<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'a OllamaFormat>,
+    options: WireOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct GenerateBody<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<&'a str>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'a OllamaFormat>,
+    options: WireOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<&'a str>,
+}
+
+/// Response (or stream frame) from `/api/chat`
+#[derive(Debug, Clone, Deserialize)]
+pub struct OllamaChatResponse {
+    /// Model that produced the response
+    pub model: String,
+    /// Generated message (partial when streaming)
+    #[serde(default)]
+    pub message: Option<ChatMessage>,
+    /// Whether generation has finished
+    pub done: bool,
+    /// Why generation stopped, e.g. `stop` or `length`
+    #[serde(default)]
+    pub done_reason: Option<String>,
+    /// Prompt tokens evaluated
+    #[serde(default)]
+    pub prompt_eval_count: Option<u32>,
+    /// Tokens generated
+    #[serde(default)]
+    pub eval_count: Option<u32>,
+    /// Total request duration in nanoseconds
+    #[serde(default)]
+    pub total_duration: Option<u64>,
+    /// Model load duration in nanoseconds
+    #[serde(default)]
+    pub load_duration: Option<u64>,
+}
+
+/// Request for `/api/generate`
+#[derive(Debug, Clone, Default)]
+pub struct GenerateRequest {
+    /// Model to use
+    pub model: String,
+    /// Raw prompt
+    pub prompt: String,
+    /// Optional system prompt overriding the Modelfile
+    pub system: Option<String>,
+    /// Sampling temperature
+    pub temperature: Option<f32>,
+    /// Maximum tokens to generate
+    pub max_tokens: Option<u32>,
+}
+
+/// Response from `/api/generate`
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenerateResponse {
+    /// Model that produced the response
+    pub model: String,
+    /// Generated text
+    pub response: String,
+    /// Whether generation has finished
+    pub done: bool,
+    /// Encoded conversation context for follow-up requests
+    #[serde(default)]
+    pub context: Option<Vec<i64>>,
+    /// Prompt tokens evaluated
+    #[serde(default)]
+    pub prompt_eval_count: Option<u32>,
+    /// Tokens generated
+    #[serde(default)]
+    pub eval_count: Option<u32>,
+}
+
+/// Family and quantisation details of a model
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelFamily {
+    /// Weight format, e.g. `gguf`
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Model family, e.g. `llama`
+    #[serde(default)]
+    pub family: Option<String>,
+    /// Parameter count, e.g. `7B`
+    #[serde(default)]
+    pub parameter_size: Option<String>,
+    /// Quantisation level, e.g. `Q4_0`
+    #[serde(default)]
+    pub quantization_level: Option<String>,
+}
+
+/// A model installed locally, as returned by `/api/tags`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalModel {
+    /// Model name including tag, e.g. `llama3:8b`
+    pub name: String,
+    /// Last modification time (RFC 3339)
+    #[serde(default)]
+    pub modified_at: Option<String>,
+    /// Size on disk in bytes
+    #[serde(default)]
+    pub size: u64,
+    /// Content digest
+    #[serde(default)]
+    pub digest: String,
+    /// Family details
+    #[serde(default)]
+    pub details: ModelFamily,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<LocalModel>,
+}
+
+/// Model information returned by `/api/show`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ModelDetails {
+    /// Modelfile contents
+    #[serde(default)]
+    pub modelfile: Option<String>,
+    /// Default runtime parameters, one per line
+    #[serde(default)]
+    pub parameters: Option<String>,
+    /// Prompt template
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Family details
+    #[serde(default)]
+    pub details: ModelFamily,
+    /// Architecture-specific metadata such as `llama.context_length`
+    #[serde(default)]
+    pub model_info: HashMap<String, serde_json::Value>,
+}
+
+impl ModelDetails {
+    /// Context length advertised in the model metadata
+    pub fn context_length(&self) -> Option<u64> {
+        self.model_info
+            .iter()
+            .find(|(key, _)| key.ends_with(".context_length"))
+            .and_then(|(_, value)| value.as_u64())
+    }
+}
+
+/// A progress frame from `/api/pull`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullProgress {
+    /// Human-readable status, e.g. `pulling manifest` or `success`
+    pub status: String,
+    /// Digest of the layer being downloaded
+    #[serde(default)]
+    pub digest: Option<String>,
+    /// Total bytes of the current layer
+    #[serde(default)]
+    pub total: Option<u64>,
+    /// Bytes downloaded so far of the current layer
+    #[serde(default)]
+    pub completed: Option<u64>,
+}
+
+impl PullProgress {
+    /// Fraction of the current layer downloaded (0.0 to 1.0), if known
+    pub fn fraction(&self) -> Option<f64> {
+        match (self.completed, self.total) {
+            (Some(completed), Some(total)) if total > 0 => Some(completed as f64 / total as f64),
+            _ => None,
+        }
+    }
+
+    /// Whether the pull has finished successfully
+    pub fn is_success(&self) -> bool {
+        self.status == "success"
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingsResponse {
+    embedding: Vec<f32>,
+}
+
+/// A line of an Ollama response, which may carry an error instead of data
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Frame<T> {
+    Error { error: String },
+    Data(T),
+}
+
+fn parse_frame<T: DeserializeOwned>(line: &str) -> Result<T> {
+    match serde_json::from_str::<Frame<T>>(line)? {
+        Frame::Data(data) => Ok(data),
+        Frame::Error { error } => Err(Error::unexpected_response(
+            Provider::Ollama.identifier(),
+            error.as_str(),
+        )),
+    }
+}
+
+/// Native client for a local or remote Ollama server
+#[derive(Debug, Clone)]
+pub struct OllamaBackend {
+    client: reqwest::Client,
+    base_url: String,
+    options: OllamaOptions,
+    timeout: Duration,
+}
+
+impl OllamaBackend {
+    /// Create a backend for the server at `base_url`, e.g. `http://localhost:11434`
+    pub fn new<S: Into<String>>(base_url: S) -> Result<Self> {
+        Self::with_timeout(base_url, Duration::from_secs(300))
+    }
+
+    /// `timeout` bounds connecting and each non-streaming request; streams such
+    /// as a long pull or generation run for as long as the server keeps sending
+    fn with_timeout<S: Into<String>>(base_url: S, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(timeout)
+            .build()
+            .map_err(transport_error)?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            options: OllamaOptions::default(),
+            timeout,
+        })
+    }
+
+    /// Create a backend from a config, honouring its base URL, timeout and `ollama.*` settings
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let backend = Self::with_timeout(
+            config.effective_base_url(),
+            Duration::from_millis(config.timeout_ms as u64),
+        )?;
+        Ok(backend.with_options(OllamaOptions::from_config(config)))
+    }
+
+    /// Set the Ollama-specific options applied to every request
+    pub fn with_options(mut self, options: OllamaOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Options applied to every request
+    pub fn options(&self) -> &OllamaOptions {
+        &self.options
+    }
+
+    /// Base URL of the server
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/api/{}", self.base_url, path)
+    }
+
+    fn wire_options(&self, temperature: Option<f32>, max_tokens: Option<u32>) -> WireOptions {
+        WireOptions {
+            num_ctx: self.options.num_ctx,
+            num_gpu: self.options.num_gpu,
+            temperature,
+            num_predict: max_tokens,
+        }
+    }
+
+    fn chat_body<'a>(&'a self, request: &'a ChatRequest, stream: bool) -> ChatBody<'a> {
+        ChatBody {
+            model: &request.model,
+            messages: &request.messages,
+            stream,
+            format: self.options.format.as_ref(),
+            options: self.wire_options(request.temperature, request.max_tokens),
+            keep_alive: self.options.keep_alive.as_deref(),
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let response = request.send().await.map_err(transport_error)?;
+        check_status(Provider::Ollama, response).await
+    }
+
+    /// Start a streaming request; only connecting is bounded by the timeout
+    async fn post_stream<B: Serialize>(&self, path: &str, body: &B) -> Result<reqwest::Response> {
+        self.send(self.client.post(self.url(path)).json(body)).await
+    }
+
+    async fn post_json<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        let request = self.client.post(self.url(path)).json(body).timeout(self.timeout);
+        let text = self.send(request).await?.text().await.map_err(transport_error)?;
+        parse_frame(&text)
+    }
+
+    /// Send a chat request to `/api/chat` and return the raw Ollama response
+    pub async fn chat_raw(&self, request: &ChatRequest) -> Result<OllamaChatResponse> {
+        debug!("Ollama chat with model {}", request.model);
+        self.post_json("chat", &self.chat_body(request, false)).await
+    }
+
+    /// Complete a raw prompt with `/api/generate`
+    pub async fn generate(&self, request: &GenerateRequest) -> Result<GenerateResponse> {
+        let body = GenerateBody {
+            model: &request.model,
+            prompt: &request.prompt,
+            system: request.system.as_deref(),
+            stream: false,
+            format: self.options.format.as_ref(),
+            options: self.wire_options(request.temperature, request.max_tokens),
+            keep_alive: self.options.keep_alive.as_deref(),
+        };
+        self.post_json("generate", &body).await
+    }
+
+    /// List locally installed models with `/api/tags`
+    pub async fn tags(&self) -> Result<Vec<LocalModel>> {
+        let request = self.client.get(self.url("tags")).timeout(self.timeout);
+        let text = self
+            .send(request)
+            .await?
+            .text()
+            .await
+            .map_err(transport_error)?;
+        let tags: TagsResponse = parse_frame(&text)?;
+        Ok(tags.models)
+    }
+
+    /// Show details of an installed model with `/api/show`
+    pub async fn show(&self, model: &str) -> Result<ModelDetails> {
+        self.post_json("show", &serde_json::json!({ "model": model }))
+            .await
+            .map_err(|e| match e {
+                Error::UnexpectedResponse { .. } => Error::invalid_model(model, "ollama"),
+                other => other,
+            })
+    }
+
+    /// Pull a model with `/api/pull`, streaming download progress
+    pub async fn pull(&self, model: &str) -> Result<BoxStream<'static, Result<PullProgress>>> {
+        debug!("Pulling Ollama model {}", model);
+        let response = self
+            .post_stream("pull", &serde_json::json!({ "model": model, "stream": true }))
+            .await?;
+
+        Ok(line_stream(response)
+            .filter(|line| futures::future::ready(!matches!(line, Ok(l) if l.trim().is_empty())))
+            .map(|line| line.and_then(|l| parse_frame(&l)))
+            .boxed())
+    }
+
+    /// Compute an embedding vector with `/api/embeddings`
+    pub async fn embeddings(&self, model: &str, prompt: &str) -> Result<Vec<f32>> {
+        let mut body = serde_json::json!({ "model": model, "prompt": prompt });
+        if let Some(keep_alive) = &self.options.keep_alive {
+            body["keep_alive"] = serde_json::Value::String(keep_alive.clone());
+        }
+        let response: EmbeddingsResponse = self.post_json("embeddings", &body).await?;
+        Ok(response.embedding)
+    }
+
+    fn to_chat_response(raw: OllamaChatResponse, response_time: Duration) -> ChatResponse {
+        let tokens_used = match (raw.prompt_eval_count, raw.eval_count) {
+            (None, None) => None,
+            (input, output) => Some(input.unwrap_or(0) + output.unwrap_or(0)),
+        };
+
+        let mut metadata = ResponseMetadata {
+            input_tokens: raw.prompt_eval_count,
+            output_tokens: raw.eval_count,
+            stop_reason: raw.done_reason,
+            ..Default::default()
+        };
+        if let Some(total) = raw.total_duration {
+            metadata.provider_data.insert("total_duration_ns".to_string(), total.into());
+        }
+        if let Some(load) = raw.load_duration {
+            metadata.provider_data.insert("load_duration_ns".to_string(), load.into());
+        }
+
+        ChatResponse::new(
+            raw.message.map(|m| m.content).unwrap_or_default(),
+            Provider::Ollama,
+            raw.model,
+            tokens_used,
+            response_time,
+        )
+        .with_metadata(metadata)
+    }
+}
+
+impl ChatBackend for OllamaBackend {
+    fn provider(&self) -> Provider {
+        Provider::Ollama
+    }
+
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+        Box::pin(async move {
+            let start_time = Instant::now();
+            let raw = self.chat_raw(&request).await?;
+            let mut response = Self::to_chat_response(raw, start_time.elapsed());
+            response.metadata.temperature = request.temperature;
+            Ok(response)
+        })
+    }
+
+    fn chat_stream(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChunkStream>> {
+        Box::pin(async move {
+            let response = self.post_stream("chat", &self.chat_body(&request, true)).await?;
+            let stream_id = Uuid::new_v4();
+
+            let chunks = line_stream(response)
+                .filter(|line| futures::future::ready(!matches!(line, Ok(l) if l.trim().is_empty())))
+                .enumerate()
+                .map(move |(index, line)| {
+                    let frame: OllamaChatResponse = parse_frame(&line?)?;
+                    let content = frame.message.map(|m| m.content).unwrap_or_default();
+                    Ok(StreamChunk::new(stream_id, content, index as u32, frame.done))
+                });
+
+            Ok(chunks.boxed())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::http::testing::slow_server;
+
+    #[test]
+    fn test_chat_body_serialization() {
+        let backend = OllamaBackend::new("http://localhost:11434/")
+            .unwrap()
+            .with_options(OllamaOptions::default().num_ctx(8192).keep_alive("10m").json());
+        let request = ChatRequest::new("llama3")
+            .message(ChatMessage::user("Hi"))
+            .temperature(0.2);
+
+        let body = serde_json::to_value(backend.chat_body(&request, false)).unwrap();
+        assert_eq!(body["model"], "llama3");
+        assert_eq!(body["messages"][0]["role"], "user");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["format"], "json");
+        assert_eq!(body["keep_alive"], "10m");
+        assert_eq!(body["options"]["num_ctx"], 8192);
+        assert!(body["options"].get("num_predict").is_none());
+        assert_eq!(backend.base_url(), "http://localhost:11434");
+    }
+
+    #[test]
+    fn test_options_from_config() {
+        let mut config = Config::default();
+        config.provider = Provider::Ollama;
+        config.set_provider_setting("num_ctx", 4096).unwrap();
+        config.set_provider_setting("keep_alive", -1).unwrap();
+        config.set_provider_setting("format", "json").unwrap();
+
+        let options = OllamaOptions::from_config(&config);
+        assert_eq!(options.num_ctx, Some(4096));
+        assert_eq!(options.keep_alive.as_deref(), Some("-1"));
+        assert_eq!(options.format, Some(OllamaFormat::Json));
+    }
+
+    #[test]
+    fn test_chat_response_mapping() {
+        let raw: OllamaChatResponse = parse_frame(
+            r#"{"model":"llama3","message":{"role":"assistant","content":"Hello!"},"done":true,
+                "done_reason":"stop","prompt_eval_count":12,"eval_count":3,"total_duration":5000}"#,
+        )
+        .unwrap();
+
+        let response = OllamaBackend::to_chat_response(raw, Duration::from_millis(10));
+        assert_eq!(response.content, "Hello!");
+        assert_eq!(response.provider, Provider::Ollama);
+        assert_eq!(response.tokens_used, Some(15));
+        assert_eq!(response.metadata.output_tokens, Some(3));
+        assert_eq!(response.metadata.stop_reason.as_deref(), Some("stop"));
+    }
+
+    #[test]
+    fn test_error_frame() {
+        let err = parse_frame::<OllamaChatResponse>(r#"{"error":"model 'nope' not found"}"#).unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+
+    #[test]
+    fn test_pull_progress() {
+        let progress: PullProgress =
+            parse_frame(r#"{"status":"pulling abc","digest":"sha256:abc","total":200,"completed":50}"#).unwrap();
+        assert_eq!(progress.fraction(), Some(0.25));
+        assert!(!progress.is_success());
+
+        let done: PullProgress = parse_frame(r#"{"status":"success"}"#).unwrap();
+        assert!(done.is_success());
+        assert_eq!(done.fraction(), None);
+    }
+
+    #[test]
+    fn test_model_details_context_length() {
+        let details: ModelDetails =
+            parse_frame(r#"{"details":{"family":"llama"},"model_info":{"llama.context_length":8192}}"#).unwrap();
+        assert_eq!(details.context_length(), Some(8192));
+        assert_eq!(details.details.family.as_deref(), Some("llama"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_spares_streams() {
+        const LINES: &[&str] = &[
+            r#"{"model":"llama3","message":{"role":"assistant","content":"Hel"},"done":false}"#,
+            r#"{"model":"llama3","message":{"role":"assistant","content":"lo"},"done":true}"#,
+        ];
+        let url = slow_server(LINES, Duration::from_millis(150)).await;
+        let backend = OllamaBackend::with_timeout(url, Duration::from_millis(200)).unwrap();
+        let request = || ChatRequest::new("llama3").message(ChatMessage::user("Hi"));
+
+        // The stream takes longer than the timeout and still arrives whole
+        let chunks: Vec<_> = backend.chat_stream(request()).await.unwrap().collect().await;
+        let content: String = chunks.into_iter().map(|chunk| chunk.unwrap().content).collect();
+        assert_eq!(content, "Hello");
+
+        // A plain chat waiting as long is cut off
+        let err = backend.chat(request()).await.unwrap_err();
+        assert!(matches!(err, Error::NetworkError { .. }), "{:?}", err);
+    }
+}
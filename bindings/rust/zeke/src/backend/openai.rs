@@ -0,0 +1,686 @@
+//! Native backend for OpenAI-compatible chat completions APIs
+//!
+//! Talks to OpenAI itself as well as compatible servers such as xAI, OMEN,
+//! vLLM, llama.cpp and LM Studio. Responses are attributed to the
+//! [`Provider`] the backend was created for, so a registered
+//! [`CustomProvider`](crate::CustomProvider) is never reported as OpenAI.
+
+use super::http::{check_status, line_stream, sse_data, status_error, transport_error};
+use super::{AccessToken, ChatBackend, ChatMessage, ChatRequest, ChunkStream, Role};
+use crate::{
+    keys::{KeyLease, KeyPool},
+    registry::AuthScheme,
+    response::{ChatResponse, ResponseMetadata, StreamChunk},
+    Config, Error, Provider, Result,
+};
+use futures::future::BoxFuture;
+use futures::stream::{BoxStream, StreamExt};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+use uuid::Uuid;
+
+/// Request body of `POST /chat/completions`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChatCompletionRequest {
+    /// Model to use
+    pub model: String,
+    /// Conversation messages
+    pub messages: Vec<ChatMessage>,
+    /// Sampling temperature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Maximum number of tokens to generate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Whether to stream the response as server-sent events
+    pub stream: bool,
+    /// Streaming options, sent with streaming requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+}
+
+/// Options of a streaming chat completion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct StreamOptions {
+    /// Send token usage in a final chunk
+    pub include_usage: bool,
+}
+
+impl From<ChatRequest> for ChatCompletionRequest {
+    fn from(request: ChatRequest) -> Self {
+        Self {
+            model: request.model,
+            messages: request.messages,
+            temperature: request.temperature,
+            max_tokens: request.max_tokens,
+            stream: false,
+            stream_options: None,
+        }
+    }
+}
+
+/// A message returned by the API (content may be absent, e.g. for tool calls)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ResponseMessage {
+    /// Author of the message
+    pub role: Role,
+    /// Text content
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// One completion choice
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Choice {
+    /// Position of this choice
+    pub index: u32,
+    /// Generated message
+    pub message: ResponseMessage,
+    /// Why generation stopped
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// Token usage reported by the API
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct CompletionUsage {
+    /// Tokens in the prompt
+    pub prompt_tokens: u32,
+    /// Tokens generated
+    pub completion_tokens: u32,
+    /// Sum of prompt and completion tokens
+    pub total_tokens: u32,
+}
+
+/// Response body of a non-streaming chat completion
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChatCompletionResponse {
+    /// Completion identifier
+    #[serde(default)]
+    pub id: String,
+    /// Model that produced the completion
+    pub model: String,
+    /// Generated choices
+    pub choices: Vec<Choice>,
+    /// Token usage, if reported
+    #[serde(default)]
+    pub usage: Option<CompletionUsage>,
+}
+
+/// Incremental content of a streamed choice
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Delta {
+    /// Text added by this chunk
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// One choice inside a streamed chunk
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChunkChoice {
+    /// Position of this choice
+    pub index: u32,
+    /// Incremental content
+    #[serde(default)]
+    pub delta: Delta,
+    /// Why generation stopped, on the last chunk of a choice
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// A server-sent event of a streaming chat completion
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChatCompletionChunk {
+    /// Completion identifier
+    #[serde(default)]
+    pub id: String,
+    /// Choices updated by this chunk
+    #[serde(default)]
+    pub choices: Vec<ChunkChoice>,
+    /// Token usage, sent by some servers on the final chunk
+    #[serde(default)]
+    pub usage: Option<CompletionUsage>,
+}
+
+/// A model listed by `GET /models`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ModelEntry {
+    /// Model identifier
+    pub id: String,
+    /// Owning organisation, if reported
+    #[serde(default)]
+    pub owned_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelList {
+    data: Vec<ModelEntry>,
+}
+
+/// Native client for an OpenAI-compatible chat completions API
+#[derive(Debug, Clone)]
+pub struct OpenAIBackend {
+    client: reqwest::Client,
+    provider: Provider,
+    base_url: String,
+    auth: AuthScheme,
+    keys: Option<Arc<KeyPool>>,
+    timeout: Duration,
+}
+
+impl OpenAIBackend {
+    /// Create a backend for `provider` at `base_url` using bearer authentication
+    pub fn new<S: Into<String>>(provider: Provider, base_url: S) -> Result<Self> {
+        Self::with_timeout(provider, base_url.into(), Duration::from_secs(600))
+    }
+
+    /// `timeout` bounds connecting and each non-streaming request; a stream
+    /// runs for as long as the server keeps sending chunks
+    fn with_timeout(provider: Provider, base_url: String, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(timeout)
+            .build()
+            .map_err(transport_error)?;
+
+        let auth = match provider.custom_definition() {
+            Some(definition) => definition.auth.clone(),
+            None => AuthScheme::Bearer,
+        };
+
+        Ok(Self {
+            client,
+            provider,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            auth,
+            keys: None,
+            timeout,
+        })
+    }
+
+    /// Create a backend from a config, honouring its provider, base URL, API keys and timeout
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut backend = Self::with_timeout(
+            config.provider,
+            config.effective_base_url(),
+            Duration::from_millis(config.timeout_ms as u64),
+        )?;
+        backend.keys = config.key_pool().map(Arc::new);
+        Ok(backend)
+    }
+
+    /// Set the API key
+    pub fn with_api_key<S: Into<String>>(self, api_key: S) -> Self {
+        let keys = KeyPool::single(self.provider.identifier(), Secret::new(api_key.into()));
+        self.with_key_pool(Arc::new(keys))
+    }
+
+    /// Rotate requests through a shared pool of API keys
+    pub fn with_key_pool(mut self, keys: Arc<KeyPool>) -> Self {
+        self.keys = Some(keys);
+        self
+    }
+
+    /// API keys requests are sent with, if any
+    pub fn key_pool(&self) -> Option<&Arc<KeyPool>> {
+        self.keys.as_ref()
+    }
+
+    /// Override how the API key is sent
+    pub fn with_auth(mut self, auth: AuthScheme) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Base URL of the API
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Send a request with `token` as a bearer token, or else with the next
+    /// key from the pool, if there is one
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&ChatCompletionRequest>,
+        token: Option<&AccessToken>,
+    ) -> Result<(reqwest::Response, Option<KeyLease>)> {
+        let lease = match token {
+            Some(_) => None,
+            None => self.keys.as_ref().map(|keys| keys.acquire()).transpose()?,
+        };
+
+        let mut builder = self
+            .client
+            .request(method, format!("{}/{}", self.base_url, path));
+        let header = lease
+            .as_ref()
+            .and_then(|lease| self.auth.header(lease.secret().expose_secret()));
+        if let Some((name, value)) = header {
+            builder = builder.header(name, value);
+        }
+        if let Some(token) = token {
+            builder = builder.bearer_auth(token.secret().expose_secret());
+        }
+        if let Some(body) = body {
+            builder = builder.json(body);
+        }
+        if !body.is_some_and(|body| body.stream) {
+            builder = builder.timeout(self.timeout);
+        }
+
+        let response = match builder.send().await {
+            Ok(response) => check_status(self.provider, response).await,
+            Err(e) => Err(transport_error(e)),
+        };
+        match response {
+            Ok(response) => Ok((response, lease)),
+            Err(e) => {
+                if let Some(lease) = &lease {
+                    lease.record_error(&e);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Send a chat completion request and wait for the complete response
+    pub async fn chat_completions(&self, request: &ChatCompletionRequest) -> Result<ChatCompletionResponse> {
+        self.leased_completions(request, None).await.map(|(raw, _)| raw)
+    }
+
+    async fn leased_completions(
+        &self,
+        request: &ChatCompletionRequest,
+        token: Option<&AccessToken>,
+    ) -> Result<(ChatCompletionResponse, Option<KeyLease>)> {
+        debug!("{} chat completion for model {}", self.provider, request.model);
+
+        let mut request = request.clone();
+        request.stream = false;
+        request.stream_options = None;
+        let (response, lease) = self
+            .send(reqwest::Method::POST, "chat/completions", Some(&request), token)
+            .await?;
+        let raw: ChatCompletionResponse = response.json().await.map_err(transport_error)?;
+        if let Some(lease) = &lease {
+            lease.record_usage(
+                raw.usage.map(|u| u.prompt_tokens),
+                raw.usage.map(|u| u.completion_tokens),
+            );
+        }
+        Ok((raw, lease))
+    }
+
+    /// Send a chat completion request and stream its chunks
+    ///
+    /// The key stays leased until the stream ends; the usage the stream
+    /// reports and any error event are recorded against it.
+    pub async fn chat_completions_stream(
+        &self,
+        request: &ChatCompletionRequest,
+    ) -> Result<BoxStream<'static, Result<ChatCompletionChunk>>> {
+        self.leased_stream(request, None).await
+    }
+
+    async fn leased_stream(
+        &self,
+        request: &ChatCompletionRequest,
+        token: Option<&AccessToken>,
+    ) -> Result<BoxStream<'static, Result<ChatCompletionChunk>>> {
+        debug!("{} streaming chat completion for model {}", self.provider, request.model);
+
+        let mut request = request.clone();
+        request.stream = true;
+        request.stream_options = Some(StreamOptions { include_usage: true });
+        let (response, lease) = self
+            .send(reqwest::Method::POST, "chat/completions", Some(&request), token)
+            .await?;
+        let provider = self.provider;
+        let mut lease = lease.map(StreamLease::new);
+
+        Ok(line_stream(response)
+            .filter_map(move |line| {
+                let chunk = match line {
+                    Ok(line) => match sse_data(&line) {
+                        Some("[DONE]") => {
+                            if let Some(lease) = &mut lease {
+                                lease.record_usage();
+                            }
+                            None
+                        }
+                        Some(data) => Some(parse_chunk(provider, data)),
+                        None => None,
+                    },
+                    Err(e) => Some(Err(e)),
+                };
+                if let (Some(lease), Some(chunk)) = (&mut lease, &chunk) {
+                    lease.observe(chunk);
+                }
+                futures::future::ready(chunk)
+            })
+            .boxed())
+    }
+
+    /// List the models served by the API
+    pub async fn models(&self) -> Result<Vec<ModelEntry>> {
+        let (response, lease) = self.send(reqwest::Method::GET, "models", None, None).await?;
+        let list: Result<ModelList> = response.json().await.map_err(transport_error);
+        if let (Some(lease), Err(e)) = (&lease, &list) {
+            lease.record_error(e);
+        }
+        Ok(list?.data)
+    }
+
+    fn to_chat_response(
+        &self,
+        raw: ChatCompletionResponse,
+        temperature: Option<f32>,
+        response_time: Duration,
+    ) -> ChatResponse {
+        let choice = raw.choices.into_iter().next();
+        let mut metadata = ResponseMetadata {
+            input_tokens: raw.usage.map(|u| u.prompt_tokens),
+            output_tokens: raw.usage.map(|u| u.completion_tokens),
+            temperature,
+            stop_reason: choice.as_ref().and_then(|c| c.finish_reason.clone()),
+            ..Default::default()
+        };
+        if !raw.id.is_empty() {
+            metadata
+                .provider_data
+                .insert("completion_id".to_string(), raw.id.into());
+        }
+
+        ChatResponse::new(
+            choice.and_then(|c| c.message.content).unwrap_or_default(),
+            self.provider,
+            raw.model,
+            raw.usage.map(|u| u.total_tokens),
+            response_time,
+        )
+        .with_metadata(metadata)
+    }
+}
+
+/// Parse the payload of a streamed event, which is a chunk or an error
+fn parse_chunk(provider: Provider, data: &str) -> Result<ChatCompletionChunk> {
+    match stream_error(provider, data) {
+        Some(error) => Err(error),
+        None => serde_json::from_str(data).map_err(Into::into),
+    }
+}
+
+/// Map an error event sent mid-stream onto a Zeke error
+///
+/// OpenAI names the error in `code` or `type`; compatible servers such as
+/// vLLM put the HTTP status in `code` instead.
+fn stream_error(provider: Provider, data: &str) -> Option<Error> {
+    let value: serde_json::Value = serde_json::from_str(data).ok()?;
+    let error = value.get("error")?;
+
+    let status = match error.get("code").and_then(serde_json::Value::as_u64) {
+        Some(code) => u16::try_from(code).unwrap_or(500),
+        None => {
+            let kind = ["code", "type"]
+                .iter()
+                .filter_map(|field| error.get(*field)?.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            if kind.contains("rate_limit") || kind.contains("quota") {
+                429
+            } else if kind.contains("api_key") || kind.contains("auth") {
+                401
+            } else if kind.contains("server_error") || kind.contains("overloaded") {
+                503
+            } else {
+                400
+            }
+        }
+    };
+    let status = reqwest::StatusCode::from_u16(status).unwrap_or(reqwest::StatusCode::BAD_GATEWAY);
+    Some(status_error(provider, status, data))
+}
+
+/// The key a stream was sent with, held until the stream ends
+///
+/// Servers that report usage send it on the last chunk before `[DONE]`. It is
+/// attributed to the key at `[DONE]`, or when a stream cut short is dropped.
+#[derive(Debug)]
+struct StreamLease {
+    lease: KeyLease,
+    usage: Option<CompletionUsage>,
+    recorded: bool,
+}
+
+impl StreamLease {
+    fn new(lease: KeyLease) -> Self {
+        Self {
+            lease,
+            usage: None,
+            recorded: false,
+        }
+    }
+
+    fn observe(&mut self, chunk: &Result<ChatCompletionChunk>) {
+        match chunk {
+            Ok(chunk) => {
+                if let Some(usage) = chunk.usage {
+                    self.usage = Some(usage);
+                }
+            }
+            Err(e) => self.lease.record_error(e),
+        }
+    }
+
+    fn record_usage(&mut self) {
+        if let Some(usage) = self.usage.filter(|_| !self.recorded) {
+            self.lease
+                .record_usage(Some(usage.prompt_tokens), Some(usage.completion_tokens));
+            self.recorded = true;
+        }
+    }
+}
+
+impl Drop for StreamLease {
+    fn drop(&mut self) {
+        self.record_usage();
+    }
+}
+
+/// Convert a streamed chunk into a [`StreamChunk`]; a `finish_reason` marks the final chunk
+fn map_chunk(stream_id: Uuid, index: u32, raw: ChatCompletionChunk) -> StreamChunk {
+    let choice = raw.choices.into_iter().next();
+    let finish_reason = choice.as_ref().and_then(|c| c.finish_reason.clone());
+    let content = choice.and_then(|c| c.delta.content).unwrap_or_default();
+
+    let mut chunk = StreamChunk::new(stream_id, content, index, finish_reason.is_some());
+    if let Some(reason) = finish_reason {
+        chunk
+            .metadata
+            .provider_data
+            .insert("stop_reason".to_string(), reason.into());
+    }
+    if let Some(usage) = raw.usage {
+        chunk.metadata.tokens = Some(usage.completion_tokens);
+    }
+    chunk
+}
+
+impl ChatBackend for OpenAIBackend {
+    fn provider(&self) -> Provider {
+        self.provider
+    }
+
+    fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+        Box::pin(async move {
+            let start_time = Instant::now();
+            let temperature = request.temperature;
+            let token = request.access_token.clone();
+            let (raw, lease) = self
+                .leased_completions(&ChatCompletionRequest::from(request), token.as_ref())
+                .await?;
+            let mut response = self.to_chat_response(raw, temperature, start_time.elapsed());
+            if let Some(lease) = lease {
+                response
+                    .metadata
+                    .provider_data
+                    .insert("key_id".to_string(), lease.id().into());
+            }
+            Ok(response)
+        })
+    }
+
+    fn chat_stream(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChunkStream>> {
+        Box::pin(async move {
+            let token = request.access_token.clone();
+            let chunks = self
+                .leased_stream(&ChatCompletionRequest::from(request), token.as_ref())
+                .await?;
+            let stream_id = Uuid::new_v4();
+
+            // The chunk carrying only usage follows the final one
+            Ok(chunks
+                .filter(|chunk| {
+                    futures::future::ready(!matches!(chunk, Ok(chunk) if chunk.choices.is_empty()))
+                })
+                .enumerate()
+                .map(move |(index, chunk)| Ok(map_chunk(stream_id, index as u32, chunk?)))
+                .boxed())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::http::testing::{slow_server, sse, sse_server};
+    use crate::{CustomProvider, ProviderRegistry};
+
+    #[test]
+    fn test_request_serialization() {
+        let request = ChatCompletionRequest::from(
+            ChatRequest::new("gpt-4o")
+                .message(ChatMessage::system("Be brief."))
+                .message(ChatMessage::user("Hi"))
+                .max_tokens(64),
+        );
+
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["max_tokens"], 64);
+        assert_eq!(body["stream"], false);
+        assert!(body.get("temperature").is_none());
+        assert!(body.get("stream_options").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_timeout_spares_streams() {
+        const LINES: &[&str] = &[
+            r#"data: {"id":"c","choices":[{"index":0,"delta":{"content":"Hel"}}]}"#,
+            r#"data: {"id":"c","choices":[{"index":0,"delta":{"content":"lo"},"finish_reason":"stop"}]}"#,
+            "data: [DONE]",
+        ];
+        let url = slow_server(LINES, Duration::from_millis(150)).await;
+        let backend = OpenAIBackend::with_timeout(Provider::OpenAI, url, Duration::from_millis(200)).unwrap();
+        let request = || ChatRequest::new("gpt-4o").message(ChatMessage::user("Hi"));
+
+        // The stream takes longer than the timeout and still arrives whole
+        let chunks: Vec<_> = backend.chat_stream(request()).await.unwrap().collect().await;
+        let content: String = chunks.into_iter().map(|chunk| chunk.unwrap().content).collect();
+        assert_eq!(content, "Hello");
+
+        // A plain request waiting as long is cut off
+        let err = backend.chat(request()).await.unwrap_err();
+        assert!(matches!(err, Error::NetworkError { .. }), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_stream_records_usage_and_errors_on_pooled_key() {
+        let (url, _) = sse_server(vec![
+            sse(&[
+                r#"{"id":"c","choices":[{"index":0,"delta":{"content":"Hello"}}]}"#,
+                r#"{"id":"c","choices":[{"index":0,"delta":{},"finish_reason":"stop"}]}"#,
+                r#"{"id":"c","choices":[],"usage":{"prompt_tokens":7,"completion_tokens":3,"total_tokens":10}}"#,
+                "[DONE]",
+            ]),
+            sse(&[r#"{"error":{"message":"Rate limit reached","type":"requests","code":"rate_limit_exceeded"}}"#]),
+        ])
+        .await;
+        let keys = Arc::new(KeyPool::new("openai", [Secret::new("sk-1".to_string())]).unwrap());
+        let backend = OpenAIBackend::new(Provider::OpenAI, url)
+            .unwrap()
+            .with_key_pool(Arc::clone(&keys));
+        let request = || ChatRequest::new("gpt-4o").message(ChatMessage::user("Hi"));
+
+        let chunks: Vec<_> = backend.chat_stream(request()).await.unwrap().collect().await;
+        let chunks: Vec<_> = chunks.into_iter().map(Result::unwrap).collect();
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[1].is_final);
+        let usage = &keys.usage()[0];
+        assert_eq!((usage.input_tokens, usage.output_tokens), (7, 3));
+        assert_eq!(usage.failures, 0);
+
+        let chunks: Vec<_> = backend.chat_stream(request()).await.unwrap().collect().await;
+        assert!(chunks[0].as_ref().unwrap_err().is_rate_limit());
+        let usage = &keys.usage()[0];
+        assert_eq!(usage.failures, 1);
+        assert!(usage.quarantined);
+    }
+
+    #[test]
+    fn test_stream_error_mapping() {
+        let error = |data| stream_error(Provider::OpenAI, data).unwrap();
+        assert!(error(r#"{"error":{"message":"bad key","code":"invalid_api_key"}}"#).is_auth_error());
+        assert!(error(r#"{"error":{"message":"busy","type":"server_error"}}"#).is_retryable());
+        assert!(error(r#"{"error":{"object":"error","message":"slow down","code":429}}"#).is_rate_limit());
+        assert!(stream_error(Provider::OpenAI, r#"{"id":"c","choices":[]}"#).is_none());
+    }
+
+    #[test]
+    fn test_response_attributed_to_custom_provider() {
+        let provider = ProviderRegistry::register(
+            CustomProvider::new("openai-test-vllm", "http://localhost:8000/v1")
+                .auth(AuthScheme::Header("x-api-key".to_string())),
+        )
+        .unwrap();
+        let backend = OpenAIBackend::new(provider, "http://localhost:8000/v1/").unwrap();
+        assert_eq!(backend.auth, AuthScheme::Header("x-api-key".to_string()));
+        assert_eq!(backend.base_url(), "http://localhost:8000/v1");
+
+        let raw: ChatCompletionResponse = serde_json::from_str(
+            r#"{
+                "id": "cmpl-1",
+                "model": "qwen2.5-coder-32b",
+                "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hello"}, "finish_reason": "stop"}],
+                "usage": {"prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6}
+            }"#,
+        )
+        .unwrap();
+
+        let response = backend.to_chat_response(raw, None, Duration::from_millis(5));
+        assert_eq!(response.provider, provider);
+        assert_eq!(response.content, "Hello");
+        assert_eq!(response.tokens_used, Some(6));
+        assert_eq!(response.metadata.stop_reason.as_deref(), Some("stop"));
+    }
+
+    #[test]
+    fn test_chunk_mapping() {
+        let stream_id = Uuid::new_v4();
+        let chunk: ChatCompletionChunk =
+            serde_json::from_str(r#"{"id":"c","choices":[{"index":0,"delta":{"content":"Hi"}}]}"#).unwrap();
+        let mapped = map_chunk(stream_id, 0, chunk);
+        assert_eq!(mapped.content, "Hi");
+        assert!(!mapped.is_final);
+
+        let chunk: ChatCompletionChunk =
+            serde_json::from_str(r#"{"id":"c","choices":[{"index":0,"delta":{},"finish_reason":"length"}]}"#).unwrap();
+        let mapped = map_chunk(stream_id, 1, chunk);
+        assert!(mapped.is_final);
+        assert_eq!(mapped.metadata.provider_data["stop_reason"], "length");
+    }
+}
@@ -0,0 +1,393 @@
+//! Running many prompts with bounded concurrency
+//!
+//! [`Zeke::chat_batch`](crate::Zeke::chat_batch) and
+//! [`ZekePool::chat_batch`](crate::ZekePool::chat_batch) send every prompt of
+//! a batch, at most [`BatchOptions::with_concurrency`] at a time, and report
+//! each prompt's outcome separately so one failure does not sink the batch.
+
+use crate::{ChatResponse, Error, Result};
+use futures::{stream, Future, StreamExt};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Requests in flight at once by default
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// Order of the items in a [`BatchResult`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchOrder {
+    /// Same order as the prompts
+    #[default]
+    Input,
+    /// Order in which the requests finished
+    Completion,
+}
+
+/// Progress of a batch, reported after each prompt finishes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchProgress {
+    /// Position of the prompt that just finished
+    pub index: usize,
+    /// Prompts finished so far, including this one
+    pub completed: usize,
+    /// Prompts that failed so far
+    pub failed: usize,
+    /// Prompts in the batch
+    pub total: usize,
+}
+
+type ProgressCallback = Arc<dyn Fn(BatchProgress) + Send + Sync>;
+
+/// How a batch is run
+#[derive(Clone)]
+pub struct BatchOptions {
+    concurrency: usize,
+    timeout: Option<Duration>,
+    order: BatchOrder,
+    progress: Option<ProgressCallback>,
+}
+
+impl fmt::Debug for BatchOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchOptions")
+            .field("concurrency", &self.concurrency)
+            .field("timeout", &self.timeout)
+            .field("order", &self.order)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_BATCH_CONCURRENCY,
+            timeout: None,
+            order: BatchOrder::default(),
+            progress: None,
+        }
+    }
+}
+
+impl BatchOptions {
+    /// Default options: four requests at a time, no timeout, input order
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run at most this many requests at once; values below 1 are raised to 1
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Fail any single prompt that takes longer than this
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Choose the order of the returned items
+    pub fn with_order(mut self, order: BatchOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Call `callback` each time a prompt finishes
+    ///
+    /// With [`BatchOrder::Input`] a finished prompt is reported once every
+    /// prompt before it has finished too.
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(BatchProgress) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+}
+
+/// The outcome of one prompt
+#[derive(Debug)]
+pub struct BatchItem {
+    /// Position of the prompt in the batch
+    pub index: usize,
+    /// The response, or why there is none
+    pub result: Result<ChatResponse>,
+}
+
+/// Totals for a finished batch
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchSummary {
+    /// Prompts in the batch
+    pub total: usize,
+    /// Prompts that produced a response
+    pub succeeded: usize,
+    /// Prompts that failed
+    pub failed: usize,
+    /// Failures by [`Error::category`]
+    pub failures_by_category: BTreeMap<&'static str, usize>,
+    /// Tokens reported across all responses
+    pub tokens_used: u64,
+    /// Wall-clock time for the whole batch
+    pub elapsed: Duration,
+}
+
+impl BatchSummary {
+    fn record(&mut self, result: &Result<ChatResponse>) {
+        match result {
+            Ok(response) => {
+                self.succeeded += 1;
+                self.tokens_used += u64::from(response.tokens_used.unwrap_or(0));
+            }
+            Err(e) => {
+                self.failed += 1;
+                *self.failures_by_category.entry(e.category()).or_default() += 1;
+            }
+        }
+    }
+
+    /// Prompts finished per second
+    pub fn throughput(&self) -> f64 {
+        per_second(self.succeeded + self.failed, self.elapsed)
+    }
+
+    /// Tokens generated per second
+    pub fn tokens_per_second(&self) -> f64 {
+        per_second(self.tokens_used as usize, self.elapsed)
+    }
+}
+
+fn per_second(count: usize, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 { count as f64 / secs } else { 0.0 }
+}
+
+/// Every item of a batch plus its totals
+#[derive(Debug)]
+pub struct BatchResult {
+    /// One item per prompt, ordered by [`BatchOptions::with_order`]
+    pub items: Vec<BatchItem>,
+    /// Totals across all items
+    pub summary: BatchSummary,
+}
+
+impl BatchResult {
+    /// Responses that succeeded, with their prompt positions
+    pub fn successes(&self) -> impl Iterator<Item = (usize, &ChatResponse)> {
+        self.items
+            .iter()
+            .filter_map(|item| item.result.as_ref().ok().map(|r| (item.index, r)))
+    }
+
+    /// Errors of the prompts that failed, with their prompt positions
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &Error)> {
+        self.items
+            .iter()
+            .filter_map(|item| item.result.as_ref().err().map(|e| (item.index, e)))
+    }
+}
+
+/// Run `chat` for every prompt under `options`
+pub(crate) async fn run<F, Fut>(prompts: Vec<String>, options: &BatchOptions, mut chat: F) -> BatchResult
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<ChatResponse>>,
+{
+    let total = prompts.len();
+    let timeout = options.timeout;
+    let start = Instant::now();
+    debug!("Running batch of {} prompts, {} at a time", total, options.concurrency);
+
+    let requests = prompts.into_iter().enumerate().map(|(index, prompt)| {
+        let request = chat(prompt);
+        async move {
+            let result = match timeout {
+                Some(limit) => tokio::time::timeout(limit, request)
+                    .await
+                    .unwrap_or_else(|_| Err(Error::network(format!("timed out after {:?}", limit)))),
+                None => request.await,
+            };
+            BatchItem { index, result }
+        }
+    });
+
+    let mut items = Vec::with_capacity(total);
+    let mut summary = BatchSummary {
+        total,
+        ..Default::default()
+    };
+    let mut finish = |item: BatchItem| {
+        summary.record(&item.result);
+        if let Some(progress) = &options.progress {
+            progress(BatchProgress {
+                index: item.index,
+                completed: summary.succeeded + summary.failed,
+                failed: summary.failed,
+                total,
+            });
+        }
+        items.push(item);
+    };
+
+    let requests = stream::iter(requests);
+    match options.order {
+        BatchOrder::Input => {
+            let mut finished = requests.buffered(options.concurrency);
+            while let Some(item) = finished.next().await {
+                finish(item);
+            }
+        }
+        BatchOrder::Completion => {
+            let mut finished = requests.buffer_unordered(options.concurrency);
+            while let Some(item) = finished.next().await {
+                finish(item);
+            }
+        }
+    }
+
+    summary.elapsed = start.elapsed();
+    debug!(
+        "Batch finished: {} succeeded, {} failed in {:?}",
+        summary.succeeded, summary.failed, summary.elapsed
+    );
+    BatchResult { items, summary }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Provider;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    fn response(content: &str, tokens: u32) -> ChatResponse {
+        ChatResponse::new(
+            content.to_string(),
+            Provider::Ollama,
+            "test-model".to_string(),
+            Some(tokens),
+            Duration::ZERO,
+        )
+    }
+
+    /// Answers "fail" prompts with an error, others after `delay_ms` each
+    async fn fake_chat(prompt: String) -> Result<ChatResponse> {
+        let (text, delay) = prompt.split_once(':').unwrap();
+        tokio::time::sleep(Duration::from_millis(delay.parse().unwrap())).await;
+        match text {
+            "fail" => Err(Error::network("connection reset")),
+            _ => Ok(response(text, 10)),
+        }
+    }
+
+    fn prompts(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_input_order_and_summary() {
+        let batch = run(
+            prompts(&["a:30", "fail:0", "c:10"]),
+            &BatchOptions::new(),
+            fake_chat,
+        )
+        .await;
+
+        let indexes: Vec<usize> = batch.items.iter().map(|item| item.index).collect();
+        assert_eq!(indexes, [0, 1, 2]);
+        assert_eq!(batch.summary.total, 3);
+        assert_eq!(batch.summary.succeeded, 2);
+        assert_eq!(batch.summary.failed, 1);
+        assert_eq!(batch.summary.failures_by_category.get("network"), Some(&1));
+        assert_eq!(batch.summary.tokens_used, 20);
+        assert_eq!(batch.failures().map(|(i, _)| i).collect::<Vec<_>>(), [1]);
+        assert!(batch.summary.throughput() > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_completion_order() {
+        let options = BatchOptions::new().with_order(BatchOrder::Completion);
+        let batch = run(prompts(&["slow:60", "fast:0"]), &options, fake_chat).await;
+
+        let contents: Vec<&str> = batch.successes().map(|(_, r)| r.content.as_str()).collect();
+        assert_eq!(contents, ["fast", "slow"]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let options = BatchOptions::new().with_concurrency(2);
+        let batch = run(prompts(&["a:10"; 6]), &options, |prompt| {
+            let in_flight = Arc::clone(&in_flight);
+            let peak = Arc::clone(&peak);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                let result = fake_chat(prompt).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                result
+            }
+        })
+        .await;
+
+        assert_eq!(batch.summary.succeeded, 6);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_fails_single_item() {
+        let options = BatchOptions::new().with_timeout(Duration::from_millis(20));
+        let batch = run(prompts(&["a:0", "b:500"]), &options, fake_chat).await;
+
+        assert!(batch.items[0].result.is_ok());
+        let err = batch.items[1].result.as_ref().unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_timeout_fires_during_blocking_core_call() {
+        // Core calls block their thread; on the only worker this would stall
+        // the timer unless the call runs on the blocking pool, as Zeke::chat does
+        let options = BatchOptions::new().with_timeout(Duration::from_millis(50));
+        let batch = run(prompts(&["slow"]), &options, |prompt| {
+            crate::ffi_utils::run_blocking(move || {
+                std::thread::sleep(Duration::from_millis(500));
+                Ok(response(&prompt, 1))
+            })
+        })
+        .await;
+
+        let err = batch.items[0].result.as_ref().unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        assert!(batch.summary.elapsed < Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn test_progress_callback() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&seen);
+        let options = BatchOptions::new().on_progress(move |progress| {
+            recorder.lock().unwrap().push(progress);
+        });
+
+        run(prompts(&["a:0", "fail:0", "c:0"]), &options, fake_chat).await;
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 3);
+        assert_eq!(seen[2].completed, 3);
+        assert_eq!(seen[2].failed, 1);
+        assert!(seen.iter().all(|p| p.total == 3));
+    }
+
+    #[tokio::test]
+    async fn test_empty_batch() {
+        let batch = run(Vec::new(), &BatchOptions::new(), fake_chat).await;
+        assert!(batch.items.is_empty());
+        assert_eq!(batch.summary.throughput(), 0.0);
+    }
+}
@@ -0,0 +1,8 @@
+//! Hooks for the benchmarks in `benches/`
+//!
+//! They run the real FFI callbacks and [`RawResponse`](crate::RawResponse)
+//! paths over a buffer shaped like the core's, without a provider.
+
+pub use crate::ffi_utils::harness::{drive_c_stream, drive_stream};
+pub use crate::raw::with_core_buffer;
+pub use crate::zeke::{collect_arena, collect_strings};
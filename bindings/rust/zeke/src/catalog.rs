@@ -0,0 +1,209 @@
+//! Model capability catalog
+//!
+//! Describes individual models: context window, output limit, capabilities,
+//! pricing and deprecation. The built-in catalog is embedded from
+//! `data/models.json`; entries can be added or overridden at runtime or
+//! through [`Config::models`](crate::Config::models).
+
+use crate::{Provider, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{LazyLock, RwLock};
+
+/// Built-in catalog data
+const BUILTIN_MODELS: &str = include_str!("../data/models.json");
+
+/// A feature a model may support
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// Function/tool calling
+    Tools,
+    /// Image input
+    Vision,
+    /// Guaranteed JSON output
+    JsonMode,
+    /// Streaming responses
+    Streaming,
+    /// Extended reasoning before answering
+    Reasoning,
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Capability::Tools => write!(f, "tools"),
+            Capability::Vision => write!(f, "vision"),
+            Capability::JsonMode => write!(f, "JSON mode"),
+            Capability::Streaming => write!(f, "streaming"),
+            Capability::Reasoning => write!(f, "reasoning"),
+        }
+    }
+}
+
+/// Price per million tokens in US dollars
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pricing {
+    /// Price of one million input tokens
+    pub input_per_mtok: f64,
+    /// Price of one million output tokens
+    pub output_per_mtok: f64,
+}
+
+/// Capabilities and limits of a single model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// Provider serving the model
+    pub provider: Provider,
+    /// Model identifier as sent to the provider
+    pub id: String,
+    /// Context window in tokens
+    pub context_length: u32,
+    /// Maximum tokens the model can generate per response
+    pub max_output_tokens: u32,
+    /// Supported capabilities
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+    /// Token pricing (absent for local models)
+    #[serde(default)]
+    pub pricing: Option<Pricing>,
+    /// Date (`YYYY-MM-DD`) the provider retires or retired the model
+    #[serde(default)]
+    pub deprecated_on: Option<String>,
+}
+
+impl ModelInfo {
+    /// Check whether the model supports a capability
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    /// Check whether the model has a retirement date
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated_on.is_some()
+    }
+
+    /// Estimated cost in US dollars of a request, if pricing is known
+    pub fn estimate_cost(&self, input_tokens: u32, output_tokens: u32) -> Option<f64> {
+        self.pricing.map(|p| {
+            (input_tokens as f64 * p.input_per_mtok + output_tokens as f64 * p.output_per_mtok)
+                / 1_000_000.0
+        })
+    }
+}
+
+type CatalogKey = (Provider, String);
+
+static CATALOG: LazyLock<RwLock<HashMap<CatalogKey, ModelInfo>>> = LazyLock::new(|| {
+    let models: Vec<ModelInfo> =
+        serde_json::from_str(BUILTIN_MODELS).expect("embedded data/models.json is valid");
+    RwLock::new(
+        models
+            .into_iter()
+            .map(|info| ((info.provider, info.id.clone()), info))
+            .collect(),
+    )
+});
+
+/// Process-wide model catalog, seeded with the built-in data
+#[derive(Debug, Clone, Copy)]
+pub struct ModelCatalog;
+
+impl ModelCatalog {
+    /// Look up a model, ignoring an Ollama-style `:latest` tag
+    pub fn get(provider: Provider, model: &str) -> Option<ModelInfo> {
+        let catalog = CATALOG.read().unwrap_or_else(|e| e.into_inner());
+        catalog
+            .get(&(provider, model.to_string()))
+            .or_else(|| {
+                let base = model.strip_suffix(":latest")?;
+                catalog.get(&(provider, base.to_string()))
+            })
+            .cloned()
+    }
+
+    /// Add or replace a model entry
+    pub fn register(info: ModelInfo) {
+        let mut catalog = CATALOG.write().unwrap_or_else(|e| e.into_inner());
+        catalog.insert((info.provider, info.id.clone()), info);
+    }
+
+    /// Add or replace entries from a JSON array in the `data/models.json` format
+    pub fn load_json(json: &str) -> Result<usize> {
+        let models: Vec<ModelInfo> = serde_json::from_str(json)?;
+        let count = models.len();
+        models.into_iter().for_each(Self::register);
+        Ok(count)
+    }
+
+    /// All known models of a provider, sorted by identifier
+    pub fn models(provider: Provider) -> Vec<ModelInfo> {
+        let catalog = CATALOG.read().unwrap_or_else(|e| e.into_inner());
+        let mut models: Vec<ModelInfo> = catalog
+            .values()
+            .filter(|info| info.provider == provider)
+            .cloned()
+            .collect();
+        models.sort_by(|a, b| a.id.cmp(&b.id));
+        models
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_catalog_covers_default_models() {
+        for provider in [
+            Provider::OpenAI,
+            Provider::Claude,
+            Provider::Ollama,
+            Provider::XAI,
+            Provider::Google,
+            Provider::Azure,
+        ] {
+            let info = provider.model_info(provider.default_model());
+            assert!(info.is_some(), "no catalog entry for {} default model", provider);
+        }
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let gpt4o = Provider::OpenAI.model_info("gpt-4o").unwrap();
+        assert!(gpt4o.supports(Capability::Tools));
+        assert!(gpt4o.supports(Capability::Vision));
+        assert_eq!(gpt4o.max_output_tokens, 16384);
+
+        let gpt4 = Provider::OpenAI.model_info("gpt-4").unwrap();
+        assert!(!gpt4.supports(Capability::Vision));
+
+        assert!(Provider::Claude.model_info("claude-3-opus-20240229").unwrap().is_deprecated());
+        assert!(Provider::Ollama.model_info("llama3:latest").is_some());
+        assert!(Provider::OpenAI.model_info("no-such-model").is_none());
+    }
+
+    #[test]
+    fn test_estimate_cost() {
+        let info = Provider::OpenAI.model_info("gpt-4o").unwrap();
+        let cost = info.estimate_cost(1_000_000, 100_000).unwrap();
+        assert!((cost - 3.5).abs() < 1e-9);
+
+        assert_eq!(Provider::Ollama.model_info("llama3").unwrap().estimate_cost(10, 10), None);
+    }
+
+    #[test]
+    fn test_register_override() {
+        let count = ModelCatalog::load_json(
+            r#"[{"provider": "omen", "id": "catalog-test", "context_length": 4096,
+                 "max_output_tokens": 1024, "capabilities": ["streaming"]}]"#,
+        )
+        .unwrap();
+        assert_eq!(count, 1);
+
+        let info = ModelCatalog::get(Provider::Omen, "catalog-test").unwrap();
+        assert!(info.supports(Capability::Streaming));
+        assert!(ModelCatalog::models(Provider::Omen).contains(&info));
+    }
+}
@@ -1,6 +1,15 @@
 //! Configuration management for Zeke
 
-use crate::{Error, Provider, Result};
+use crate::{
+    catalog::{Capability, ModelCatalog, ModelInfo},
+    credentials::{CredentialChain, CredentialStore},
+    discovery::ModelCache,
+    keys::{KeyPool, KeySelection},
+    layers::{self, ConfigExplanation, ConfigSource, Layer, Provenance},
+    registry::{CustomProvider, ProviderRegistry},
+    routing::{Router, RoutingPolicy},
+    Error, Provider, Result,
+};
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -11,6 +20,10 @@ use url::Url;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// AI provider to use
+    ///
+    /// May name one of [`custom_providers`](Self::custom_providers), which
+    /// [`validate`](Self::validate) checks.
+    #[serde(deserialize_with = "crate::provider::deserialize_declared")]
     pub provider: Provider,
     
     /// Base URL for API requests
@@ -19,6 +32,20 @@ pub struct Config {
     /// API key for authentication (stored securely)
     #[serde(skip)]
     pub api_key: Option<Secret<String>>,
+
+    /// Further API keys for the same provider, rotated through a [`KeyPool`]
+    #[serde(skip)]
+    pub api_keys: Vec<Secret<String>>,
+
+    /// How the key pool picks a key for each request
+    #[serde(default)]
+    pub key_selection: KeySelection,
+
+    /// Name of the stored credential holding the API key
+    ///
+    /// Saved in place of the key itself; see [`Config::load_credential`].
+    #[serde(default)]
+    pub credential: Option<String>,
     
     /// Model name to use
     pub model: String,
@@ -33,6 +60,9 @@ pub struct Config {
     pub streaming: bool,
     
     /// Enable GPU acceleration (where supported)
+    ///
+    /// No current core build supports it; instances turn it off when the
+    /// library lacks GhostLLM.
     pub enable_gpu: bool,
     
     /// Enable automatic failover to backup providers
@@ -43,6 +73,22 @@ pub struct Config {
     
     /// Provider-specific settings
     pub provider_settings: HashMap<String, serde_json::Value>,
+
+    /// User-defined OpenAI-compatible providers
+    #[serde(default)]
+    pub custom_providers: Vec<CustomProvider>,
+
+    /// Model catalog additions and overrides
+    #[serde(default)]
+    pub models: Vec<ModelInfo>,
+
+    /// Constraints for automatic model selection
+    #[serde(default)]
+    pub routing: RoutingPolicy,
+
+    /// Where each field's value came from
+    #[serde(skip)]
+    pub provenance: Provenance,
 }
 
 impl Default for Config {
@@ -51,6 +97,9 @@ impl Default for Config {
             provider: Provider::OpenAI,
             base_url: None,
             api_key: None,
+            api_keys: Vec::new(),
+            key_selection: KeySelection::default(),
+            credential: None,
             model: "gpt-4o".to_string(),
             temperature: 0.7,
             max_tokens: 2048,
@@ -59,6 +108,10 @@ impl Default for Config {
             enable_fallback: true,
             timeout_ms: 30000,
             provider_settings: HashMap::new(),
+            custom_providers: Vec::new(),
+            models: Vec::new(),
+            routing: RoutingPolicy::default(),
+            provenance: Provenance::default(),
         }
     }
 }
@@ -74,26 +127,73 @@ impl Config {
         ConfigBuilder::new()
     }
 
-    /// Load configuration from a TOML file
+    /// Load configuration from a file
+    ///
+    /// Accepts a flat `Config` in TOML or JSON, `config.example.json` and the
+    /// CLI's `zeke.toml` layout. Fields the file does not set keep their
+    /// defaults; use [`ConfigLoader`](crate::ConfigLoader) to merge several files.
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| Error::ConfigError {
-                message: format!("Failed to read config file: {}", e),
-            })?;
-        
-        let mut config: Config = toml::from_str(&content)
-            .map_err(|e| Error::ConfigError {
-                message: format!("Failed to parse TOML config: {}", e),
-            })?;
-        
+        let path = path.as_ref();
+        let layer = Layer::from_path(path)?;
+        let mut config = layers::resolve(&[(ConfigSource::File(path.to_path_buf()), layer)])?;
+        config.finish_loading()?;
+        Ok(config)
+    }
+
+    /// Register definitions and pick up the API key after loading from a file
+    pub(crate) fn finish_loading(&mut self) -> Result<()> {
+        self.register_custom_providers()?;
+        self.register_models();
+
         // Try to load API key from environment if not set
-        if config.api_key.is_none() {
-            if let Some(key) = Self::get_api_key_from_env(config.provider) {
-                config.api_key = Some(Secret::new(key));
+        if self.api_key.is_none() {
+            self.load_api_key_from_env();
+        }
+        self.load_referenced_credential()?;
+
+        Ok(())
+    }
+
+    /// Name to look the API key up under in a credential store
+    ///
+    /// This is [`credential`](Self::credential) if set, otherwise the name the
+    /// Zig CLI uses for the provider.
+    pub fn credential_name(&self) -> &str {
+        self.credential
+            .as_deref()
+            .unwrap_or_else(|| self.provider.credential_name())
+    }
+
+    /// Load the API key from a credential store
+    ///
+    /// Returns whether the store had the credential; if not, the current key
+    /// is left unchanged.
+    pub fn load_credential(&mut self, store: &dyn CredentialStore) -> Result<bool> {
+        let Some(secret) = store.get(self.credential_name())? else {
+            return Ok(false);
+        };
+        self.api_key = Some(secret);
+        self.provenance
+            .record("api_key", ConfigSource::CredentialStore(store.name().to_string()));
+        Ok(true)
+    }
+
+    /// Resolve an explicitly named credential through the standard stores
+    pub(crate) fn load_referenced_credential(&mut self) -> Result<()> {
+        if self.api_key.is_none() && self.credential.is_some() {
+            let chain = CredentialChain::standard();
+            if let Some((store, secret)) = chain.find(self.credential_name())? {
+                self.api_key = Some(secret);
+                self.provenance
+                    .record("api_key", ConfigSource::CredentialStore(store.to_string()));
             }
         }
-        
-        Ok(config)
+        Ok(())
+    }
+
+    /// Report each field's value and the layer it came from, with secrets redacted
+    pub fn explain(&self) -> ConfigExplanation {
+        layers::explain(self)
     }
 
     /// Save configuration to a TOML file (API key is excluded)
@@ -119,8 +219,46 @@ impl Config {
             .clone()
     }
 
+    /// Register this config's custom providers with the [`ProviderRegistry`]
+    pub fn register_custom_providers(&self) -> Result<()> {
+        for definition in &self.custom_providers {
+            ProviderRegistry::register(definition.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Register this config's model entries with the [`ModelCatalog`]
+    pub fn register_models(&self) {
+        for info in &self.models {
+            ModelCatalog::register(info.clone());
+        }
+    }
+
+    /// Catalog entry for the configured model, preferring this config's overrides
+    pub fn model_info(&self) -> Option<ModelInfo> {
+        self.models
+            .iter()
+            .find(|info| info.provider == self.provider && info.id == self.model)
+            .cloned()
+            .or_else(|| self.provider.model_info(&self.model))
+    }
+
     /// Validate the configuration
     pub fn validate(&self) -> Result<()> {
+        // Validate custom provider definitions
+        for definition in &self.custom_providers {
+            definition.validate()?;
+        }
+        if let Provider::Custom(name) = self.provider {
+            let defined = self.custom_providers.iter().any(|d| d.name == name)
+                || ProviderRegistry::get(name).is_some();
+            if !defined {
+                return Err(Error::ConfigError {
+                    message: format!("Custom provider '{}' is not defined", name),
+                });
+            }
+        }
+
         // Validate base URL
         if let Some(ref url) = self.base_url {
             Url::parse(url).map_err(|e| Error::ConfigError {
@@ -150,7 +288,7 @@ impl Config {
         }
 
         // Check if API key is required but missing
-        if self.provider.requires_api_key() && self.api_key.is_none() {
+        if self.provider.requires_api_key() && self.api_key.is_none() && self.api_keys.is_empty() {
             return Err(Error::ConfigError {
                 message: format!(
                     "API key is required for provider {}",
@@ -159,14 +297,35 @@ impl Config {
             });
         }
 
-        // Validate model for provider
-        let valid_models = self.provider.default_models();
-        if !valid_models.is_empty() && !valid_models.contains(&self.model.as_str()) {
+        // Reject settings the model cannot honour
+        if let Some(info) = self.model_info() {
+            if self.max_tokens > info.max_output_tokens {
+                return Err(Error::ConfigError {
+                    message: format!(
+                        "Max tokens {} exceeds the output limit of {} ({} tokens)",
+                        self.max_tokens, info.id, info.max_output_tokens
+                    ),
+                });
+            }
+            if self.streaming && !info.capabilities.is_empty() && !info.supports(Capability::Streaming) {
+                return Err(Error::ConfigError {
+                    message: format!("Model {} does not support streaming", info.id),
+                });
+            }
+            if let Some(date) = &info.deprecated_on {
+                tracing::warn!("Model '{}' is deprecated as of {}", info.id, date);
+            }
+        }
+
+        // Validate model against the discovered (or built-in) model list
+        let known = ModelCache::default().known_models(self.provider, &self.effective_base_url());
+        if !known.models.is_empty() && !known.contains(&self.model) {
             tracing::warn!(
-                "Model '{}' may not be valid for provider {}. Valid models: {:?}",
+                "Model '{}' may not be valid for provider {}. Known models ({:?}): {:?}",
                 self.model,
                 self.provider.display_name(),
-                valid_models
+                known.source,
+                known.models
             );
         }
 
@@ -180,28 +339,60 @@ impl Config {
 
     /// Get API key (use carefully)
     pub fn api_key(&self) -> Option<&str> {
-        self.api_key.as_ref().map(|k| k.expose_secret())
+        self.api_key.as_ref().map(|k| k.expose_secret().as_str())
+    }
+
+    /// Pool of every configured API key, [`api_key`](Self::api_key) first
+    ///
+    /// Returns `None` if no key is configured.
+    pub fn key_pool(&self) -> Option<KeyPool> {
+        let keys = self.api_key.iter().chain(&self.api_keys).cloned();
+        KeyPool::new(self.provider.identifier(), keys)
+            .ok()
+            .map(|pool| pool.with_selection(self.key_selection))
     }
 
     /// Try to get API key from environment variables
-    fn get_api_key_from_env(provider: Provider) -> Option<String> {
+    pub(crate) fn get_api_key_from_env(provider: Provider) -> Option<String> {
+        Self::api_key_env_var(provider).map(|(_, key)| key)
+    }
+
+    /// Set the API key from the environment, recording the variable it came from
+    pub(crate) fn load_api_key_from_env(&mut self) -> bool {
+        match Self::api_key_env_var(self.provider) {
+            Some((var, key)) => {
+                self.api_key = Some(Secret::new(key));
+                self.provenance.record("api_key", ConfigSource::Environment(var));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// First non-empty API key variable for a provider, with its name
+    pub(crate) fn api_key_env_var(provider: Provider) -> Option<(String, String)> {
+        let custom_vars = provider
+            .custom_definition()
+            .map(|d| d.api_key_env_vars())
+            .unwrap_or_default();
+
         let env_vars = match provider {
             Provider::OpenAI => vec!["OPENAI_API_KEY", "OPENAI_KEY"],
             Provider::Claude => vec!["ANTHROPIC_API_KEY", "CLAUDE_API_KEY"],
             Provider::Copilot => vec!["GITHUB_TOKEN", "COPILOT_TOKEN"],
             Provider::GhostLLM => vec!["GHOSTLLM_API_KEY", "GHOST_API_KEY"],
+            Provider::XAI => vec!["XAI_API_KEY", "GROK_API_KEY"],
+            Provider::Google => vec!["GOOGLE_API_KEY", "GEMINI_API_KEY"],
+            Provider::Azure => vec!["AZURE_OPENAI_API_KEY", "AZURE_API_KEY"],
+            Provider::Omen => vec!["OMEN_API_KEY", "ZEKE_API_KEY"],
             Provider::Ollama => vec![], // No API key needed
+            Provider::Custom(_) => custom_vars.iter().map(String::as_str).collect(),
         };
 
-        for var in env_vars {
-            if let Ok(key) = std::env::var(var) {
-                if !key.is_empty() {
-                    return Some(key);
-                }
-            }
-        }
-
-        None
+        env_vars.into_iter().find_map(|var| match std::env::var(var) {
+            Ok(key) if !key.is_empty() => Some((var.to_string(), key)),
+            _ => None,
+        })
     }
 
     /// Get provider-specific setting
@@ -240,6 +431,7 @@ impl Config {
         // Clear API key if switching to provider that doesn't need one
         if !provider.requires_api_key() {
             config.api_key = None;
+            config.api_keys.clear();
         }
         
         config
@@ -250,82 +442,119 @@ impl Config {
 #[derive(Debug)]
 pub struct ConfigBuilder {
     config: Config,
+    router: Option<Router>,
 }
 
 impl ConfigBuilder {
     /// Create a new config builder
     pub fn new() -> Self {
+        Self::from_config(Config::default())
+    }
+
+    /// Start from an existing configuration, such as one from [`ConfigLoader`](crate::ConfigLoader)
+    pub fn from_config(config: Config) -> Self {
         Self {
-            config: Config::default(),
+            config,
+            router: None,
         }
     }
 
+    /// Record that a field was set explicitly
+    fn touch(mut self, field: &str) -> Self {
+        self.config.provenance.record(field, ConfigSource::Builder);
+        self
+    }
+
     /// Set the AI provider
     pub fn provider(mut self, provider: Provider) -> Self {
         self.config.provider = provider;
         // Auto-set default model for provider
         self.config.model = provider.default_model().to_string();
-        self
+        self.touch("provider").touch("model")
     }
 
     /// Set the base URL
     pub fn base_url<S: Into<String>>(mut self, url: S) -> Self {
         self.config.base_url = Some(url.into());
-        self
+        self.touch("base_url")
     }
 
     /// Set the API key
     pub fn api_key<S: Into<String>>(mut self, key: S) -> Self {
         self.config.api_key = Some(Secret::new(key.into()));
-        self
+        self.touch("api_key")
+    }
+
+    /// Add API keys to rotate through alongside [`api_key`](Self::api_key)
+    pub fn api_keys<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config
+            .api_keys
+            .extend(keys.into_iter().map(|key| Secret::new(key.into())));
+        self.touch("api_keys")
+    }
+
+    /// Choose how pooled API keys are picked
+    pub fn key_selection(mut self, selection: KeySelection) -> Self {
+        self.config.key_selection = selection;
+        self.touch("key_selection")
+    }
+
+    /// Look the API key up under this name in the credential stores
+    pub fn credential<S: Into<String>>(mut self, name: S) -> Self {
+        self.config.credential = Some(name.into());
+        self.touch("credential")
     }
 
-    /// Set the model name
+    /// Set the model name, or a routing alias resolved by [`router`](Self::router)
     pub fn model<S: Into<String>>(mut self, model: S) -> Self {
         self.config.model = model.into();
-        self
+        self.touch("model")
     }
 
     /// Set the temperature
     pub fn temperature(mut self, temperature: f32) -> Self {
         self.config.temperature = temperature;
-        self
+        self.touch("temperature")
     }
 
     /// Set the maximum tokens
     pub fn max_tokens(mut self, max_tokens: u32) -> Self {
         self.config.max_tokens = max_tokens;
-        self
+        self.touch("max_tokens")
     }
 
     /// Enable or disable streaming
     pub fn streaming(mut self, streaming: bool) -> Self {
         self.config.streaming = streaming;
-        self
+        self.touch("streaming")
     }
 
     /// Enable or disable GPU acceleration
     pub fn gpu(mut self, enable_gpu: bool) -> Self {
         self.config.enable_gpu = enable_gpu;
-        self
+        self.touch("enable_gpu")
     }
 
     /// Enable or disable fallback providers
     pub fn fallback(mut self, enable_fallback: bool) -> Self {
         self.config.enable_fallback = enable_fallback;
-        self
+        self.touch("enable_fallback")
     }
 
     /// Set request timeout in milliseconds
     pub fn timeout_ms(mut self, timeout_ms: u32) -> Self {
         self.config.timeout_ms = timeout_ms;
-        self
+        self.touch("timeout_ms")
     }
 
     /// Set request timeout in seconds
     pub fn timeout_secs(mut self, timeout_secs: u32) -> Self {
         self.config.timeout_ms = timeout_secs * 1000;
-        self
+        self.touch("timeout_ms")
     }
 
     /// Add a provider-specific setting
@@ -334,26 +563,57 @@ impl ConfigBuilder {
         T: Serialize,
     {
         self.config.set_provider_setting(key, value)?;
-        Ok(self)
+        Ok(self.touch("provider_settings"))
+    }
+
+    /// Define a custom OpenAI-compatible provider
+    pub fn custom_provider(mut self, definition: CustomProvider) -> Self {
+        self.config.custom_providers.push(definition);
+        self.touch("custom_providers")
+    }
+
+    /// Add or override a model catalog entry
+    pub fn model_info(mut self, info: ModelInfo) -> Self {
+        self.config.models.push(info);
+        self.touch("models")
+    }
+
+    /// Set the constraints for automatic model selection
+    pub fn routing_policy(mut self, policy: RoutingPolicy) -> Self {
+        self.config.routing = policy;
+        self.touch("routing")
+    }
+
+    /// Resolve model aliases such as `code-fast` through a router
+    pub fn router(mut self, router: Router) -> Self {
+        self.router = Some(router);
+        self
     }
 
     /// Load API key from environment variable
     pub fn api_key_from_env(mut self) -> Self {
-        if let Some(key) = Config::get_api_key_from_env(self.config.provider) {
-            self.config.api_key = Some(Secret::new(key));
-        }
+        self.config.load_api_key_from_env();
         self
     }
 
     /// Build the configuration
     pub fn build(self) -> Result<Config> {
-        // Try to get API key from environment if not set
         let mut config = self.config;
+        config.register_custom_providers()?;
+        config.register_models();
+
+        // Replace a routing alias with its provider and model
+        if let Some((provider, model)) = self.router.and_then(|r| r.model(&config.model)) {
+            config.provider = provider;
+            config.model = model;
+            config.provenance.record("provider", ConfigSource::Builder);
+        }
+
+        // Try to get API key from environment if not set
         if config.api_key.is_none() {
-            if let Some(key) = Config::get_api_key_from_env(config.provider) {
-                config.api_key = Some(Secret::new(key));
-            }
+            config.load_api_key_from_env();
         }
+        config.load_referenced_credential()?;
 
         // Validate the configuration
         config.validate()?;
@@ -396,6 +656,31 @@ mod tests {
         assert_eq!(config.model, "claude-3-5-sonnet-20241022");
     }
 
+    #[test]
+    fn test_builder_resolves_routing_alias() {
+        let router = Router::from_json(
+            r#"{"aliases": {"code-fast": {"provider": "ollama", "model": "deepseek-coder:14b"}}}"#,
+        )
+        .unwrap();
+
+        let config = Config::builder()
+            .router(router.clone())
+            .model("code-fast")
+            .build()
+            .unwrap();
+        assert_eq!(config.provider, Provider::Ollama);
+        assert_eq!(config.model, "deepseek-coder:14b");
+
+        // Plain model names pass through untouched
+        let config = Config::builder()
+            .provider(Provider::Ollama)
+            .router(router)
+            .model("llama3")
+            .build()
+            .unwrap();
+        assert_eq!(config.model, "llama3");
+    }
+
     #[test]
     fn test_config_validation() {
         let mut config = Config::default();
@@ -452,4 +737,91 @@ mod tests {
         // Model should change to Claude's default
         assert_eq!(claude_config.model, "claude-3-5-sonnet-20241022");
     }
+
+    #[test]
+    fn test_validate_against_model_limits() {
+        let mut config = Config::builder()
+            .provider(Provider::Ollama)
+            .model("llama3")
+            .build()
+            .unwrap();
+
+        config.max_tokens = 8192;
+        assert!(config.validate().is_ok());
+        config.max_tokens = 8193;
+        assert!(config.validate().is_err());
+
+        // Config overrides take precedence over the built-in catalog
+        config.models.push(ModelInfo {
+            provider: Provider::Ollama,
+            id: "llama3".to_string(),
+            context_length: 8192,
+            max_output_tokens: 16384,
+            capabilities: vec![Capability::Streaming],
+            pricing: None,
+            deprecated_on: None,
+        });
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_custom_provider_config() {
+        let config: Config = toml::from_str(
+            r#"
+            provider = "cfg-vllm"
+            model = "qwen2.5-coder-32b"
+            temperature = 0.2
+            max_tokens = 4096
+            streaming = true
+            enable_gpu = false
+            enable_fallback = false
+            timeout_ms = 60000
+            provider_settings = {}
+
+            [[custom_providers]]
+            name = "cfg-vllm"
+            base_url = "http://localhost:8000/v1"
+            models = ["qwen2.5-coder-32b"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.provider, Provider::Custom("cfg-vllm"));
+        assert!(config.validate().is_ok());
+
+        config.register_custom_providers().unwrap();
+        assert_eq!(config.effective_base_url(), "http://localhost:8000/v1");
+        assert!(config.provider.is_local());
+
+        let undefined = Config {
+            provider: Provider::custom("cfg-missing").unwrap(),
+            ..Config::default()
+        };
+        assert!(undefined.validate().is_err());
+    }
+
+    #[test]
+    fn test_key_pool() {
+        let config = Config::builder()
+            .provider(Provider::OpenAI)
+            .api_key("sk-one")
+            .api_keys(["sk-two", "sk-one"])
+            .key_selection(KeySelection::LeastUsed)
+            .build()
+            .unwrap();
+        let pool = config.key_pool().unwrap();
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.key_ids()[0], crate::key_id("sk-one"));
+
+        let toml = crate::ZekeToml::parse(
+            "[providers.openai]\napi_keys = [\"sk-a\", \"sk-b\"]\nkey_selection = \"least-used\"\n",
+        )
+        .unwrap();
+        let config = toml.to_config_for(Provider::OpenAI).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.key_selection, KeySelection::LeastUsed);
+        assert_eq!(config.key_pool().unwrap().len(), 2);
+
+        assert!(Config::default().key_pool().is_none());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,603 @@
+//! Credential storage
+//!
+//! API keys are looked up by name in a [`CredentialStore`] instead of being
+//! written into configuration files. Stores are available for environment
+//! variables, the freedesktop Secret Service (shared with the Zig CLI's
+//! `zeke auth set-key`) and, with the `credential-file` feature, a
+//! passphrase-encrypted local file.
+
+use crate::{Config, Error, Provider, Result};
+use secrecy::Secret;
+use std::fmt;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+#[cfg(feature = "credential-file")]
+pub use file::EncryptedFileStore;
+
+/// A place API keys can be read from and saved to
+pub trait CredentialStore: fmt::Debug + Send + Sync {
+    /// Short name of the store, used in errors and [`ConfigSource`](crate::ConfigSource)
+    fn name(&self) -> &str;
+
+    /// Look up a credential, returning `None` if it is not stored
+    fn get(&self, name: &str) -> Result<Option<Secret<String>>>;
+
+    /// Save a credential, replacing any previous value
+    fn set(&self, name: &str, secret: &str) -> Result<()>;
+
+    /// Remove a credential; removing a missing credential is not an error
+    fn delete(&self, name: &str) -> Result<()>;
+}
+
+/// Read-only store backed by environment variables
+///
+/// Provider names map to the provider's usual variables (`openai` reads
+/// `OPENAI_API_KEY`, then `OPENAI_KEY`). Any other name reads
+/// `ZEKE_CREDENTIAL_<NAME>`, upper-cased with non-alphanumerics replaced by `_`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvStore;
+
+impl EnvStore {
+    /// Variable holding a credential that is not named after a provider
+    pub fn variable(name: &str) -> String {
+        let suffix: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+            .collect();
+        format!("ZEKE_CREDENTIAL_{}", suffix)
+    }
+}
+
+impl CredentialStore for EnvStore {
+    fn name(&self) -> &str {
+        "environment"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<Secret<String>>> {
+        if let Some(provider) = Provider::from_str(name) {
+            return Ok(Config::api_key_env_var(provider).map(|(_, key)| Secret::new(key)));
+        }
+        Ok(std::env::var(Self::variable(name))
+            .ok()
+            .filter(|key| !key.is_empty())
+            .map(Secret::new))
+    }
+
+    fn set(&self, _name: &str, _secret: &str) -> Result<()> {
+        Err(Error::credential(self.name(), "environment variables are read-only"))
+    }
+
+    fn delete(&self, _name: &str) -> Result<()> {
+        Err(Error::credential(self.name(), "environment variables are read-only"))
+    }
+}
+
+/// Store backed by the freedesktop Secret Service (GNOME Keyring, KWallet)
+///
+/// Secrets are kept under the `service` and `account` attributes through
+/// `secret-tool`, the same way as the Zig CLI, so keys saved with
+/// `zeke auth set-key openai ...` are found under the name `openai`.
+#[derive(Debug, Clone)]
+pub struct SecretServiceStore {
+    service: String,
+}
+
+impl Default for SecretServiceStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecretServiceStore {
+    /// Store using the CLI's `zeke` service
+    pub fn new() -> Self {
+        Self::with_service("zeke")
+    }
+
+    /// Store using a different service attribute
+    pub fn with_service<S: Into<String>>(service: S) -> Self {
+        Self {
+            service: service.into(),
+        }
+    }
+
+    /// Check whether `secret-tool` is installed
+    pub fn is_available() -> bool {
+        std::env::var_os("PATH").is_some_and(|paths| {
+            std::env::split_paths(&paths).any(|dir| dir.join("secret-tool").is_file())
+        })
+    }
+
+    fn command(&self, action: &str, name: &str) -> Command {
+        let mut command = Command::new("secret-tool");
+        command.arg(action);
+        if action == "store" {
+            command.args(["--label", &format!("Zeke - {}", self.service)]);
+        }
+        command.args(["service", &self.service, "account", name]);
+        command
+    }
+
+    fn failed(&self, action: &str, detail: impl fmt::Display) -> Error {
+        Error::credential(self.name(), format!("secret-tool {} failed: {}", action, detail))
+    }
+}
+
+impl CredentialStore for SecretServiceStore {
+    fn name(&self) -> &str {
+        "secret-service"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<Secret<String>>> {
+        let output = self
+            .command("lookup", name)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| self.failed("lookup", e))?;
+
+        match output.status.code() {
+            Some(0) => {
+                let secret = String::from_utf8(output.stdout)
+                    .map_err(|_| self.failed("lookup", "secret is not UTF-8"))?;
+                let secret = secret.trim();
+                Ok((!secret.is_empty()).then(|| Secret::new(secret.to_string())))
+            }
+            // Exit code 1 means no matching item
+            Some(1) => Ok(None),
+            _ => Err(self.failed("lookup", String::from_utf8_lossy(&output.stderr).trim())),
+        }
+    }
+
+    fn set(&self, name: &str, secret: &str) -> Result<()> {
+        let mut child = self
+            .command("store", name)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| self.failed("store", e))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(secret.as_bytes())
+                .map_err(|e| self.failed("store", e))?;
+        }
+
+        let output = child.wait_with_output().map_err(|e| self.failed("store", e))?;
+        if !output.status.success() {
+            return Err(self.failed("store", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(())
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        let output = self
+            .command("clear", name)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| self.failed("clear", e))?;
+
+        // `clear` also exits with 1 when nothing matched
+        match output.status.code() {
+            Some(0) | Some(1) => Ok(()),
+            _ => Err(self.failed("clear", String::from_utf8_lossy(&output.stderr).trim())),
+        }
+    }
+}
+
+/// Several stores searched in order
+///
+/// Lookups return the first store's hit. Saving uses the first store that
+/// accepts the credential, and deleting removes it from every writable store.
+#[derive(Debug, Default)]
+pub struct CredentialChain {
+    stores: Vec<Box<dyn CredentialStore>>,
+}
+
+impl CredentialChain {
+    /// Empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Environment variables, then the Secret Service when it is installed
+    pub fn standard() -> Self {
+        let chain = Self::new().with(EnvStore);
+        if SecretServiceStore::is_available() {
+            chain.with(SecretServiceStore::new())
+        } else {
+            chain
+        }
+    }
+
+    /// Append a store, searched after the existing ones
+    pub fn with<S: CredentialStore + 'static>(mut self, store: S) -> Self {
+        self.stores.push(Box::new(store));
+        self
+    }
+
+    /// The stores in search order
+    pub fn stores(&self) -> impl Iterator<Item = &dyn CredentialStore> {
+        self.stores.iter().map(|s| s.as_ref())
+    }
+
+    /// Look up a credential along with the name of the store that had it
+    pub fn find(&self, name: &str) -> Result<Option<(&str, Secret<String>)>> {
+        for store in &self.stores {
+            if let Some(secret) = store.get(name)? {
+                return Ok(Some((store.name(), secret)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl CredentialStore for CredentialChain {
+    fn name(&self) -> &str {
+        "chain"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<Secret<String>>> {
+        Ok(self.find(name)?.map(|(_, secret)| secret))
+    }
+
+    fn set(&self, name: &str, secret: &str) -> Result<()> {
+        let mut last_error = None;
+        for store in &self.stores {
+            match store.set(name, secret) {
+                Ok(()) => return Ok(()),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| Error::credential(self.name(), "no credential stores configured")))
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        let mut deleted = false;
+        let mut last_error = None;
+        for store in &self.stores {
+            match store.delete(name) {
+                Ok(()) => deleted = true,
+                Err(e) => last_error = Some(e),
+            }
+        }
+        match last_error {
+            Some(e) if !deleted => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Default location of the encrypted credential file
+pub fn default_credential_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("zeke").join("credentials.enc"))
+}
+
+#[cfg(feature = "credential-file")]
+mod file {
+    use super::CredentialStore;
+    use crate::{Error, Result};
+    use argon2::{Algorithm, Argon2, Params, Version};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+    use chacha20poly1305::aead::rand_core::RngCore;
+    use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+    use secrecy::Secret;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+    use std::sync::Mutex;
+    use zeroize::Zeroizing;
+
+    const FORMAT_VERSION: u32 = 1;
+    /// Plaintext encrypted into the file to check the passphrase on open
+    const CHECK_VALUE: &[u8] = b"zeke-credentials";
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct KdfParams {
+        algorithm: String,
+        salt: String,
+        m_cost: u32,
+        t_cost: u32,
+        p_cost: u32,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Sealed {
+        nonce: String,
+        ciphertext: String,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct CredentialFile {
+        version: u32,
+        kdf: KdfParams,
+        check: Sealed,
+        #[serde(default)]
+        entries: BTreeMap<String, Sealed>,
+    }
+
+    /// Credentials encrypted with a key derived from a passphrase
+    ///
+    /// The key is derived with Argon2id and each entry is sealed with
+    /// XChaCha20-Poly1305, bound to its name. Only names are stored in the
+    /// clear.
+    pub struct EncryptedFileStore {
+        path: PathBuf,
+        cipher: XChaCha20Poly1305,
+        file: Mutex<CredentialFile>,
+    }
+
+    impl std::fmt::Debug for EncryptedFileStore {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("EncryptedFileStore").field("path", &self.path).finish_non_exhaustive()
+        }
+    }
+
+    impl EncryptedFileStore {
+        /// Open the file at `path`, creating an empty store if it does not exist
+        ///
+        /// Fails if the passphrase does not match the one the file was created with.
+        pub fn open<P: AsRef<Path>>(path: P, passphrase: &Secret<String>) -> Result<Self> {
+            use secrecy::ExposeSecret;
+
+            let path = path.as_ref().to_path_buf();
+            if !path.exists() {
+                let params = Params::default();
+                let mut salt = [0u8; 16];
+                OsRng.fill_bytes(&mut salt);
+                let kdf = KdfParams {
+                    algorithm: "argon2id".to_string(),
+                    salt: STANDARD.encode(salt),
+                    m_cost: params.m_cost(),
+                    t_cost: params.t_cost(),
+                    p_cost: params.p_cost(),
+                };
+                let cipher = derive_cipher(&kdf, passphrase.expose_secret())?;
+                let check = seal(&cipher, "", CHECK_VALUE)?;
+                let file = CredentialFile {
+                    version: FORMAT_VERSION,
+                    kdf,
+                    check,
+                    entries: BTreeMap::new(),
+                };
+                let store = Self {
+                    path,
+                    cipher,
+                    file: Mutex::new(file),
+                };
+                store.save(&store.file.lock().unwrap_or_else(|e| e.into_inner()))?;
+                return Ok(store);
+            }
+
+            let content = std::fs::read_to_string(&path)?;
+            let file: CredentialFile = serde_json::from_str(&content)?;
+            if file.version != FORMAT_VERSION {
+                return Err(Error::credential(
+                    "file",
+                    format!("unsupported credential file version {}", file.version),
+                ));
+            }
+            let cipher = derive_cipher(&file.kdf, passphrase.expose_secret())?;
+            if open_sealed(&cipher, "", &file.check).as_deref().map(|v| &v[..]) != Some(CHECK_VALUE) {
+                return Err(Error::credential("file", "wrong passphrase for credential file"));
+            }
+
+            Ok(Self {
+                path,
+                cipher,
+                file: Mutex::new(file),
+            })
+        }
+
+        /// Path of the backing file
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        /// Names of all stored credentials
+        pub fn names(&self) -> Vec<String> {
+            let file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+            file.entries.keys().cloned().collect()
+        }
+
+        /// Write the file atomically, readable by the owner only
+        fn save(&self, file: &CredentialFile) -> Result<()> {
+            if let Some(parent) = self.path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let temp = self.path.with_extension("tmp");
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            let mut out = options.open(&temp)?;
+            std::io::Write::write_all(&mut out, serde_json::to_string_pretty(file)?.as_bytes())?;
+            out.sync_all()?;
+            std::fs::rename(&temp, &self.path)?;
+            Ok(())
+        }
+    }
+
+    impl CredentialStore for EncryptedFileStore {
+        fn name(&self) -> &str {
+            "file"
+        }
+
+        fn get(&self, name: &str) -> Result<Option<Secret<String>>> {
+            let file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(sealed) = file.entries.get(name) else {
+                return Ok(None);
+            };
+            let plaintext = open_sealed(&self.cipher, name, sealed)
+                .ok_or_else(|| Error::credential("file", format!("credential '{}' is corrupted", name)))?;
+            let secret = String::from_utf8(plaintext.to_vec())
+                .map_err(|_| Error::credential("file", format!("credential '{}' is not UTF-8", name)))?;
+            Ok(Some(Secret::new(secret)))
+        }
+
+        fn set(&self, name: &str, secret: &str) -> Result<()> {
+            let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+            let sealed = seal(&self.cipher, name, secret.as_bytes())?;
+            file.entries.insert(name.to_string(), sealed);
+            self.save(&file)
+        }
+
+        fn delete(&self, name: &str) -> Result<()> {
+            let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+            if file.entries.remove(name).is_some() {
+                self.save(&file)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn derive_cipher(kdf: &KdfParams, passphrase: &str) -> Result<XChaCha20Poly1305> {
+        let invalid = |detail: &dyn std::fmt::Display| {
+            Error::credential("file", format!("invalid key derivation parameters: {}", detail))
+        };
+        if kdf.algorithm != "argon2id" {
+            return Err(invalid(&kdf.algorithm));
+        }
+        let salt = STANDARD.decode(&kdf.salt).map_err(|e| invalid(&e))?;
+        let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(32)).map_err(|e| invalid(&e))?;
+
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+            .map_err(|e| invalid(&e))?;
+        Ok(XChaCha20Poly1305::new(key.as_ref().into()))
+    }
+
+    fn seal(cipher: &XChaCha20Poly1305, name: &str, plaintext: &[u8]) -> Result<Sealed> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, Payload { msg: plaintext, aad: name.as_bytes() })
+            .map_err(|_| Error::credential("file", "encryption failed"))?;
+        Ok(Sealed {
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        })
+    }
+
+    fn open_sealed(cipher: &XChaCha20Poly1305, name: &str, sealed: &Sealed) -> Option<Zeroizing<Vec<u8>>> {
+        let nonce = STANDARD.decode(&sealed.nonce).ok()?;
+        if nonce.len() != 24 {
+            return None;
+        }
+        let ciphertext = STANDARD.decode(&sealed.ciphertext).ok()?;
+        cipher
+            .decrypt(XNonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: name.as_bytes() })
+            .ok()
+            .map(Zeroizing::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secrecy::ExposeSecret;
+
+    #[test]
+    fn test_env_store() {
+        let store = EnvStore;
+        assert_eq!(EnvStore::variable("work-openai"), "ZEKE_CREDENTIAL_WORK_OPENAI");
+        assert!(store.get("credentials-test-missing").unwrap().is_none());
+        assert!(store.set("openai", "sk-test").is_err());
+    }
+
+    #[derive(Debug, Default)]
+    struct MemoryStore(std::sync::Mutex<std::collections::HashMap<String, String>>);
+
+    impl CredentialStore for MemoryStore {
+        fn name(&self) -> &str {
+            "memory"
+        }
+
+        fn get(&self, name: &str) -> Result<Option<Secret<String>>> {
+            Ok(self.0.lock().unwrap().get(name).cloned().map(Secret::new))
+        }
+
+        fn set(&self, name: &str, secret: &str) -> Result<()> {
+            self.0.lock().unwrap().insert(name.to_string(), secret.to_string());
+            Ok(())
+        }
+
+        fn delete(&self, name: &str) -> Result<()> {
+            self.0.lock().unwrap().remove(name);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_chain_falls_through_read_only_stores() {
+        let chain = CredentialChain::new().with(EnvStore).with(MemoryStore::default());
+        assert!(chain.get("credentials-test-chain").unwrap().is_none());
+
+        chain.set("credentials-test-chain", "secret").unwrap();
+        let (store, secret) = chain.find("credentials-test-chain").unwrap().unwrap();
+        assert_eq!(store, "memory");
+        assert_eq!(secret.expose_secret(), "secret");
+
+        chain.delete("credentials-test-chain").unwrap();
+        assert!(chain.get("credentials-test-chain").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_config_loads_named_credential() {
+        let store = MemoryStore::default();
+        store.set("work", "sk-work").unwrap();
+
+        let mut config = Config {
+            credential: Some("work".to_string()),
+            ..Config::default()
+        };
+        assert!(config.load_credential(&store).unwrap());
+        assert_eq!(config.api_key(), Some("sk-work"));
+        assert_eq!(
+            config.provenance.source("api_key"),
+            &crate::ConfigSource::CredentialStore("memory".to_string())
+        );
+
+        // The name is saved, the key is not
+        let saved = toml::to_string(&config).unwrap();
+        assert!(saved.contains("credential = \"work\""));
+        assert!(!saved.contains("sk-work"));
+    }
+
+    #[test]
+    fn test_provider_credential_names_match_cli() {
+        assert_eq!(Provider::Claude.credential_name(), "anthropic");
+        assert_eq!(Provider::Copilot.credential_name(), "github");
+        assert_eq!(Provider::Google.credential_name(), "google");
+    }
+
+    #[cfg(feature = "credential-file")]
+    #[test]
+    fn test_encrypted_file_store() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("credentials.enc");
+        let passphrase = Secret::new("correct horse".to_string());
+
+        let store = EncryptedFileStore::open(&path, &passphrase).unwrap();
+        store.set("openai", "sk-secret").unwrap();
+        store.set("google", "AIza-secret").unwrap();
+        store.delete("google").unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("sk-secret"));
+
+        let reopened = EncryptedFileStore::open(&path, &passphrase).unwrap();
+        assert_eq!(reopened.names(), vec!["openai".to_string()]);
+        assert_eq!(reopened.get("openai").unwrap().unwrap().expose_secret(), "sk-secret");
+        assert!(reopened.get("google").unwrap().is_none());
+
+        let wrong = Secret::new("battery staple".to_string());
+        assert!(EncryptedFileStore::open(&path, &wrong).is_err());
+    }
+}
@@ -0,0 +1,367 @@
+//! Dynamic model discovery
+//!
+//! Model lists are fetched from each provider's listing endpoint and cached
+//! on disk with a TTL. When the provider cannot be reached the last cached
+//! list is used, and failing that the static [`Provider::default_models`].
+
+use crate::{Error, Provider, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Where a model list came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelSource {
+    /// Fetched from the provider just now
+    Live,
+    /// Read from the on-disk cache
+    Cache,
+    /// Built-in list shipped with the crate
+    Static,
+}
+
+/// Models available from a provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelList {
+    /// Provider the models belong to
+    pub provider: Provider,
+    /// Model identifiers
+    pub models: Vec<String>,
+    /// Where the list came from
+    pub source: ModelSource,
+    /// When the list was fetched from the provider
+    pub fetched_at: Option<SystemTime>,
+}
+
+impl ModelList {
+    /// Built-in list for a provider
+    pub fn fallback(provider: Provider) -> Self {
+        Self {
+            provider,
+            models: provider.default_models().into_iter().map(String::from).collect(),
+            source: ModelSource::Static,
+            fetched_at: None,
+        }
+    }
+
+    /// Check whether a model is in the list
+    pub fn contains(&self, model: &str) -> bool {
+        self.models.iter().any(|m| m == model)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    provider: String,
+    base_url: String,
+    fetched_at: u64,
+    models: Vec<String>,
+}
+
+/// On-disk cache of discovered model lists, one file per provider endpoint
+#[derive(Debug, Clone)]
+pub struct ModelCache {
+    dir: Option<PathBuf>,
+    ttl: Duration,
+}
+
+impl ModelCache {
+    /// Default time a cached list is considered fresh
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// Create a cache stored in `dir`
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            ttl: Self::DEFAULT_TTL,
+        }
+    }
+
+    /// Platform cache directory for model lists (e.g. `~/.cache/zeke/models`)
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("zeke").join("models"))
+    }
+
+    /// Set how long cached lists stay fresh
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Time cached lists stay fresh
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    fn path(&self, provider: Provider, base_url: &str) -> Option<PathBuf> {
+        // FNV-1a keeps file names stable across Rust versions
+        let hash = base_url.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}-{:016x}.json", provider.identifier(), hash)))
+    }
+
+    fn read(path: &Path, provider: Provider, base_url: &str) -> Option<CacheEntry> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+        (entry.provider == provider.identifier() && entry.base_url == base_url).then_some(entry)
+    }
+
+    /// Cached list for a provider endpoint regardless of age
+    pub fn load(&self, provider: Provider, base_url: &str) -> Option<ModelList> {
+        let entry = Self::read(&self.path(provider, base_url)?, provider, base_url)?;
+        Some(ModelList {
+            provider,
+            models: entry.models,
+            source: ModelSource::Cache,
+            fetched_at: Some(UNIX_EPOCH + Duration::from_secs(entry.fetched_at)),
+        })
+    }
+
+    /// Cached list for a provider endpoint if it is younger than the TTL
+    pub fn load_fresh(&self, provider: Provider, base_url: &str) -> Option<ModelList> {
+        self.load(provider, base_url).filter(|list| {
+            list.fetched_at
+                .and_then(|at| at.elapsed().ok())
+                .is_some_and(|age| age < self.ttl)
+        })
+    }
+
+    /// Store a freshly fetched list
+    pub fn store(&self, provider: Provider, base_url: &str, models: &[String]) -> Result<()> {
+        let Some(path) = self.path(provider, base_url) else {
+            return Ok(());
+        };
+
+        let entry = CacheEntry {
+            provider: provider.identifier().to_string(),
+            base_url: base_url.to_string(),
+            fetched_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            models: models.to_vec(),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_vec_pretty(&entry)?)?;
+        Ok(())
+    }
+
+    /// Best list known without network access: the cache if present, else the static list
+    pub fn known_models(&self, provider: Provider, base_url: &str) -> ModelList {
+        self.load(provider, base_url)
+            .unwrap_or_else(|| ModelList::fallback(provider))
+    }
+}
+
+impl Default for ModelCache {
+    fn default() -> Self {
+        Self {
+            dir: Self::default_dir(),
+            ttl: Self::DEFAULT_TTL,
+        }
+    }
+}
+
+/// Lists a provider's models, consulting the cache before the network
+#[derive(Debug, Clone, Default)]
+pub struct ModelDiscovery {
+    cache: ModelCache,
+    #[cfg(feature = "discovery")]
+    client: reqwest::Client,
+}
+
+impl ModelDiscovery {
+    /// Create a discovery client using the default cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a different cache
+    pub fn with_cache(mut self, cache: ModelCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// The cache in use
+    pub fn cache(&self) -> &ModelCache {
+        &self.cache
+    }
+
+    /// List models for a provider endpoint
+    ///
+    /// A fresh cache entry wins; otherwise the endpoint is queried (with the
+    /// `discovery` feature) and the result cached. On failure the stale cache
+    /// entry or the static list is returned.
+    pub async fn list(&self, provider: Provider, base_url: &str, api_key: Option<&str>) -> ModelList {
+        if let Some(list) = self.cache.load_fresh(provider, base_url) {
+            return list;
+        }
+
+        #[cfg(feature = "discovery")]
+        match self.fetch(provider, base_url, api_key).await {
+            Ok(models) => {
+                if let Err(e) = self.cache.store(provider, base_url, &models) {
+                    tracing::warn!("Failed to cache model list for {}: {}", provider, e);
+                }
+                return ModelList {
+                    provider,
+                    models,
+                    source: ModelSource::Live,
+                    fetched_at: Some(SystemTime::now()),
+                };
+            }
+            Err(e) => tracing::debug!("Model discovery for {} failed: {}", provider, e),
+        }
+        #[cfg(not(feature = "discovery"))]
+        let _ = api_key;
+
+        self.cache.known_models(provider, base_url)
+    }
+
+    /// Query a provider's model-listing endpoint
+    #[cfg(feature = "discovery")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "discovery")))]
+    pub async fn fetch(&self, provider: Provider, base_url: &str, api_key: Option<&str>) -> Result<Vec<String>> {
+        use crate::backend::http::{check_status, transport_error};
+
+        let base_url = base_url.trim_end_matches('/');
+        let key = api_key.unwrap_or_default();
+        let request = match provider {
+            Provider::Ollama => self.client.get(format!("{}/api/tags", base_url)),
+            Provider::Claude => self
+                .client
+                .get(format!("{}/models", base_url))
+                .header("x-api-key", key)
+                .header("anthropic-version", "2023-06-01"),
+            Provider::Google => self
+                .client
+                .get(format!("{}/models", base_url))
+                .query(&[("key", key)]),
+            Provider::Azure => self
+                .client
+                .get(format!("{}/openai/models", base_url))
+                .query(&[("api-version", "2024-02-15-preview")])
+                .header("api-key", key),
+            Provider::OpenAI | Provider::XAI | Provider::Omen => self
+                .client
+                .get(format!("{}/models", base_url))
+                .bearer_auth(key),
+            Provider::Custom(_) => {
+                let request = self.client.get(format!("{}/models", base_url));
+                let header = provider
+                    .custom_definition()
+                    .and_then(|d| api_key.and_then(|key| d.auth.header(key)));
+                match header {
+                    Some((name, value)) => request.header(name, value),
+                    None => request,
+                }
+            }
+            Provider::Copilot | Provider::GhostLLM => {
+                return Err(Error::provider_unavailable(
+                    provider.identifier(),
+                    "provider has no model-listing endpoint",
+                ));
+            }
+        };
+
+        let response = request
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(transport_error)?;
+        let body: serde_json::Value = check_status(provider, response)
+            .await?
+            .json()
+            .await
+            .map_err(transport_error)?;
+
+        parse_model_list(provider, &body)
+    }
+}
+
+/// Extract model identifiers from a listing response
+#[cfg_attr(not(feature = "discovery"), allow(dead_code))]
+fn parse_model_list(provider: Provider, body: &serde_json::Value) -> Result<Vec<String>> {
+    let (array, field) = match provider {
+        Provider::Ollama => ("models", "name"),
+        Provider::Google => ("models", "name"),
+        _ => ("data", "id"),
+    };
+
+    let entries = body
+        .get(array)
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| {
+            Error::unexpected_response(
+                provider.identifier().to_string(),
+                format!("model list has no '{}' array", array),
+            )
+        })?;
+
+    Ok(entries
+        .iter()
+        .filter_map(|entry| entry.get(field).and_then(|v| v.as_str()))
+        .map(|name| name.strip_prefix("models/").unwrap_or(name).to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ModelCache::new(dir.path());
+        let models = vec!["llama3.1:8b".to_string(), "qwen2.5-coder:7b".to_string()];
+
+        assert!(cache.load(Provider::Ollama, "http://localhost:11434").is_none());
+        cache.store(Provider::Ollama, "http://localhost:11434", &models).unwrap();
+
+        let list = cache.load_fresh(Provider::Ollama, "http://localhost:11434").unwrap();
+        assert_eq!(list.models, models);
+        assert_eq!(list.source, ModelSource::Cache);
+
+        // Entries are per endpoint
+        assert!(cache.load(Provider::Ollama, "http://gpu-box:11434").is_none());
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ModelCache::new(dir.path()).with_ttl(Duration::ZERO);
+        cache.store(Provider::OpenAI, "https://api.openai.com/v1", &["gpt-4o".to_string()]).unwrap();
+
+        assert!(cache.load_fresh(Provider::OpenAI, "https://api.openai.com/v1").is_none());
+        assert!(cache.load(Provider::OpenAI, "https://api.openai.com/v1").is_some());
+    }
+
+    #[test]
+    fn test_known_models_falls_back_to_static() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = ModelCache::new(dir.path()).known_models(Provider::Claude, "https://api.anthropic.com/v1");
+        assert_eq!(list.source, ModelSource::Static);
+        assert!(list.contains(Provider::Claude.default_model()));
+    }
+
+    #[test]
+    fn test_parse_model_list() {
+        let openai = serde_json::json!({"data": [{"id": "gpt-4o"}, {"id": "o1"}]});
+        assert_eq!(parse_model_list(Provider::OpenAI, &openai).unwrap(), vec!["gpt-4o", "o1"]);
+
+        let ollama = serde_json::json!({"models": [{"name": "llama3.1:8b"}]});
+        assert_eq!(parse_model_list(Provider::Ollama, &ollama).unwrap(), vec!["llama3.1:8b"]);
+
+        let google = serde_json::json!({"models": [{"name": "models/gemini-1.5-flash"}]});
+        assert_eq!(parse_model_list(Provider::Google, &google).unwrap(), vec!["gemini-1.5-flash"]);
+
+        assert!(parse_model_list(Provider::OpenAI, &serde_json::json!({})).is_err());
+    }
+}
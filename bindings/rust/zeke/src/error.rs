@@ -1,8 +1,10 @@
 //! Error types and handling for Zeke operations
 
+use crate::Provider;
 use std::ffi::NulError;
+use std::time::Duration;
 use thiserror::Error;
-use zeke_sys::ZekeErrorCode;
+use zeke_sys::{c_buf_to_str, LoadError, ZekeErrorCode, ZekeErrorDetail};
 
 /// Result type used throughout the Zeke crate
 pub type Result<T> = std::result::Result<T, Error>;
@@ -24,6 +26,8 @@ pub enum Error {
         provider: String,
         /// Additional error details
         message: String,
+        /// HTTP status the provider answered with, if known
+        http_status: Option<u16>,
     },
 
     /// Failed to load configuration
@@ -38,6 +42,8 @@ pub enum Error {
     NetworkError {
         /// Network error details
         message: String,
+        /// HTTP status the provider answered with, if known
+        http_status: Option<u16>,
     },
 
     /// Invalid model specified
@@ -47,6 +53,8 @@ pub enum Error {
         model: String,
         /// The provider that rejected the model
         provider: String,
+        /// HTTP status the provider answered with, if known
+        http_status: Option<u16>,
     },
 
     /// Token exchange failed during OAuth
@@ -63,6 +71,8 @@ pub enum Error {
         provider: String,
         /// Response error details
         message: String,
+        /// HTTP status the provider answered with, if known
+        http_status: Option<u16>,
     },
 
     /// Memory allocation error
@@ -88,6 +98,23 @@ pub enum Error {
         provider: String,
         /// Unavailability reason
         message: String,
+        /// HTTP status the provider answered with, if known
+        http_status: Option<u16>,
+        /// How long the provider asked to wait before retrying, if it said
+        retry_after: Option<Duration>,
+    },
+
+    /// Provider rejected the request because a rate limit was hit
+    #[error("Rate limited by provider {provider}: {message}")]
+    RateLimited {
+        /// The provider that limited the request
+        provider: String,
+        /// Rate limit details
+        message: String,
+        /// HTTP status the provider answered with, if known
+        http_status: Option<u16>,
+        /// How long the provider asked to wait before retrying, if it said
+        retry_after: Option<Duration>,
     },
 
     /// Streaming operation failed
@@ -97,6 +124,47 @@ pub enum Error {
         message: String,
     },
 
+    /// A callback passed to a streaming call panicked; the stream was stopped
+    #[error("Stream callback panicked: {message}")]
+    CallbackPanicked {
+        /// The panic message
+        message: String,
+    },
+
+    /// Reading or writing a credential store failed
+    #[error("Credential store {store} failed: {message}")]
+    CredentialError {
+        /// The store that failed
+        store: String,
+        /// Credential error details
+        message: String,
+    },
+
+    /// The runtime-loaded Zeke library could not be opened
+    #[error("Zeke library unavailable: {message}")]
+    LibraryUnavailable {
+        /// Load failure details
+        message: String,
+    },
+
+    /// The loaded Zeke library does not export a required function
+    #[error("Zeke library does not provide {symbol}")]
+    MissingSymbol {
+        /// The missing function
+        symbol: String,
+    },
+
+    /// The Zeke library was built against a different ABI than these bindings
+    #[error("Zeke library ABI mismatch: {item} is {found}, bindings expect {expected}")]
+    AbiMismatch {
+        /// What differs, such as `ABI version` or `ZekeConfig.timeout_ms offset`
+        item: String,
+        /// Value these bindings were compiled with
+        expected: u64,
+        /// Value the library reported
+        found: u64,
+    },
+
     /// String conversion error (contains null bytes)
     #[error("String conversion error: {0}")]
     StringConversion(#[from] NulError),
@@ -145,6 +213,7 @@ impl Error {
         Self::AuthenticationFailed {
             provider: provider.into(),
             message: message.into(),
+            http_status: None,
         }
     }
 
@@ -152,6 +221,7 @@ impl Error {
     pub fn network<S: Into<String>>(message: S) -> Self {
         Self::NetworkError {
             message: message.into(),
+            http_status: None,
         }
     }
 
@@ -160,6 +230,7 @@ impl Error {
         Self::InvalidModel {
             model: model.into(),
             provider: provider.into(),
+            http_status: None,
         }
     }
 
@@ -168,6 +239,26 @@ impl Error {
         Self::ProviderUnavailable {
             provider: provider.into(),
             message: message.into(),
+            http_status: None,
+            retry_after: None,
+        }
+    }
+
+    /// Create a rate limit error
+    pub fn rate_limited<S: Into<String>>(provider: S, message: S) -> Self {
+        Self::RateLimited {
+            provider: provider.into(),
+            message: message.into(),
+            http_status: None,
+            retry_after: None,
+        }
+    }
+
+    /// Create a credential store error
+    pub fn credential<S: Into<String>, M: Into<String>>(store: S, message: M) -> Self {
+        Self::CredentialError {
+            store: store.into(),
+            message: message.into(),
         }
     }
 
@@ -178,16 +269,82 @@ impl Error {
         }
     }
 
+    /// Create an unexpected response error
+    pub fn unexpected_response<S: Into<String>>(provider: S, message: S) -> Self {
+        Self::UnexpectedResponse {
+            provider: provider.into(),
+            message: message.into(),
+            http_status: None,
+        }
+    }
+
+    /// Record the HTTP status the provider answered with
+    ///
+    /// Errors that never come from a provider response ignore it.
+    pub fn with_http_status(mut self, status: u16) -> Self {
+        match &mut self {
+            Error::AuthenticationFailed { http_status, .. }
+            | Error::NetworkError { http_status, .. }
+            | Error::InvalidModel { http_status, .. }
+            | Error::UnexpectedResponse { http_status, .. }
+            | Error::ProviderUnavailable { http_status, .. }
+            | Error::RateLimited { http_status, .. } => *http_status = Some(status),
+            _ => {}
+        }
+        self
+    }
+
+    /// Record how long the provider asked to wait before retrying
+    ///
+    /// Only rate limit and provider unavailable errors keep it.
+    pub fn with_retry_after(mut self, delay: Duration) -> Self {
+        if let Error::ProviderUnavailable { retry_after, .. } | Error::RateLimited { retry_after, .. } =
+            &mut self
+        {
+            *retry_after = Some(delay);
+        }
+        self
+    }
+
+    /// HTTP status the provider answered with, if known
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            Error::AuthenticationFailed { http_status, .. }
+            | Error::NetworkError { http_status, .. }
+            | Error::InvalidModel { http_status, .. }
+            | Error::UnexpectedResponse { http_status, .. }
+            | Error::ProviderUnavailable { http_status, .. }
+            | Error::RateLimited { http_status, .. } => *http_status,
+            _ => None,
+        }
+    }
+
+    /// How long the provider asked to wait before retrying, if it said
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::ProviderUnavailable { retry_after, .. } | Error::RateLimited { retry_after, .. } => {
+                *retry_after
+            }
+            _ => None,
+        }
+    }
+
     /// Check if this error is retryable
     pub fn is_retryable(&self) -> bool {
         matches!(
             self,
             Error::NetworkError { .. }
                 | Error::ProviderUnavailable { .. }
+                | Error::RateLimited { .. }
                 | Error::UnexpectedResponse { .. }
         )
     }
 
+    /// Check if this error is a provider rate limit
+    pub fn is_rate_limit(&self) -> bool {
+        matches!(self, Error::RateLimited { .. })
+    }
+
     /// Check if this error is related to authentication
     pub fn is_auth_error(&self) -> bool {
         matches!(
@@ -196,6 +353,16 @@ impl Error {
         )
     }
 
+    /// Check if this error comes from an incomplete or missing Zeke library
+    pub fn is_library_error(&self) -> bool {
+        matches!(
+            self,
+            Error::LibraryUnavailable { .. }
+                | Error::MissingSymbol { .. }
+                | Error::AbiMismatch { .. }
+        )
+    }
+
     /// Get the error category for metrics/logging
     pub fn category(&self) -> &'static str {
         match self {
@@ -210,7 +377,12 @@ impl Error {
             Error::MemoryError { .. } => "memory",
             Error::InvalidParameter { .. } => "parameter",
             Error::ProviderUnavailable { .. } => "provider",
-            Error::StreamingFailed { .. } => "streaming",
+            Error::RateLimited { .. } => "rate_limit",
+            Error::StreamingFailed { .. } | Error::CallbackPanicked { .. } => "streaming",
+            Error::CredentialError { .. } => "credentials",
+            Error::LibraryUnavailable { .. }
+            | Error::MissingSymbol { .. }
+            | Error::AbiMismatch { .. } => "library",
             Error::StringConversion(_) | Error::Utf8Error(_) => "encoding",
             Error::JsonError(_) => "serialization",
             Error::IoError(_) => "io",
@@ -245,10 +417,9 @@ impl From<ZekeErrorCode> for Error {
             ZekeErrorCode::ZEKE_TOKEN_EXCHANGE_FAILED => Error::TokenExchangeFailed {
                 message: "OAuth token exchange failed".to_string(),
             },
-            ZekeErrorCode::ZEKE_UNEXPECTED_RESPONSE => Error::UnexpectedResponse {
-                provider: "unknown".to_string(),
-                message: "Received unexpected response".to_string(),
-            },
+            ZekeErrorCode::ZEKE_UNEXPECTED_RESPONSE => {
+                Error::unexpected_response("unknown", "Received unexpected response")
+            }
             ZekeErrorCode::ZEKE_MEMORY_ERROR => Error::MemoryError {
                 message: "Memory allocation failed".to_string(),
             },
@@ -263,6 +434,66 @@ impl From<ZekeErrorCode> for Error {
             ZekeErrorCode::ZEKE_STREAMING_FAILED => {
                 Error::streaming("Streaming operation failed")
             }
+            ZekeErrorCode::ZEKE_RATE_LIMITED => Error::rate_limited("unknown", "Rate limit exceeded"),
+        }
+    }
+}
+
+impl From<&ZekeErrorDetail> for Error {
+    /// Build the error for a failed call from the details the core recorded
+    fn from(detail: &ZekeErrorDetail) -> Self {
+        let provider = Provider::from_ffi_int(detail.provider)
+            .map_or("unknown", |p| p.identifier())
+            .to_string();
+        let model = c_buf_to_str(&detail.model)
+            .filter(|m| !m.is_empty())
+            .unwrap_or("unknown")
+            .to_string();
+        let message = c_buf_to_str(&detail.message)
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| zeke_sys::error_to_string(detail.code))
+            .to_string();
+
+        // A provider's 429 can arrive under any code; the core's own limiter has a code
+        let error = match (detail.code, detail.http_status) {
+            (ZekeErrorCode::ZEKE_RATE_LIMITED, _) | (_, 429) => Error::rate_limited(provider, message),
+            (ZekeErrorCode::ZEKE_AUTHENTICATION_FAILED, _) => Error::authentication(provider, message),
+            (ZekeErrorCode::ZEKE_NETWORK_ERROR, _) => Error::network(message),
+            (ZekeErrorCode::ZEKE_INVALID_MODEL, _) => Error::invalid_model(model, provider),
+            (ZekeErrorCode::ZEKE_UNEXPECTED_RESPONSE, _) => Error::unexpected_response(provider, message),
+            (ZekeErrorCode::ZEKE_PROVIDER_UNAVAILABLE, _) => Error::provider_unavailable(provider, message),
+            (ZekeErrorCode::ZEKE_INITIALIZATION_FAILED, _) => Error::initialization(message),
+            (ZekeErrorCode::ZEKE_STREAMING_FAILED, _) => Error::streaming(message),
+            (ZekeErrorCode::ZEKE_CONFIG_LOAD_FAILED, _) => Error::ConfigError { message },
+            (ZekeErrorCode::ZEKE_TOKEN_EXCHANGE_FAILED, _) => Error::TokenExchangeFailed { message },
+            (ZekeErrorCode::ZEKE_MEMORY_ERROR, _) => Error::MemoryError { message },
+            (ZekeErrorCode::ZEKE_INVALID_PARAMETER, _) => Error::InvalidParameter {
+                parameter: "unknown".to_string(),
+                message,
+            },
+            (ZekeErrorCode::ZEKE_SUCCESS, _) => Error::from(ZekeErrorCode::ZEKE_SUCCESS),
+        };
+
+        let error = match detail.http_status {
+            0 => error,
+            status => error.with_http_status(status),
+        };
+        match detail.retry_after_ms {
+            0 => error,
+            ms => error.with_retry_after(Duration::from_millis(u64::from(ms))),
+        }
+    }
+}
+
+impl From<LoadError> for Error {
+    fn from(err: LoadError) -> Self {
+        match err {
+            LoadError::MissingSymbol { symbol } => Error::MissingSymbol {
+                symbol: symbol.to_string(),
+            },
+            err @ LoadError::NotFound { .. } => Error::LibraryUnavailable {
+                message: err.to_string(),
+            },
         }
     }
 }
@@ -277,6 +508,9 @@ pub fn check_result(code: ZekeErrorCode) -> Result<()> {
 }
 
 /// Enhanced error checking with last error message
+///
+/// The message is thread-local and can be stale; calls on a handle are
+/// checked with the per-call details instead.
 pub fn check_result_with_context(code: ZekeErrorCode) -> Result<()> {
     if code == ZekeErrorCode::ZEKE_SUCCESS {
         Ok(())
@@ -336,6 +570,90 @@ mod tests {
         assert!(!Error::network("test").is_auth_error());
     }
 
+    #[test]
+    fn test_load_error_conversion() {
+        let err = Error::from(LoadError::MissingSymbol { symbol: "zeke_ghostllm_init" });
+        assert!(matches!(&err, Error::MissingSymbol { symbol } if symbol == "zeke_ghostllm_init"));
+        assert!(err.is_library_error());
+        assert_eq!(err.category(), "library");
+
+        let err = Error::from(LoadError::NotFound {
+            tried: vec!["libzeke_ffi.so".into()],
+            message: "not found".into(),
+        });
+        assert!(matches!(err, Error::LibraryUnavailable { .. }));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_abi_mismatch_message() {
+        let err = Error::AbiMismatch {
+            item: "ZekeConfig.timeout_ms offset".to_string(),
+            expected: 44,
+            found: 48,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Zeke library ABI mismatch: ZekeConfig.timeout_ms offset is 48, bindings expect 44"
+        );
+        assert!(err.is_library_error());
+        assert!(!err.is_retryable());
+    }
+
+    fn detail(code: ZekeErrorCode, http_status: u16, retry_after_ms: u32, message: &str) -> ZekeErrorDetail {
+        let mut detail = ZekeErrorDetail {
+            code,
+            http_status,
+            provider: Provider::Claude.core_client() as i32,
+            retry_after_ms,
+            ..Default::default()
+        };
+        for (slot, byte) in detail.model.iter_mut().zip(b"claude-3-opus") {
+            *slot = *byte as _;
+        }
+        for (slot, byte) in detail.message.iter_mut().zip(message.bytes()) {
+            *slot = byte as _;
+        }
+        detail
+    }
+
+    #[test]
+    fn test_error_from_detail() {
+        let err = Error::from(&detail(ZekeErrorCode::ZEKE_UNEXPECTED_RESPONSE, 429, 1500, "slow down"));
+        assert!(matches!(&err, Error::RateLimited { provider, message, .. } if provider == "claude" && message == "slow down"));
+        assert_eq!(err.http_status(), Some(429));
+        assert_eq!(err.retry_after(), Some(Duration::from_millis(1500)));
+
+        // The core's client-side limiter reports no status, only when to retry
+        let err = Error::from(&detail(ZekeErrorCode::ZEKE_RATE_LIMITED, 0, 800, "RateLimitExceeded"));
+        assert!(err.is_rate_limit());
+        assert_eq!(err.http_status(), None);
+        assert_eq!(err.retry_after(), Some(Duration::from_millis(800)));
+
+        let err = Error::from(&detail(ZekeErrorCode::ZEKE_INVALID_MODEL, 0, 0, ""));
+        assert_eq!(err.to_string(), "Invalid model 'claude-3-opus' for provider claude");
+        assert_eq!(err.http_status(), None);
+
+        // Without a message the code's description is used
+        let err = Error::from(&detail(ZekeErrorCode::ZEKE_NETWORK_ERROR, 0, 0, ""));
+        assert_eq!(err.to_string(), format!("Network error: {}", zeke_sys::error_to_string(ZekeErrorCode::ZEKE_NETWORK_ERROR)));
+    }
+
+    #[test]
+    fn test_http_status_and_retry_after() {
+        let err = Error::provider_unavailable("openai", "overloaded")
+            .with_http_status(503)
+            .with_retry_after(Duration::from_secs(2));
+        assert_eq!(err.http_status(), Some(503));
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(2)));
+
+        // Errors that never come from a provider response keep neither
+        let err = Error::custom("local").with_http_status(500).with_retry_after(Duration::from_secs(1));
+        assert_eq!(err.http_status(), None);
+        assert_eq!(err.retry_after(), None);
+        assert_eq!(Error::network("reset").with_retry_after(Duration::from_secs(1)).retry_after(), None);
+    }
+
     #[test]
     fn test_error_code_conversion() {
         let err = Error::from(ZekeErrorCode::ZEKE_NETWORK_ERROR);
@@ -1,8 +1,10 @@
 //! FFI utility functions for safe interaction with zeke-sys
 
 use crate::{Error, Result};
+use std::any::Any;
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
 
 /// Convert a Rust string to a C string
 pub(crate) fn string_to_c_string(s: &str) -> Result<CString> {
@@ -110,6 +112,102 @@ impl Default for CStringManager {
     }
 }
 
+/// Run the body of an FFI callback, catching any panic
+///
+/// Unwinding into the Zig core is undefined behavior, so every
+/// `extern "C"` callback runs its Rust code through this and turns a panic
+/// into [`Error::CallbackPanicked`].
+///
+/// This needs `panic = "unwind"`; with `panic = "abort"` the process aborts
+/// before anything can be caught.
+pub(crate) fn catch_callback_panic<R>(f: impl FnOnce() -> R) -> Result<R> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| Error::CallbackPanicked {
+        message: panic_message(payload.as_ref()),
+    })
+}
+
+/// Run a blocking call into the Zig core off the async worker threads
+///
+/// A core call blocks for the whole request. On tokio's blocking pool it
+/// leaves the caller's worker free, so timeouts around the returned future
+/// can fire and other tasks keep running while the call is in the core.
+#[cfg(feature = "async")]
+pub(crate) async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(Error::custom(format!("core call was cancelled: {}", e))),
+    }
+}
+
+/// Without the `async` feature there is no blocking pool; run the call in place
+#[cfg(not(feature = "async"))]
+pub(crate) async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    f()
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Stand-in for the Zig core when testing or benchmarking FFI callbacks
+#[cfg(any(test, feature = "bench"))]
+pub(crate) mod harness {
+    use std::ffi::{c_void, CString};
+    use zeke_sys::{ZekeStreamCallback, ZekeStreamChunk};
+
+    /// Feed `chunks` to `callback` the way `zeke_chat_stream` does
+    ///
+    /// Stops at the first chunk the callback rejects and returns how many
+    /// chunks were delivered. A panic escaping the callback aborts the test
+    /// binary, so reaching the return value proves the panic was contained.
+    ///
+    /// # Safety
+    ///
+    /// `user_data` must be the context `callback` expects.
+    pub unsafe fn drive_stream(callback: ZekeStreamCallback, user_data: *mut c_void, chunks: &[&str]) -> usize {
+        let chunks: Vec<CString> = chunks.iter().map(|c| CString::new(*c).unwrap()).collect();
+        unsafe { drive_c_stream(callback, user_data, &chunks) }
+    }
+
+    /// [`drive_stream`] over chunks that are already null-terminated
+    ///
+    /// # Safety
+    ///
+    /// `user_data` must be the context `callback` expects.
+    pub unsafe fn drive_c_stream(callback: ZekeStreamCallback, user_data: *mut c_void, chunks: &[CString]) -> usize {
+        let callback = callback.expect("stream callback");
+        let total = chunks.len() as u32;
+
+        for (index, content) in chunks.iter().enumerate() {
+            let chunk = ZekeStreamChunk {
+                content: content.as_ptr(),
+                is_final: index + 1 == chunks.len(),
+                chunk_index: index as u32,
+                total_chunks: total,
+            };
+            if !unsafe { callback(&chunk, user_data) } {
+                return index + 1;
+            }
+        }
+        chunks.len()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,6 +249,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_catch_callback_panic() {
+        assert_eq!(catch_callback_panic(|| 7).unwrap(), 7);
+
+        let err = catch_callback_panic(|| panic!("chunk {} was bad", 3)).unwrap_err();
+        assert!(matches!(err, Error::CallbackPanicked { ref message } if message == "chunk 3 was bad"));
+        assert_eq!(err.category(), "streaming");
+
+        let err = catch_callback_panic(|| std::panic::panic_any(42)).unwrap_err();
+        assert_eq!(err.to_string(), "Stream callback panicked: unknown panic");
+    }
+
     #[test]
     fn test_string_with_null_bytes() {
         let result = CStringHolder::new("string\0with\0nulls");
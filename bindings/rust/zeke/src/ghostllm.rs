@@ -1,7 +1,10 @@
 //! GhostLLM GPU acceleration features
+//!
+//! Unsupported: the core has no GhostLLM client yet, so every call fails with
+//! [`Error::ProviderUnavailable`]. Only built with the opt-in `ghostllm` feature.
 
 use crate::{
-    error::{check_result_with_context, Error, Result},
+    error::{Error, Result},
     ffi_utils::CStringHolder,
     Zeke,
 };
@@ -59,7 +62,7 @@ impl GpuInfo {
 
     /// Get a health score from 0.0 to 1.0
     pub fn health_score(&self) -> f64 {
-        let mut score = 1.0;
+        let mut score: f64 = 1.0;
 
         // Penalize high utilization
         if self.utilization_percent > 90 {
@@ -137,6 +140,14 @@ impl<'a> GhostLLM<'a> {
         }
     }
 
+    /// Check whether the loaded Zeke library provides GhostLLM
+    ///
+    /// Always true unless the library is loaded at runtime (the `dynamic`
+    /// feature) and was built without it.
+    pub fn is_supported() -> bool {
+        zeke_has!(zeke_ghostllm_init)
+    }
+
     /// Initialize GhostLLM with default settings
     pub async fn initialize(&mut self) -> Result<()> {
         self.initialize_with_url("http://localhost:8080", true).await
@@ -147,11 +158,13 @@ impl<'a> GhostLLM<'a> {
         debug!("Initializing GhostLLM with URL: {}, GPU: {}", base_url, enable_gpu);
 
         let url_cstr = CStringHolder::new(base_url)?;
+        let core = self.zeke.core()?;
+        let handle = core.lock();
         let result = unsafe {
-            zeke_ghostllm_init(self.zeke.handle, url_cstr.as_ptr(), enable_gpu)
-        };
+            zeke_call!(zeke_ghostllm_init(handle.raw(), url_cstr.as_ptr(), enable_gpu))
+        }?;
 
-        check_result_with_context(result)?;
+        handle.check(result)?;
         self.initialized = true;
 
         info!("GhostLLM initialized successfully");
@@ -167,11 +180,13 @@ impl<'a> GhostLLM<'a> {
         debug!("Getting GPU information");
 
         let mut gpu_info = unsafe { std::mem::zeroed::<ZekeGpuInfo>() };
+        let core = self.zeke.core()?;
+        let handle = core.lock();
         let result = unsafe {
-            zeke_ghostllm_get_gpu_info(self.zeke.handle, &mut gpu_info)
-        };
+            zeke_call!(zeke_ghostllm_get_gpu_info(handle.raw(), &mut gpu_info))
+        }?;
 
-        check_result_with_context(result)?;
+        handle.check(result)?;
 
         let device_name = unsafe {
             crate::ffi_utils::c_string_to_string(gpu_info.device_name)?
@@ -187,9 +202,7 @@ impl<'a> GhostLLM<'a> {
         };
 
         // Free the FFI memory
-        unsafe {
-            zeke_free_gpu_info(&mut gpu_info);
-        }
+        unsafe { zeke_call!(zeke_free_gpu_info(&mut gpu_info)) }?;
 
         debug!("Retrieved GPU info: {:.1}% utilization, {}°C", 
                info.utilization_percent, info.temperature_celsius);
@@ -208,9 +221,10 @@ impl<'a> GhostLLM<'a> {
         let model_cstr = CStringHolder::new(model)?;
         let start_time = std::time::Instant::now();
 
+        let core = self.zeke.core()?;
         let result = unsafe {
-            zeke_ghostllm_benchmark(self.zeke.handle, model_cstr.as_ptr(), batch_size)
-        };
+            zeke_call!(zeke_ghostllm_benchmark(core.lock().raw(), model_cstr.as_ptr(), batch_size))
+        }?;
 
         let duration = start_time.elapsed();
         let success = result == ZekeErrorCode::ZEKE_SUCCESS;
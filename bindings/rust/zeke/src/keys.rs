@@ -0,0 +1,458 @@
+//! Pools of API keys shared by one provider
+//!
+//! Organizations often hold several keys per provider to spread rate limits.
+//! A [`KeyPool`] hands them out in turn, takes a key out of rotation when the
+//! provider rejects it, and counts usage per key. Keys are only ever reported
+//! by their [`key_id`], never by value.
+
+use crate::{Error, Result};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// How long a rate-limited key is skipped by default
+pub const DEFAULT_QUARANTINE: Duration = Duration::from_secs(60);
+
+/// How a [`KeyPool`] picks the key for the next request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeySelection {
+    /// Take each key in turn
+    #[default]
+    RoundRobin,
+    /// Take the key that has served the fewest requests
+    LeastUsed,
+}
+
+impl KeySelection {
+    /// Name used in configuration files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeySelection::RoundRobin => "round-robin",
+            KeySelection::LeastUsed => "least-used",
+        }
+    }
+}
+
+/// Identifier for an API key that is safe to log and report
+///
+/// The first 12 hex digits of the key's SHA-256 hash, prefixed with `key-`.
+pub fn key_id(key: &str) -> String {
+    let digest = Sha256::digest(key.as_bytes());
+    let hex: String = digest[..6].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("key-{}", hex)
+}
+
+/// Usage counters for one key of a [`KeyPool`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyUsage {
+    /// Hashed key identifier, see [`key_id`]
+    pub key_id: String,
+    /// Requests the key was handed out for
+    pub requests: u64,
+    /// Requests that failed
+    pub failures: u64,
+    /// Input tokens reported by the provider
+    pub input_tokens: u64,
+    /// Output tokens reported by the provider
+    pub output_tokens: u64,
+    /// Whether the key is currently out of rotation
+    pub quarantined: bool,
+}
+
+/// Why a key is out of rotation
+#[derive(Debug, Clone, Copy)]
+enum Hold {
+    /// Rate limited; usable again after the instant
+    Until(Instant),
+    /// Rejected; usable again only after [`KeyPool::release`]
+    UntilReleased,
+}
+
+#[derive(Debug, Default)]
+struct KeyState {
+    requests: u64,
+    failures: u64,
+    input_tokens: u64,
+    output_tokens: u64,
+    hold: Option<Hold>,
+}
+
+impl KeyState {
+    /// Whether the key can be handed out, lifting an expired hold
+    fn available(&mut self, now: Instant) -> bool {
+        match self.hold {
+            Some(Hold::Until(until)) if until <= now => {
+                self.hold = None;
+                true
+            }
+            Some(_) => false,
+            None => true,
+        }
+    }
+}
+
+struct PooledKey {
+    id: String,
+    secret: Secret<String>,
+    state: Mutex<KeyState>,
+}
+
+impl PooledKey {
+    fn state(&self) -> std::sync::MutexGuard<'_, KeyState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// API keys for one provider, handed out per request
+pub struct KeyPool {
+    provider: String,
+    keys: Vec<PooledKey>,
+    selection: KeySelection,
+    quarantine: Duration,
+    next: AtomicUsize,
+}
+
+impl fmt::Debug for KeyPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPool")
+            .field("provider", &self.provider)
+            .field("keys", &self.key_ids())
+            .field("selection", &self.selection)
+            .field("quarantine", &self.quarantine)
+            .finish()
+    }
+}
+
+impl KeyPool {
+    /// Create a pool for `provider`; duplicate keys are dropped
+    pub fn new<S, I>(provider: S, keys: I) -> Result<Self>
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = Secret<String>>,
+    {
+        let provider = provider.into();
+        let mut pooled: Vec<PooledKey> = Vec::new();
+        for secret in keys {
+            let id = key_id(secret.expose_secret());
+            if pooled.iter().all(|k| k.id != id) {
+                pooled.push(PooledKey {
+                    id,
+                    secret,
+                    state: Mutex::default(),
+                });
+            }
+        }
+
+        if pooled.is_empty() {
+            return Err(Error::ConfigError {
+                message: format!("Key pool for {} needs at least one API key", provider),
+            });
+        }
+
+        Ok(Self {
+            provider,
+            keys: pooled,
+            selection: KeySelection::default(),
+            quarantine: DEFAULT_QUARANTINE,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// A pool holding just one key
+    pub(crate) fn single<S: Into<String>>(provider: S, key: Secret<String>) -> Self {
+        Self {
+            provider: provider.into(),
+            keys: vec![PooledKey {
+                id: key_id(key.expose_secret()),
+                secret: key,
+                state: Mutex::default(),
+            }],
+            selection: KeySelection::default(),
+            quarantine: DEFAULT_QUARANTINE,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Choose how keys are picked
+    pub fn with_selection(mut self, selection: KeySelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Skip rate-limited keys for this long
+    pub fn with_quarantine(mut self, quarantine: Duration) -> Self {
+        self.quarantine = quarantine;
+        self
+    }
+
+    /// Provider the keys belong to
+    pub fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    /// Number of keys in the pool
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether the pool has no keys; never true for a constructed pool
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Hashed identifiers of the keys, in pool order
+    pub fn key_ids(&self) -> Vec<&str> {
+        self.keys.iter().map(|k| k.id.as_str()).collect()
+    }
+
+    /// Take a key for one request
+    ///
+    /// Fails with [`Error::ProviderUnavailable`] when every key is quarantined.
+    pub fn acquire(self: &Arc<Self>) -> Result<KeyLease> {
+        let now = Instant::now();
+        let count = self.keys.len();
+
+        let index = match self.selection {
+            KeySelection::RoundRobin => {
+                let start = self.next.fetch_add(1, Ordering::Relaxed);
+                (0..count)
+                    .map(|offset| (start + offset) % count)
+                    .find(|&i| self.keys[i].state().available(now))
+            }
+            KeySelection::LeastUsed => self
+                .keys
+                .iter()
+                .enumerate()
+                .filter_map(|(i, key)| {
+                    let mut state = key.state();
+                    state.available(now).then_some((i, state.requests))
+                })
+                .min_by_key(|&(_, requests)| requests)
+                .map(|(i, _)| i),
+        };
+
+        let Some(index) = index else {
+            return Err(Error::provider_unavailable(
+                self.provider.clone(),
+                format!("all {} API keys are quarantined", count),
+            ));
+        };
+
+        self.keys[index].state().requests += 1;
+        debug!("Using {} key {}", self.provider, self.keys[index].id);
+        Ok(KeyLease {
+            pool: Arc::clone(self),
+            index,
+        })
+    }
+
+    /// Put a quarantined key back into rotation
+    ///
+    /// Returns `false` if no key has this identifier.
+    pub fn release(&self, key_id: &str) -> bool {
+        match self.keys.iter().find(|k| k.id == key_id) {
+            Some(key) => {
+                key.state().hold = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Usage counters for every key, in pool order
+    pub fn usage(&self) -> Vec<KeyUsage> {
+        let now = Instant::now();
+        self.keys
+            .iter()
+            .map(|key| {
+                let mut state = key.state();
+                KeyUsage {
+                    key_id: key.id.clone(),
+                    requests: state.requests,
+                    failures: state.failures,
+                    input_tokens: state.input_tokens,
+                    output_tokens: state.output_tokens,
+                    quarantined: !state.available(now),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A key handed out by [`KeyPool::acquire`] for one request
+pub struct KeyLease {
+    pool: Arc<KeyPool>,
+    index: usize,
+}
+
+impl fmt::Debug for KeyLease {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyLease")
+            .field("provider", &self.pool.provider)
+            .field("key_id", &self.id())
+            .finish()
+    }
+}
+
+impl KeyLease {
+    fn key(&self) -> &PooledKey {
+        &self.pool.keys[self.index]
+    }
+
+    /// Hashed identifier of the key
+    pub fn id(&self) -> &str {
+        &self.key().id
+    }
+
+    /// The key itself
+    pub fn secret(&self) -> &Secret<String> {
+        &self.key().secret
+    }
+
+    /// Attribute token usage reported by the provider to the key
+    pub fn record_usage(&self, input_tokens: Option<u32>, output_tokens: Option<u32>) {
+        let mut state = self.key().state();
+        state.input_tokens += u64::from(input_tokens.unwrap_or(0));
+        state.output_tokens += u64::from(output_tokens.unwrap_or(0));
+    }
+
+    /// Record a failed request
+    ///
+    /// Authentication failures quarantine the key until it is released;
+    /// rate limits quarantine it for the provider's retry-after delay, or
+    /// the pool's quarantine period when none was reported.
+    pub fn record_error(&self, error: &Error) {
+        let hold = if error.is_auth_error() {
+            Some(Hold::UntilReleased)
+        } else if error.is_rate_limit() {
+            let delay = error.retry_after().unwrap_or(self.pool.quarantine);
+            Some(Hold::Until(Instant::now() + delay))
+        } else {
+            None
+        };
+
+        let mut state = self.key().state();
+        state.failures += 1;
+        if let Some(hold) = hold {
+            warn!(
+                "Quarantining {} key {} after {} error",
+                self.pool.provider,
+                self.id(),
+                error.category()
+            );
+            state.hold = Some(hold);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(keys: &[&str]) -> Arc<KeyPool> {
+        Arc::new(
+            KeyPool::new("test", keys.iter().map(|k| Secret::new(k.to_string()))).unwrap(),
+        )
+    }
+
+    fn take(pool: &Arc<KeyPool>) -> String {
+        pool.acquire().unwrap().secret().expose_secret().clone()
+    }
+
+    #[test]
+    fn test_key_id_hides_key() {
+        let id = key_id("sk-secret-value");
+        assert_eq!(id, key_id("sk-secret-value"));
+        assert_ne!(id, key_id("sk-other-value"));
+        assert_eq!(id.len(), "key-".len() + 12);
+        assert!(!id.contains("secret"));
+    }
+
+    #[test]
+    fn test_round_robin_and_duplicates() {
+        let pool = pool(&["a", "b", "a", "c"]);
+        assert_eq!(pool.len(), 3);
+        let order: Vec<String> = (0..4).map(|_| take(&pool)).collect();
+        assert_eq!(order, ["a", "b", "c", "a"]);
+        assert!(KeyPool::new("test", Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_least_used() {
+        let pool = Arc::new(
+            KeyPool::new("test", ["a", "b"].map(|k| Secret::new(k.to_string())))
+                .unwrap()
+                .with_selection(KeySelection::LeastUsed),
+        );
+        let first = pool.acquire().unwrap();
+        let second = pool.acquire().unwrap();
+        assert_ne!(first.id(), second.id());
+        assert!(pool.usage().iter().all(|u| u.requests == 1));
+    }
+
+    #[test]
+    fn test_quarantine() {
+        let pool = pool(&["a", "b"]);
+
+        pool.acquire().unwrap().record_error(&Error::authentication("test", "revoked"));
+        assert_eq!(take(&pool), "b");
+        assert_eq!(take(&pool), "b");
+
+        pool.acquire().unwrap().record_error(&Error::rate_limited("test", "slow down"));
+        assert!(pool.acquire().is_err());
+
+        // Rate limit holds expire on their own
+        let pool = Arc::new(
+            KeyPool::new("test", [Secret::new("a".to_string())])
+                .unwrap()
+                .with_quarantine(Duration::ZERO),
+        );
+        pool.acquire().unwrap().record_error(&Error::rate_limited("test", "slow down"));
+        assert_eq!(take(&pool), "a");
+
+        // A reported retry-after overrides the quarantine period
+        pool.acquire().unwrap().record_error(
+            &Error::rate_limited("test", "slow down").with_retry_after(Duration::from_secs(60)),
+        );
+        assert!(pool.acquire().is_err());
+        assert!(pool.release(&pool.usage()[0].key_id));
+
+        // Other errors leave the key in rotation
+        pool.acquire().unwrap().record_error(&Error::network("reset"));
+        assert_eq!(take(&pool), "a");
+    }
+
+    #[test]
+    fn test_release_and_usage() {
+        let pool = pool(&["a", "b"]);
+        let lease = pool.acquire().unwrap();
+        lease.record_usage(Some(10), Some(5));
+        lease.record_error(&Error::authentication("test", "revoked"));
+        let id = lease.id().to_string();
+
+        let usage = pool.usage();
+        assert_eq!(usage[0].key_id, id);
+        assert_eq!((usage[0].input_tokens, usage[0].output_tokens), (10, 5));
+        assert_eq!((usage[0].requests, usage[0].failures), (1, 1));
+        assert!(usage[0].quarantined);
+
+        assert!(pool.release(&id));
+        assert!(!pool.release("key-unknown"));
+        assert!(!pool.usage()[0].quarantined);
+    }
+
+    #[test]
+    fn test_debug_output_omits_keys() {
+        let pool = pool(&["sk-hidden-key"]);
+        let lease = pool.acquire().unwrap();
+        for output in [format!("{:?}", pool), format!("{:?}", lease), format!("{:?}", pool.usage())] {
+            assert!(!output.contains("sk-hidden-key"), "{}", output);
+        }
+    }
+}
@@ -1,19 +1,27 @@
 //! # Zeke - AI Development Companion for Rust
 //!
 //! Zeke is a powerful AI development companion that provides seamless integration
-//! with multiple AI providers including OpenAI, Claude, GitHub Copilot, Ollama, and GhostLLM.
+//! with multiple AI providers including OpenAI, Claude, GitHub Copilot, Ollama, GhostLLM,
+//! xAI, Google Gemini, Azure OpenAI and OMEN.
 //!
 //! This crate provides safe, high-level Rust bindings for the Zeke Zig library,
 //! offering memory-safe access to AI capabilities with automatic resource management.
 //!
 //! ## Features
 //!
-//! - **Multi-Provider Support**: OpenAI, Claude, GitHub Copilot, Ollama, GhostLLM
-//! - **GPU Acceleration**: GhostLLM integration with CUDA/Metal support
+//! - **Multi-Provider Support**: OpenAI, Claude, GitHub Copilot, Ollama, GhostLLM, xAI, Gemini, Azure OpenAI, OMEN
 //! - **Streaming Responses**: Real-time token streaming for interactive applications
 //! - **Automatic Failover**: Health monitoring and provider switching
 //! - **Memory Safety**: RAII-based resource management
 //! - **Async Support**: Tokio integration for non-blocking operations
+//! - **Model Routing**: Aliases like `code-fast` resolved from `routing_hints.json`
+//! - **Native Backends**: Optional pure-Rust HTTP clients (`ollama`, `anthropic`, `openai` features)
+//! - **Live Reload**: Watch config files and apply changes to a running client (`watch` feature)
+//! - **Key Rotation**: Spread requests over several API keys per provider with [`KeyPool`]
+//! - **Instance Pools**: Run requests in parallel on several core instances with `ZekePool`
+//! - **Zero-Copy Responses**: Borrow large outputs from the core with `chat_raw` and collect streams into a [`ChunkArena`]
+//! - **Batch Chat**: Send hundreds of prompts with bounded concurrency and per-prompt results (`chat_batch`)
+//! - **Runtime Loading**: Load libzeke when first used and skip the parts it lacks (`dynamic` feature, see [`LibraryFeatures`])
 //!
 //! ## Quick Start
 //!
@@ -69,31 +77,115 @@
 
 // Re-export commonly used types
 pub use config::{Config, ConfigBuilder};
+pub use credentials::{
+    default_credential_file, CredentialChain, CredentialStore, EnvStore, SecretServiceStore,
+};
 pub use error::{Error, Result};
+pub use keys::{key_id, KeyLease, KeyPool, KeySelection, KeyUsage, DEFAULT_QUARANTINE};
+pub use library::LibraryFeatures;
+pub use layers::{ConfigExplanation, ConfigLoader, ConfigSource, ExplainedField, Provenance};
+pub use native_config::NativeConfig;
 pub use provider::Provider;
+pub use raw::{ChunkArena, RawResponse};
+pub use registry::{AuthScheme, CustomProvider, ProviderRegistry};
+pub use discovery::{ModelCache, ModelDiscovery, ModelList, ModelSource};
+pub use catalog::{Capability, ModelCatalog, ModelInfo, Pricing};
 pub use response::{ChatResponse, StreamChunk};
+pub use zeke_toml::{DefaultSection, FeatureSection, ProviderSection, ZekeToml};
 pub use zeke::Zeke;
 
+#[cfg(feature = "credential-file")]
+#[cfg_attr(docsrs, doc(cfg(feature = "credential-file")))]
+pub use credentials::EncryptedFileStore;
+
 #[cfg(feature = "ghostllm")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ghostllm")))]
 pub use ghostllm::GhostLLM;
 
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use backend::{AccessToken, ChatBackend, ChatMessage, ChatRequest, Role};
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use batch::{
+    BatchItem, BatchOptions, BatchOrder, BatchProgress, BatchResult, BatchSummary,
+    DEFAULT_BATCH_CONCURRENCY,
+};
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use pool::{PooledZeke, ZekePool, DEFAULT_HEALTH_INTERVAL};
+
+#[cfg(feature = "watch")]
+#[cfg_attr(docsrs, doc(cfg(feature = "watch")))]
+pub use watch::{ConfigStream, DEFAULT_DEBOUNCE};
+
+#[cfg(feature = "ollama")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ollama")))]
+pub use backend::ollama::OllamaBackend;
+
+#[cfg(feature = "anthropic")]
+#[cfg_attr(docsrs, doc(cfg(feature = "anthropic")))]
+pub use backend::anthropic::AnthropicBackend;
+
+#[cfg(feature = "openai")]
+#[cfg_attr(docsrs, doc(cfg(feature = "openai")))]
+pub use backend::openai::OpenAIBackend;
+
 // Internal modules
+mod catalog;
 mod config;
+mod credentials;
+mod discovery;
 mod error;
+mod keys;
+mod layers;
+mod library;
+mod native_config;
 mod provider;
+mod raw;
+mod registry;
 mod response;
 mod zeke;
+mod zeke_toml;
 
 #[cfg(feature = "ghostllm")]
 mod ghostllm;
 
+#[cfg(feature = "async")]
+mod batch;
+
+#[cfg(feature = "async")]
+mod pool;
+
 #[cfg(feature = "async")]
 mod stream;
 
+#[cfg(feature = "watch")]
+mod watch;
+
+// Model alias routing
+pub mod routing;
+
+// OAuth sign-in flows
+#[cfg(feature = "oauth")]
+#[cfg_attr(docsrs, doc(cfg(feature = "oauth")))]
+pub mod auth;
+
+// Native provider backends
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod backend;
+
 // Utility modules
 mod ffi_utils;
 
+// Hooks for benches/; not part of the public API
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench;
+
 /// Prelude module for convenient imports
 pub mod prelude {
     pub use crate::{Config, ConfigBuilder, Error, Provider, Result, Zeke};
@@ -106,9 +198,18 @@ pub mod prelude {
 /// The version of this crate
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// The version of the underlying Zeke library
+/// The release of the Zeke library these bindings were written against
+///
+/// Informational only; compatibility with the loaded library is decided by
+/// [`ZEKE_ABI_VERSION`].
 pub const ZEKE_VERSION: &str = "0.2.0";
 
+/// The library ABI revision these bindings require
+///
+/// [`Zeke::new`] compares it, and every struct layout, with what the library
+/// reports and fails with [`Error::AbiMismatch`] on any difference.
+pub const ZEKE_ABI_VERSION: u32 = zeke_sys::ZEKE_ABI_VERSION;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,173 @@
+//! Runtime view of the Zeke core library
+//!
+//! With the `dynamic` feature the library is loaded when first used and may
+//! be older or built with fewer options than these bindings. Optional parts
+//! are probed here so callers can skip them instead of failing mid-request.
+
+use crate::error::{check_result_with_context, Error, Result};
+use zeke_sys::abi::StructInfo;
+use zeke_sys::{zeke_call, zeke_has, LoadError, ZekeStructLayout, ZEKE_ABI_VERSION};
+
+/// Optional parts of the core library that are available
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LibraryFeatures {
+    /// The library could be loaded at all
+    pub loaded: bool,
+    /// GhostLLM GPU acceleration (`zeke_ghostllm_*`)
+    pub ghostllm: bool,
+    /// Streaming chat (`zeke_chat_stream`)
+    pub streaming: bool,
+    /// Configuration files (`zeke_load_config` and friends)
+    pub native_config: bool,
+    /// Provider health reports (`zeke_get_provider_status`)
+    pub provider_status: bool,
+}
+
+impl LibraryFeatures {
+    /// Probe the core library, loading it if necessary
+    pub fn detect() -> Self {
+        Self {
+            loaded: load().is_ok(),
+            ghostllm: zeke_has!(zeke_ghostllm_init)
+                && zeke_has!(zeke_ghostllm_get_gpu_info)
+                && zeke_has!(zeke_ghostllm_benchmark)
+                && zeke_has!(zeke_free_gpu_info),
+            streaming: zeke_has!(zeke_chat_stream),
+            native_config: zeke_has!(zeke_load_config)
+                && zeke_has!(zeke_save_config)
+                && zeke_has!(zeke_config_from)
+                && zeke_has!(zeke_config_get)
+                && zeke_has!(zeke_free_config),
+            provider_status: zeke_has!(zeke_get_provider_status),
+        }
+    }
+}
+
+/// Check that the core library was built from the same header as these bindings
+///
+/// Compares the ABI version, then the size, alignment and field offsets of
+/// every public structure, and reports the first difference.
+pub(crate) fn check_abi() -> Result<()> {
+    let found = match unsafe { zeke_call!(zeke_abi_version()) } {
+        Ok(version) => version,
+        // Libraries from before the handshake have no version to report
+        Err(LoadError::MissingSymbol { .. }) => 0,
+        Err(err) => return Err(err.into()),
+    };
+    if found != ZEKE_ABI_VERSION {
+        return Err(mismatch("ABI version", ZEKE_ABI_VERSION as usize, found as usize));
+    }
+
+    for info in zeke_sys::abi::structs() {
+        let mut reported = ZekeStructLayout::default();
+        let result = unsafe { zeke_call!(zeke_struct_layout(info.id, &mut reported)) }?;
+        check_result_with_context(result)?;
+        compare_layout(&info, &reported)?;
+    }
+    Ok(())
+}
+
+/// Find the first difference between the expected and reported layouts
+fn compare_layout(info: &StructInfo, reported: &ZekeStructLayout) -> Result<()> {
+    let expected = &info.layout;
+    let checks = [
+        ("size", expected.size, reported.size),
+        ("alignment", expected.alignment, reported.alignment),
+        ("field count", expected.field_count, reported.field_count),
+    ];
+    for (what, expected, found) in checks {
+        if expected != found {
+            return Err(mismatch(&format!("{} {}", info.name, what), expected, found));
+        }
+    }
+
+    for (i, field) in info.fields.iter().enumerate() {
+        if expected.offsets[i] != reported.offsets[i] {
+            return Err(mismatch(
+                &format!("{}.{} offset", info.name, field),
+                expected.offsets[i],
+                reported.offsets[i],
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn mismatch(item: &str, expected: usize, found: usize) -> Error {
+    Error::AbiMismatch {
+        item: item.to_string(),
+        expected: expected as u64,
+        found: found as u64,
+    }
+}
+
+/// Load the core library, reporting why it is unavailable
+#[cfg(feature = "dynamic")]
+fn load() -> std::result::Result<(), LoadError> {
+    zeke_sys::dynamic::library().map(|_| ())
+}
+
+/// The core library is linked in, so it is always available
+#[cfg(not(feature = "dynamic"))]
+fn load() -> std::result::Result<(), LoadError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_is_consistent() {
+        let features = LibraryFeatures::detect();
+        if !features.loaded {
+            assert!(!features.ghostllm);
+            assert!(!features.streaming);
+            assert!(!features.native_config);
+            assert!(!features.provider_status);
+        }
+        #[cfg(not(feature = "dynamic"))]
+        assert!(features.loaded && features.ghostllm && features.streaming);
+    }
+
+    fn config_info() -> StructInfo {
+        zeke_sys::abi::structs()[0]
+    }
+
+    #[test]
+    fn test_matching_layout() {
+        let info = config_info();
+        assert!(compare_layout(&info, &info.layout).is_ok());
+    }
+
+    #[test]
+    fn test_layout_size_mismatch() {
+        let info = config_info();
+        let reported = ZekeStructLayout {
+            size: info.layout.size + 8,
+            ..info.layout
+        };
+
+        match compare_layout(&info, &reported) {
+            Err(Error::AbiMismatch { item, expected, found }) => {
+                assert_eq!(item, "ZekeConfig size");
+                assert_eq!(found, expected + 8);
+            }
+            other => panic!("expected an ABI mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_layout_offset_mismatch() {
+        let info = config_info();
+        let mut reported = info.layout;
+        reported.offsets[9] += 4;
+
+        match compare_layout(&info, &reported) {
+            Err(Error::AbiMismatch { item, .. }) => {
+                assert_eq!(item, "ZekeConfig.timeout_ms offset");
+            }
+            other => panic!("expected an ABI mismatch, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Configuration files read and written by the Zig core
+
+use crate::{
+    error::check_result_with_context,
+    ffi_utils::{c_string_to_string, CStringHolder, CStringManager},
+    layers::ConfigSource,
+    Config, Error, Provider, Result,
+};
+use secrecy::Secret;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+use tracing::debug;
+use zeke_sys::*;
+
+/// A configuration held by the Zig core
+///
+/// Loading and saving go through `zeke_load_config` and `zeke_save_config`,
+/// so files are read exactly as the Zig CLI reads them. The handle is freed
+/// when the value is dropped.
+///
+/// ```rust,no_run
+/// use zeke::NativeConfig;
+///
+/// # fn example() -> zeke::Result<()> {
+/// let native = NativeConfig::load("zeke.toml")?;
+/// let config = native.to_config()?;
+/// println!("{} / {}", config.provider, config.model);
+/// # Ok(())
+/// # }
+/// ```
+pub struct NativeConfig {
+    raw: NonNull<ZekeConfigHandle>,
+    path: Option<PathBuf>,
+}
+
+// Safety: the handle is owned exclusively and the core keeps no references to it
+unsafe impl Send for NativeConfig {}
+
+impl fmt::Debug for NativeConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeConfig")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl NativeConfig {
+    /// Load a configuration file with the Zig loader
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        debug!("Loading native config from {}", path.display());
+
+        let path_cstr = CStringHolder::new(path_str(path)?)?;
+        let raw = unsafe { zeke_call!(zeke_load_config(path_cstr.as_ptr())) }?;
+        let raw = NonNull::new(raw).ok_or_else(|| Error::ConfigError {
+            message: format!(
+                "Zig core failed to load {}: {}",
+                path.display(),
+                get_last_error().unwrap_or_else(|| "unknown error".to_string())
+            ),
+        })?;
+
+        Ok(Self {
+            raw,
+            path: Some(path.to_path_buf()),
+        })
+    }
+
+    /// Hand a configuration to the Zig core
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut strings = CStringManager::new();
+        let ffi_config = to_ffi(config, &mut strings)?;
+
+        let raw = unsafe { zeke_call!(zeke_config_from(&ffi_config)) }?;
+        let raw = NonNull::new(raw).ok_or_else(|| Error::ConfigError {
+            message: format!(
+                "Zig core rejected the configuration: {}",
+                get_last_error().unwrap_or_else(|| "unknown error".to_string())
+            ),
+        })?;
+
+        Ok(Self { raw, path: None })
+    }
+
+    /// File the configuration was loaded from, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Write the configuration with the Zig writer
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        debug!("Saving native config to {}", path.display());
+
+        let path_cstr = CStringHolder::new(path_str(path)?)?;
+        let result = unsafe { zeke_call!(zeke_save_config(self.raw.as_ptr(), path_cstr.as_ptr())) }?;
+        check_result_with_context(result)
+    }
+
+    /// Convert to a [`Config`]
+    ///
+    /// Fields the core does not store keep their defaults, and every field it
+    /// does store is recorded as coming from the loaded file.
+    pub fn to_config(&self) -> Result<Config> {
+        let mut ffi_config = unsafe { std::mem::zeroed::<ZekeConfig>() };
+        let result = unsafe { zeke_call!(zeke_config_get(self.raw.as_ptr(), &mut ffi_config)) }?;
+        check_result_with_context(result)?;
+
+        // Safety: on success every string points into the handle, which outlives this call
+        let mut config = unsafe { from_ffi(&ffi_config)? };
+        if let Some(path) = &self.path {
+            for field in FFI_FIELDS {
+                config
+                    .provenance
+                    .record(field, ConfigSource::File(path.clone()));
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl Drop for NativeConfig {
+    fn drop(&mut self) {
+        // A handle only exists if the library loaded and exported the loaders
+        let _ = unsafe { zeke_call!(zeke_free_config(self.raw.as_ptr())) };
+    }
+}
+
+impl TryFrom<&Config> for NativeConfig {
+    type Error = Error;
+
+    fn try_from(config: &Config) -> Result<Self> {
+        Self::from_config(config)
+    }
+}
+
+impl TryFrom<&NativeConfig> for Config {
+    type Error = Error;
+
+    fn try_from(native: &NativeConfig) -> Result<Self> {
+        native.to_config()
+    }
+}
+
+/// `Config` fields carried by `ZekeConfig`
+const FFI_FIELDS: [&str; 10] = [
+    "provider",
+    "base_url",
+    "api_key",
+    "model",
+    "temperature",
+    "max_tokens",
+    "streaming",
+    "enable_gpu",
+    "enable_fallback",
+    "timeout_ms",
+];
+
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str().ok_or_else(|| Error::InvalidParameter {
+        parameter: "path".to_string(),
+        message: format!("{} is not valid UTF-8", path.display()),
+    })
+}
+
+/// Build the C view of a config; the strings live in `strings`
+///
+/// Unset strings are passed as empty rather than null.
+pub(crate) fn to_ffi(config: &Config, strings: &mut CStringManager) -> Result<ZekeConfig> {
+    Ok(ZekeConfig {
+        base_url: strings.add(&config.effective_base_url())?,
+        api_key: strings.add(config.api_key().unwrap_or_default())?,
+        provider: config.provider.core_client() as i32,
+        model_name: strings.add(&config.model)?,
+        temperature: config.temperature,
+        max_tokens: config.max_tokens,
+        stream: config.streaming,
+        enable_gpu: config.enable_gpu,
+        enable_fallback: config.enable_fallback,
+        timeout_ms: config.timeout_ms,
+    })
+}
+
+/// Read a config out of its C view
+///
+/// # Safety
+///
+/// Every non-null string in `raw` must be a valid null-terminated C string.
+pub(crate) unsafe fn from_ffi(raw: &ZekeConfig) -> Result<Config> {
+    let string = |ptr: *const std::os::raw::c_char| -> Result<Option<String>> {
+        if ptr.is_null() {
+            return Ok(None);
+        }
+        let value = unsafe { c_string_to_string(ptr)? };
+        Ok(Some(value).filter(|v| !v.is_empty()))
+    };
+
+    let provider = Provider::from_ffi_int(raw.provider).ok_or_else(|| Error::ConfigError {
+        message: format!("Unknown provider {} in native config", raw.provider),
+    })?;
+    let base_url = string(raw.base_url)?.filter(|url| url != provider.default_base_url());
+
+    Ok(Config {
+        provider,
+        base_url,
+        api_key: string(raw.api_key)?.map(Secret::new),
+        model: string(raw.model_name)?.unwrap_or_else(|| provider.default_model().to_string()),
+        temperature: raw.temperature,
+        max_tokens: raw.max_tokens,
+        streaming: raw.stream,
+        enable_gpu: raw.enable_gpu,
+        enable_fallback: raw.enable_fallback,
+        timeout_ms: raw.timeout_ms,
+        ..Config::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ffi_round_trip() {
+        let config = Config::builder()
+            .provider(Provider::Claude)
+            .api_key("sk-ant-test")
+            .model("claude-3-5-haiku-20241022")
+            .temperature(0.2)
+            .max_tokens(512)
+            .streaming(true)
+            .timeout_secs(45)
+            .build()
+            .unwrap();
+
+        let mut strings = CStringManager::new();
+        let raw = to_ffi(&config, &mut strings).unwrap();
+        let back = unsafe { from_ffi(&raw) }.unwrap();
+
+        assert_eq!(back.provider, Provider::Claude);
+        assert_eq!(back.base_url, None);
+        assert_eq!(back.api_key(), Some("sk-ant-test"));
+        assert_eq!(back.model, config.model);
+        assert_eq!(back.temperature, 0.2);
+        assert_eq!(back.max_tokens, 512);
+        assert!(back.streaming);
+        assert_eq!(back.timeout_ms, 45_000);
+    }
+
+    #[test]
+    fn test_from_ffi_empty_strings() {
+        let mut config = Config::default().with_provider(Provider::Ollama);
+        config.base_url = Some("http://gpu-box:11434".to_string());
+
+        let mut strings = CStringManager::new();
+        let mut raw = to_ffi(&config, &mut strings).unwrap();
+        raw.model_name = strings.add("").unwrap();
+        let back = unsafe { from_ffi(&raw) }.unwrap();
+
+        assert_eq!(back.api_key(), None);
+        assert_eq!(back.base_url.as_deref(), Some("http://gpu-box:11434"));
+        assert_eq!(back.model, Provider::Ollama.default_model());
+
+        raw.provider = 99;
+        assert!(unsafe { from_ffi(&raw) }.is_err());
+    }
+}
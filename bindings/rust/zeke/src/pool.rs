@@ -0,0 +1,408 @@
+//! Pools of Zig core instances for concurrent requests
+//!
+//! A core handle keeps per-handle state, so one [`Zeke`] runs its calls into
+//! the core one at a time. A [`ZekePool`] owns several instances created from
+//! the same [`Config`] and lends one to each request, so up to `size` requests
+//! run in parallel and any more wait for an instance to come back.
+
+use crate::{ffi_utils::run_blocking, BatchOptions, BatchResult, Config, Error, Result, Zeke};
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, warn};
+
+/// How long an idle instance goes without a health check by default
+pub const DEFAULT_HEALTH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Creates a pool's instances from its config
+type Factory = Arc<dyn Fn(Config) -> Result<Zeke> + Send + Sync>;
+
+#[derive(Debug)]
+struct Slot {
+    zeke: Zeke,
+    checked_at: Instant,
+}
+
+/// A fixed number of [`Zeke`] instances shared by concurrent requests
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use zeke::{Config, Provider, ZekePool};
+///
+/// # async fn example() -> zeke::Result<()> {
+/// let config = Config::builder().provider(Provider::Ollama).build()?;
+/// let pool = Arc::new(ZekePool::new(config, 4)?);
+///
+/// let zeke = pool.acquire().await?;
+/// let response = zeke.chat("Hello").await?;
+/// println!("{}", response.content);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ZekePool {
+    config: Config,
+    size: usize,
+    factory: Factory,
+    idle: Mutex<Vec<Slot>>,
+    permits: Arc<Semaphore>,
+    health_interval: Duration,
+}
+
+impl ZekePool {
+    /// Create `size` instances from `config`
+    ///
+    /// Fails if `size` is zero or any instance fails to initialize.
+    pub fn new(config: Config, size: usize) -> Result<Self> {
+        Self::with_factory(config, size, Zeke::new)
+    }
+
+    /// Create `size` instances with `factory`, which also makes replacements
+    ///
+    /// For instances that need more than the config, such as ones serving a
+    /// native backend through [`Zeke::with_backend`]. The factory runs on the
+    /// blocking pool when the pool replaces an instance.
+    pub fn with_factory<F>(config: Config, size: usize, factory: F) -> Result<Self>
+    where
+        F: Fn(Config) -> Result<Zeke> + Send + Sync + 'static,
+    {
+        if size == 0 {
+            return Err(Error::ConfigError {
+                message: "Zeke pool needs at least one instance".to_string(),
+            });
+        }
+
+        let now = Instant::now();
+        let idle = (0..size)
+            .map(|_| {
+                Ok(Slot {
+                    zeke: factory(config.clone())?,
+                    checked_at: now,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        debug!("Created pool of {} {} instances", size, config.provider);
+
+        Ok(Self {
+            config,
+            size,
+            factory: Arc::new(factory),
+            idle: Mutex::new(idle),
+            permits: Arc::new(Semaphore::new(size)),
+            health_interval: DEFAULT_HEALTH_INTERVAL,
+        })
+    }
+
+    /// Health-check an instance before lending it if it has been idle this long
+    ///
+    /// Each check is a round trip to the provider; [`Duration::MAX`] turns
+    /// checks off.
+    pub fn with_health_interval(mut self, interval: Duration) -> Self {
+        self.health_interval = interval;
+        self
+    }
+
+    /// Configuration every instance is created from
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Most instances lent out at once
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Instances that can be lent out right now
+    pub fn available(&self) -> usize {
+        self.permits.available_permits()
+    }
+
+    /// Borrow an instance for one request, waiting while all are in use
+    ///
+    /// An instance that fails its health check is replaced before it is lent.
+    pub async fn acquire(self: &Arc<Self>) -> Result<PooledZeke> {
+        let permit = Arc::clone(&self.permits)
+            .acquire_owned()
+            .await
+            .expect("pool semaphore is never closed");
+        self.checkout(permit).await
+    }
+
+    /// Borrow an instance without waiting
+    ///
+    /// Fails with [`Error::ProviderUnavailable`] when every instance is in use.
+    pub async fn try_acquire(self: &Arc<Self>) -> Result<PooledZeke> {
+        let permit = Arc::clone(&self.permits).try_acquire_owned().map_err(|_| {
+            Error::provider_unavailable(
+                self.config.provider.to_string(),
+                format!("all {} pooled instances are in use", self.size),
+            )
+        })?;
+        self.checkout(permit).await
+    }
+
+    /// Send many chat messages in parallel across the pool's instances
+    ///
+    /// Like [`Zeke::chat_batch`], but each prompt runs on its own task with its
+    /// own instance, so at most `min(concurrency, size)` requests are in the
+    /// core at once.
+    pub async fn chat_batch<I>(self: &Arc<Self>, prompts: I, options: BatchOptions) -> BatchResult
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let prompts = prompts.into_iter().map(Into::into).collect();
+        crate::batch::run(prompts, &options, |prompt: String| {
+            let pool = Arc::clone(self);
+            let task = tokio::spawn(async move {
+                let zeke = pool.acquire().await?;
+                zeke.chat(&prompt).await
+            });
+            async move {
+                task.await
+                    .unwrap_or_else(|e| Err(Error::custom(format!("batch task failed: {}", e))))
+            }
+        })
+        .await
+    }
+
+    async fn checkout(self: &Arc<Self>, permit: OwnedSemaphorePermit) -> Result<PooledZeke> {
+        // Holding a permit guarantees an idle slot, unless one was discarded
+        let slot = self.idle().pop();
+        let slot = match slot {
+            Some(slot) if slot.checked_at.elapsed() < self.health_interval => slot,
+            Some(slot) => self.checked(slot).await?,
+            None => self.create().await?,
+        };
+
+        Ok(PooledZeke {
+            slot: Some(slot),
+            pool: Arc::clone(self),
+            _permit: permit,
+        })
+    }
+
+    /// Health-check an instance, replacing it if the check fails
+    async fn checked(&self, slot: Slot) -> Result<Slot> {
+        match slot.zeke.health_check().await {
+            Ok(()) => Ok(Slot {
+                checked_at: Instant::now(),
+                ..slot
+            }),
+            Err(e) => {
+                warn!("Replacing pooled {} instance after failed health check: {}", self.config.provider, e);
+                drop(slot);
+                self.create().await
+            }
+        }
+    }
+
+    /// Create an instance on the blocking pool, as initializing a core blocks
+    async fn create(&self) -> Result<Slot> {
+        debug!("Creating pooled {} instance", self.config.provider);
+        let factory = Arc::clone(&self.factory);
+        let config = self.config.clone();
+        Ok(Slot {
+            zeke: run_blocking(move || factory(config)).await?,
+            checked_at: Instant::now(),
+        })
+    }
+
+    fn idle(&self) -> MutexGuard<'_, Vec<Slot>> {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl fmt::Debug for ZekePool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZekePool")
+            .field("config", &self.config)
+            .field("size", &self.size)
+            .field("idle", &self.idle)
+            .field("permits", &self.permits)
+            .field("health_interval", &self.health_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An instance borrowed from a [`ZekePool`], returned when dropped
+#[derive(Debug)]
+pub struct PooledZeke {
+    slot: Option<Slot>,
+    pool: Arc<ZekePool>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PooledZeke {
+    /// Destroy this instance instead of returning it
+    ///
+    /// For an instance known to be broken; the pool creates a replacement the
+    /// next time one is needed.
+    pub fn discard(mut self) {
+        self.slot = None;
+    }
+}
+
+impl Deref for PooledZeke {
+    type Target = Zeke;
+
+    fn deref(&self) -> &Zeke {
+        &self.slot.as_ref().expect("slot is only taken on drop").zeke
+    }
+}
+
+impl Drop for PooledZeke {
+    fn drop(&mut self) {
+        // Return the slot before the permit is released, so the next holder finds it
+        if let Some(slot) = self.slot.take() {
+            self.pool.idle().push(slot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{ChatBackend, ChatRequest, ChunkStream};
+    use crate::{ChatResponse, Provider};
+    use futures::future::BoxFuture;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    fn test_config() -> Config {
+        Config::builder()
+            .provider(Provider::Ollama)
+            .model("llama3")
+            .build()
+            .unwrap()
+    }
+
+    /// Backend whose health the test switches
+    #[derive(Debug)]
+    struct TestBackend {
+        healthy: Arc<AtomicBool>,
+    }
+
+    impl ChatBackend for TestBackend {
+        fn provider(&self) -> Provider {
+            Provider::Ollama
+        }
+
+        fn chat(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse>> {
+            Box::pin(async move {
+                Ok(crate::response::ResponseBuilder::new()
+                    .content("pong")
+                    .provider(Provider::Ollama)
+                    .model(request.model)
+                    .build())
+            })
+        }
+
+        fn chat_stream(&self, _request: ChatRequest) -> BoxFuture<'_, Result<ChunkStream>> {
+            Box::pin(async { Err(Error::streaming("not supported")) })
+        }
+
+        fn health_check(&self) -> BoxFuture<'_, Result<()>> {
+            let healthy = self.healthy.load(Ordering::SeqCst);
+            Box::pin(async move {
+                if healthy {
+                    Ok(())
+                } else {
+                    Err(Error::provider_unavailable("ollama", "unhealthy"))
+                }
+            })
+        }
+    }
+
+    /// A pool of test instances, the number created so far and their health
+    fn test_pool(size: usize) -> (ZekePool, Arc<AtomicUsize>, Arc<AtomicBool>) {
+        let created = Arc::new(AtomicUsize::new(0));
+        let healthy = Arc::new(AtomicBool::new(true));
+        let pool = ZekePool::with_factory(test_config(), size, {
+            let created = Arc::clone(&created);
+            let healthy = Arc::clone(&healthy);
+            move |config| {
+                created.fetch_add(1, Ordering::SeqCst);
+                let backend = TestBackend { healthy: Arc::clone(&healthy) };
+                Zeke::with_backend(config, Arc::new(backend))
+            }
+        })
+        .unwrap();
+        (pool, created, healthy)
+    }
+
+    #[test]
+    fn test_empty_pool_rejected() {
+        let err = ZekePool::new(test_config(), 0).unwrap_err();
+        assert!(matches!(err, Error::ConfigError { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_checkout_limits_concurrency() {
+        let (pool, created, _) = test_pool(2);
+        let pool = Arc::new(pool.with_health_interval(Duration::MAX));
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+
+        let first = pool.acquire().await.unwrap();
+        let second = pool.acquire().await.unwrap();
+        assert_eq!(pool.available(), 0);
+        assert_eq!(first.chat("ping").await.unwrap().content, "pong");
+
+        // A waiting checkout proceeds once an instance comes back
+        let waiting = tokio::spawn({
+            let pool = Arc::clone(&pool);
+            async move { pool.acquire().await.map(drop) }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+        drop(first);
+        waiting.await.unwrap().unwrap();
+        assert_eq!(pool.available(), 1);
+        drop(second);
+        assert_eq!(pool.available(), 2);
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_try_acquire_fails_when_exhausted() {
+        let (pool, _, _) = test_pool(1);
+        let pool = Arc::new(pool);
+
+        let held = pool.try_acquire().await.unwrap();
+        assert!(matches!(
+            pool.try_acquire().await,
+            Err(Error::ProviderUnavailable { .. })
+        ));
+
+        drop(held);
+        assert!(pool.try_acquire().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_discarded_instance_is_recreated() {
+        let (pool, created, _) = test_pool(2);
+        let pool = Arc::new(pool.with_health_interval(Duration::MAX));
+
+        pool.acquire().await.unwrap().discard();
+        assert_eq!(pool.available(), 2);
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+
+        let _a = pool.acquire().await.unwrap();
+        let _b = pool.acquire().await.unwrap();
+        assert_eq!(created.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_health_check_replaces_instance() {
+        let (pool, created, healthy) = test_pool(1);
+        let pool = Arc::new(pool.with_health_interval(Duration::ZERO));
+
+        drop(pool.acquire().await.unwrap());
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+
+        healthy.store(false, Ordering::SeqCst);
+        let replacement = pool.acquire().await.unwrap();
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+        assert_eq!(replacement.chat("ping").await.unwrap().content, "pong");
+    }
+}
@@ -1,12 +1,15 @@
 //! AI provider definitions and utilities
 
-use serde::{Deserialize, Serialize};
+use crate::catalog::{ModelCatalog, ModelInfo};
+use crate::registry::{AuthScheme, CustomProvider, ProviderRegistry};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use zeke_sys::ZekeProvider;
 
 /// AI providers supported by Zeke
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// Serialized as the provider's [`identifier`](Provider::identifier).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Provider {
     /// GitHub Copilot
     Copilot,
@@ -18,18 +21,55 @@ pub enum Provider {
     Ollama,
     /// GhostLLM GPU-accelerated inference
     GhostLLM,
+    /// xAI Grok models
+    XAI,
+    /// Google Gemini models
+    Google,
+    /// Azure OpenAI Service deployments
+    Azure,
+    /// OMEN OpenAI-compatible routing gateway
+    Omen,
+    /// User-defined OpenAI-compatible provider, see [`ProviderRegistry`]
+    Custom(&'static str),
 }
 
 impl Provider {
-    /// Get all available providers
+    /// Get all available providers, including registered custom ones
     pub fn all() -> Vec<Provider> {
-        vec![
+        let mut providers = vec![
             Provider::Copilot,
             Provider::Claude,
             Provider::OpenAI,
             Provider::Ollama,
             Provider::GhostLLM,
-        ]
+            Provider::XAI,
+            Provider::Google,
+            Provider::Azure,
+            Provider::Omen,
+        ];
+        providers.extend(ProviderRegistry::providers());
+        providers
+    }
+
+    /// Create a custom provider identity for `name`
+    ///
+    /// The name only needs to be registered with [`ProviderRegistry`] before
+    /// the provider is used, so configs may name a provider defined later.
+    pub fn custom(name: &str) -> crate::Result<Provider> {
+        ProviderRegistry::intern(&name.to_lowercase()).map(Provider::Custom)
+    }
+
+    /// Check if this is a user-defined provider
+    pub fn is_custom(&self) -> bool {
+        matches!(self, Provider::Custom(_))
+    }
+
+    /// Registered definition of a custom provider
+    pub fn custom_definition(&self) -> Option<&'static CustomProvider> {
+        match self {
+            Provider::Custom(name) => ProviderRegistry::get(name),
+            _ => None,
+        }
     }
 
     /// Get the default base URL for this provider
@@ -40,6 +80,11 @@ impl Provider {
             Provider::OpenAI => "https://api.openai.com/v1",
             Provider::Ollama => "http://localhost:11434",
             Provider::GhostLLM => "http://localhost:8080",
+            Provider::XAI => "https://api.x.ai/v1",
+            Provider::Google => "https://generativelanguage.googleapis.com/v1beta",
+            Provider::Azure => "https://YOUR_RESOURCE.openai.azure.com", // Must be configured per resource
+            Provider::Omen => "http://localhost:3000",
+            Provider::Custom(_) => self.custom_definition().map_or("", |d| d.base_url.as_str()),
         }
     }
 
@@ -73,6 +118,28 @@ impl Provider {
                 "llama2-7b",
                 "llama2-13b",
             ],
+            Provider::XAI => vec![
+                "grok-2-latest",
+                "grok-2-1212",
+                "grok-2-vision-1212",
+                "grok-beta",
+            ],
+            Provider::Google => vec![
+                "gemini-pro",
+                "gemini-1.5-flash",
+                "gemini-pro-vision",
+                "gemini-ultra",
+            ],
+            Provider::Azure => vec![
+                "gpt-4o",
+                "gpt-4",
+                "gpt-35-turbo",
+            ],
+            Provider::Omen => vec!["auto"],
+            Provider::Custom(_) => self
+                .custom_definition()
+                .map(|d| d.models.iter().map(String::as_str).collect())
+                .unwrap_or_default(),
         }
     }
 
@@ -84,9 +151,22 @@ impl Provider {
             Provider::OpenAI => "gpt-4o",
             Provider::Ollama => "llama3",
             Provider::GhostLLM => "ghostllm-7b",
+            Provider::XAI => "grok-2-latest",
+            Provider::Google => "gemini-pro",
+            Provider::Azure => "gpt-4o",
+            Provider::Omen => "auto", // Let OMEN pick the model
+            Provider::Custom(_) => self
+                .custom_definition()
+                .and_then(|d| d.default_model.as_deref().or(d.models.first().map(String::as_str)))
+                .unwrap_or(""),
         }
     }
 
+    /// Look up a model of this provider in the [`ModelCatalog`]
+    pub fn model_info(&self, model: &str) -> Option<ModelInfo> {
+        ModelCatalog::get(*self, model)
+    }
+
     /// Check if this provider supports streaming
     pub fn supports_streaming(&self) -> bool {
         match self {
@@ -95,6 +175,11 @@ impl Provider {
             Provider::OpenAI => true,
             Provider::Ollama => true,
             Provider::GhostLLM => true,
+            Provider::XAI => true,
+            Provider::Google => true,
+            Provider::Azure => true,
+            Provider::Omen => true,
+            Provider::Custom(_) => self.custom_definition().is_none_or(|d| d.streaming),
         }
     }
 
@@ -109,7 +194,11 @@ impl Provider {
 
     /// Check if this provider is hosted locally
     pub fn is_local(&self) -> bool {
-        matches!(self, Provider::Ollama | Provider::GhostLLM)
+        match self {
+            Provider::Ollama | Provider::GhostLLM => true,
+            Provider::Custom(_) => self.custom_definition().is_some_and(|d| d.is_local()),
+            _ => false,
+        }
     }
 
     /// Check if this provider requires an API key
@@ -117,6 +206,8 @@ impl Provider {
         match self {
             Provider::Ollama => false, // Local, no API key needed
             Provider::GhostLLM => false, // Can run without API key locally
+            Provider::Omen => false, // Gateway may run without auth
+            Provider::Custom(_) => self.auth_method() == AuthMethod::ApiKey,
             _ => true,
         }
     }
@@ -129,6 +220,14 @@ impl Provider {
             Provider::OpenAI => AuthMethod::ApiKey,
             Provider::Ollama => AuthMethod::None,
             Provider::GhostLLM => AuthMethod::Optional,
+            Provider::XAI => AuthMethod::ApiKey,
+            Provider::Google => AuthMethod::ApiKey,
+            Provider::Azure => AuthMethod::ApiKey,
+            Provider::Omen => AuthMethod::Optional,
+            Provider::Custom(_) => match self.custom_definition().map(|d| &d.auth) {
+                Some(AuthScheme::Bearer | AuthScheme::Header(_)) => AuthMethod::ApiKey,
+                _ => AuthMethod::None,
+            },
         }
     }
 
@@ -140,6 +239,14 @@ impl Provider {
             Provider::OpenAI => "OpenAI",
             Provider::Ollama => "Ollama",
             Provider::GhostLLM => "GhostLLM",
+            Provider::XAI => "xAI",
+            Provider::Google => "Google Gemini",
+            Provider::Azure => "Azure OpenAI",
+            Provider::Omen => "OMEN",
+            Provider::Custom(name) => self
+                .custom_definition()
+                .and_then(|d| d.display_name.as_deref())
+                .unwrap_or(name),
         }
     }
 
@@ -151,17 +258,41 @@ impl Provider {
             Provider::OpenAI => "openai",
             Provider::Ollama => "ollama",
             Provider::GhostLLM => "ghostllm",
+            Provider::XAI => "xai",
+            Provider::Google => "google",
+            Provider::Azure => "azure",
+            Provider::Omen => "omen",
+            Provider::Custom(name) => name,
         }
     }
 
-    /// Parse provider from string identifier
+    /// Name the Zig CLI stores this provider's key under in the keyring
+    pub fn credential_name(&self) -> &'static str {
+        match self {
+            Provider::Claude => "anthropic",
+            Provider::Copilot => "github",
+            other => other.identifier(),
+        }
+    }
+
+    /// Parse provider from string identifier, including registered custom providers
     pub fn from_str(s: &str) -> Option<Provider> {
-        match s.to_lowercase().as_str() {
-            "copilot" | "github-copilot" => Some(Provider::Copilot),
+        let name = s.to_lowercase();
+        Self::builtin(&name).or_else(|| ProviderRegistry::find(&name))
+    }
+
+    /// Parse a built-in provider from a lowercase identifier or alias
+    pub(crate) fn builtin(s: &str) -> Option<Provider> {
+        match s {
+            "copilot" | "github-copilot" | "github_copilot" => Some(Provider::Copilot),
             "claude" | "anthropic" => Some(Provider::Claude),
             "openai" | "gpt" => Some(Provider::OpenAI),
             "ollama" => Some(Provider::Ollama),
             "ghostllm" | "ghost-llm" | "ghost" => Some(Provider::GhostLLM),
+            "xai" | "x-ai" | "grok" => Some(Provider::XAI),
+            "google" | "gemini" | "google-gemini" => Some(Provider::Google),
+            "azure" | "azure-openai" => Some(Provider::Azure),
+            "omen" => Some(Provider::Omen),
             _ => None,
         }
     }
@@ -174,20 +305,40 @@ impl Provider {
             Provider::OpenAI => Some(60),     // OpenAI rate limits (varies by tier)
             Provider::Ollama => None,         // Local, no limits
             Provider::GhostLLM => None,       // Local, no limits
+            Provider::XAI => Some(60),        // xAI rate limits
+            Provider::Google => Some(60),     // Gemini API rate limits
+            Provider::Azure => Some(60),      // Varies by deployment quota
+            Provider::Omen => None,           // Enforced by the gateway itself
+            Provider::Custom(_) => self.custom_definition().and_then(|d| d.rate_limit),
         }
     }
 
     /// Convert to FFI provider enum
-    pub(crate) fn to_ffi(&self) -> ZekeProvider {
+    ///
+    /// Custom providers have no identity in the core's ABI.
+    pub(crate) fn to_ffi(&self) -> Option<ZekeProvider> {
         match self {
-            Provider::Copilot => ZekeProvider::ZEKE_PROVIDER_COPILOT,
-            Provider::Claude => ZekeProvider::ZEKE_PROVIDER_CLAUDE,
-            Provider::OpenAI => ZekeProvider::ZEKE_PROVIDER_OPENAI,
-            Provider::Ollama => ZekeProvider::ZEKE_PROVIDER_OLLAMA,
-            Provider::GhostLLM => ZekeProvider::ZEKE_PROVIDER_GHOSTLLM,
+            Provider::Copilot => Some(ZekeProvider::ZEKE_PROVIDER_COPILOT),
+            Provider::Claude => Some(ZekeProvider::ZEKE_PROVIDER_CLAUDE),
+            Provider::OpenAI => Some(ZekeProvider::ZEKE_PROVIDER_OPENAI),
+            Provider::Ollama => Some(ZekeProvider::ZEKE_PROVIDER_OLLAMA),
+            Provider::GhostLLM => Some(ZekeProvider::ZEKE_PROVIDER_GHOSTLLM),
+            Provider::XAI => Some(ZekeProvider::ZEKE_PROVIDER_XAI),
+            Provider::Google => Some(ZekeProvider::ZEKE_PROVIDER_GOOGLE),
+            Provider::Azure => Some(ZekeProvider::ZEKE_PROVIDER_AZURE),
+            Provider::Omen => Some(ZekeProvider::ZEKE_PROVIDER_OMEN),
+            Provider::Custom(_) => None,
         }
     }
 
+    /// Core client that serves this provider
+    ///
+    /// The core drives custom providers through its OpenAI client, pointed at
+    /// the custom provider's base URL.
+    pub(crate) fn core_client(&self) -> ZekeProvider {
+        self.to_ffi().unwrap_or(ZekeProvider::ZEKE_PROVIDER_OPENAI)
+    }
+
     /// Convert from FFI provider enum
     pub(crate) fn from_ffi(provider: ZekeProvider) -> Option<Provider> {
         match provider {
@@ -196,8 +347,21 @@ impl Provider {
             ZekeProvider::ZEKE_PROVIDER_OPENAI => Some(Provider::OpenAI),
             ZekeProvider::ZEKE_PROVIDER_OLLAMA => Some(Provider::Ollama),
             ZekeProvider::ZEKE_PROVIDER_GHOSTLLM => Some(Provider::GhostLLM),
+            ZekeProvider::ZEKE_PROVIDER_XAI => Some(Provider::XAI),
+            ZekeProvider::ZEKE_PROVIDER_GOOGLE => Some(Provider::Google),
+            ZekeProvider::ZEKE_PROVIDER_AZURE => Some(Provider::Azure),
+            ZekeProvider::ZEKE_PROVIDER_OMEN => Some(Provider::Omen),
         }
     }
+
+    /// Convert from the integer the core stores in its C structs
+    pub(crate) fn from_ffi_int(raw: i32) -> Option<Provider> {
+        Self::all()
+            .into_iter()
+            .filter_map(|p| p.to_ffi())
+            .find(|ffi| *ffi as i32 == raw)
+            .and_then(Self::from_ffi)
+    }
 }
 
 impl fmt::Display for Provider {
@@ -206,6 +370,38 @@ impl fmt::Display for Provider {
     }
 }
 
+impl Serialize for Provider {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.identifier())
+    }
+}
+
+impl<'de> Deserialize<'de> for Provider {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Provider::from_str(&name).ok_or_else(|| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(&name),
+                &"a built-in or registered provider",
+            )
+        })
+    }
+}
+
+/// Deserialize a provider that may be defined later in the same document
+///
+/// Names that are neither built in nor registered become [`Provider::custom`]
+/// identities, so the document must check that they end up defined.
+pub(crate) fn deserialize_declared<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Provider, D::Error> {
+    let name = String::deserialize(deserializer)?.to_lowercase();
+    match Provider::builtin(&name) {
+        Some(provider) => Ok(provider),
+        None => Provider::custom(&name).map_err(serde::de::Error::custom),
+    }
+}
+
 impl From<Provider> for String {
     fn from(provider: Provider) -> String {
         provider.identifier().to_string()
@@ -299,6 +495,18 @@ pub struct ProviderStatus {
 }
 
 impl ProviderStatus {
+    /// Status of a provider the core has no health data for
+    pub(crate) fn unknown(provider: Provider) -> Self {
+        Self {
+            provider,
+            is_healthy: false,
+            response_time_ms: 0,
+            error_rate: 0.0,
+            requests_per_minute: 0,
+            last_check: std::time::SystemTime::now(),
+        }
+    }
+
     /// Check if the provider is performing well
     pub fn is_performing_well(&self) -> bool {
         self.is_healthy 
@@ -350,6 +558,52 @@ mod tests {
         assert_eq!(Provider::from_str("invalid"), None);
     }
 
+    #[test]
+    fn test_new_provider_aliases() {
+        assert_eq!(Provider::from_str("grok"), Some(Provider::XAI));
+        assert_eq!(Provider::from_str("Gemini"), Some(Provider::Google));
+        assert_eq!(Provider::from_str("azure-openai"), Some(Provider::Azure));
+        assert_eq!(Provider::from_str("omen"), Some(Provider::Omen));
+
+        for provider in Provider::all() {
+            assert_eq!(Provider::from_str(provider.identifier()), Some(provider));
+        }
+    }
+
+    #[test]
+    fn test_provider_serde_names() {
+        assert_eq!(serde_json::to_string(&Provider::XAI).unwrap(), "\"xai\"");
+        assert_eq!(serde_json::to_string(&Provider::Google).unwrap(), "\"google\"");
+        assert_eq!(serde_json::from_str::<Provider>("\"gemini\"").unwrap(), Provider::Google);
+        assert_eq!(serde_json::from_str::<Provider>("\"azure\"").unwrap(), Provider::Azure);
+        assert_eq!(serde_json::from_str::<Provider>("\"omen\"").unwrap(), Provider::Omen);
+        assert_eq!(serde_json::from_str::<Provider>("\"OpenAI\"").unwrap(), Provider::OpenAI);
+    }
+
+    #[test]
+    fn test_custom_provider_serde() {
+        // Only registered custom providers deserialize
+        assert!(serde_json::from_str::<Provider>("\"serde-llamacpp\"").is_err());
+        assert!(serde_json::from_str::<Provider>("\"not valid\"").is_err());
+
+        let registered = ProviderRegistry::register(CustomProvider::new(
+            "serde-llamacpp",
+            "http://localhost:8080/v1",
+        ))
+        .unwrap();
+        let provider: Provider = serde_json::from_str("\"serde-llamacpp\"").unwrap();
+        assert_eq!(provider, registered);
+        assert_eq!(serde_json::to_string(&provider).unwrap(), "\"serde-llamacpp\"");
+        assert!(ProviderRegistry::unregister("serde-llamacpp"));
+
+        // Undefined custom providers have no metadata or ABI identity and run on the OpenAI client
+        let provider = Provider::custom("serde-undefined").unwrap();
+        assert_eq!(provider.default_base_url(), "");
+        assert_eq!(provider.auth_method(), AuthMethod::None);
+        assert_eq!(provider.to_ffi(), None);
+        assert_eq!(provider.core_client(), ZekeProvider::ZEKE_PROVIDER_OPENAI);
+    }
+
     #[test]
     fn test_provider_display() {
         assert_eq!(Provider::OpenAI.to_string(), "OpenAI");
@@ -366,9 +620,10 @@ mod tests {
 
     #[test]
     fn test_ffi_conversion() {
-        let provider = Provider::OpenAI;
-        let ffi = provider.to_ffi();
-        assert_eq!(Provider::from_ffi(ffi), Some(provider));
+        for provider in Provider::all().into_iter().filter(|p| !p.is_custom()) {
+            let ffi = provider.to_ffi().unwrap();
+            assert_eq!(Provider::from_ffi(ffi), Some(provider));
+        }
     }
 
     #[test]
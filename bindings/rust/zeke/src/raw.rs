@@ -0,0 +1,377 @@
+//! Zero-copy access to response text
+//!
+//! [`ChatResponse`] and [`StreamChunk`](crate::StreamChunk) own their text,
+//! which costs a copy (and an allocation per chunk) on every call. For large
+//! outputs, [`RawResponse`] borrows the buffer the Zig core allocated, and a
+//! [`ChunkArena`] collects a stream into one growing buffer.
+
+use crate::{response::ChatResponse, Error, Provider, Result};
+use std::ffi::CStr;
+use std::fmt;
+use std::ops::Range;
+use std::str::Utf8Error;
+use std::sync::OnceLock;
+use std::time::Duration;
+use zeke_sys::*;
+
+/// A chat response that borrows its text from the Zig core
+///
+/// Returned by [`Zeke::chat_raw`](crate::Zeke::chat_raw). The text stays in
+/// the core's buffer, which is freed when the response is dropped; it is only
+/// checked for UTF-8 the first time [`as_str`](Self::as_str) is called.
+/// Responses from a native backend arrive owned and are wrapped as they are.
+pub struct RawResponse {
+    buffer: Buffer,
+    provider: Provider,
+    model: String,
+    tokens_used: Option<u32>,
+    response_time: Duration,
+    utf8: OnceLock<std::result::Result<(), Utf8Error>>,
+}
+
+enum Buffer {
+    /// A response from `zeke_chat`, freed with `zeke_free_response`
+    Core { response: ZekeResponse, len: usize },
+    Owned(String),
+}
+
+// Safety: the core's buffer is never written after `zeke_chat` returns and is
+// owned by this response alone until `drop` frees it.
+unsafe impl Send for RawResponse {}
+unsafe impl Sync for RawResponse {}
+
+impl RawResponse {
+    /// Take ownership of a response filled in by a successful `zeke_chat`
+    ///
+    /// # Safety
+    ///
+    /// `response.content` must be null or a null-terminated buffer that is
+    /// freed with `zeke_free_response` and not used anywhere else.
+    pub(crate) unsafe fn from_core(
+        mut response: ZekeResponse,
+        provider: Provider,
+        model: String,
+        response_time: Duration,
+    ) -> Result<Self> {
+        if response.content.is_null() {
+            let _ = unsafe { zeke_call!(zeke_free_response(&mut response)) };
+            return Err(Error::custom("Received null response content"));
+        }
+
+        let len = unsafe { CStr::from_ptr(response.content) }.to_bytes().len();
+        Ok(Self {
+            tokens_used: Some(response.tokens_used),
+            buffer: Buffer::Core { response, len },
+            provider,
+            model,
+            response_time,
+            utf8: OnceLock::new(),
+        })
+    }
+
+    /// The response text as bytes, without any checks
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.buffer {
+            Buffer::Core { response, len } => unsafe {
+                std::slice::from_raw_parts(response.content as *const u8, *len)
+            },
+            Buffer::Owned(content) => content.as_bytes(),
+        }
+    }
+
+    /// The response text
+    ///
+    /// Fails with [`Error::Utf8Error`] if the core returned invalid UTF-8.
+    pub fn as_str(&self) -> Result<&str> {
+        let bytes = self.as_bytes();
+        match self.utf8.get_or_init(|| std::str::from_utf8(bytes).map(|_| ())) {
+            // Safety: validated above, and the buffer never changes
+            Ok(()) => Ok(unsafe { std::str::from_utf8_unchecked(bytes) }),
+            Err(e) => Err(Error::from(*e)),
+        }
+    }
+
+    /// Length of the response text in bytes
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    /// Check if the response text is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the text is still in the core's buffer
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.buffer, Buffer::Core { .. })
+    }
+
+    /// Provider that generated the response
+    pub fn provider(&self) -> Provider {
+        self.provider
+    }
+
+    /// Model used for generation
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Number of tokens used in the request
+    pub fn tokens_used(&self) -> Option<u32> {
+        self.tokens_used
+    }
+
+    /// Time taken to generate the response
+    pub fn response_time(&self) -> Duration {
+        self.response_time
+    }
+
+    /// Copy the response into a [`ChatResponse`]
+    pub fn to_response(&self) -> Result<ChatResponse> {
+        Ok(ChatResponse::new(
+            self.as_str()?.to_owned(),
+            self.provider,
+            self.model.clone(),
+            self.tokens_used,
+            self.response_time,
+        ))
+    }
+
+    /// Take the response text, copying it out of the core's buffer if needed
+    pub fn into_string(mut self) -> Result<String> {
+        if let Buffer::Owned(content) = &mut self.buffer {
+            return Ok(std::mem::take(content));
+        }
+        self.as_str().map(str::to_owned)
+    }
+}
+
+impl From<ChatResponse> for RawResponse {
+    fn from(response: ChatResponse) -> Self {
+        Self {
+            buffer: Buffer::Owned(response.content),
+            provider: response.provider,
+            model: response.model,
+            tokens_used: response.tokens_used,
+            response_time: response.response_time,
+            utf8: OnceLock::from(Ok(())),
+        }
+    }
+}
+
+/// Run `f` on a [`RawResponse`] borrowing `content` as if the core had allocated it
+#[cfg(feature = "bench")]
+pub fn with_core_buffer<R>(content: &CStr, f: impl FnOnce(&RawResponse) -> R) -> R {
+    let response = ZekeResponse {
+        content: content.as_ptr(),
+        ..Default::default()
+    };
+    // The buffer belongs to the caller, so the core must never free it
+    let raw = std::mem::ManuallyDrop::new(
+        unsafe { RawResponse::from_core(response, Provider::Ollama, String::new(), Duration::ZERO) }
+            .expect("content is not null"),
+    );
+    f(&raw)
+}
+
+impl Drop for RawResponse {
+    fn drop(&mut self) {
+        if let Buffer::Core { response, .. } = &mut self.buffer {
+            // The library is loaded, or `zeke_chat` could not have run
+            let _ = unsafe { zeke_call!(zeke_free_response(response)) };
+        }
+    }
+}
+
+impl fmt::Debug for RawResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawResponse")
+            .field("len", &self.len())
+            .field("borrowed", &self.is_borrowed())
+            .field("provider", &self.provider)
+            .field("model", &self.model)
+            .field("tokens_used", &self.tokens_used)
+            .field("response_time", &self.response_time)
+            .finish()
+    }
+}
+
+/// One buffer holding every chunk of a stream
+///
+/// Filled by [`Zeke::chat_stream_arena`](crate::Zeke::chat_stream_arena).
+/// Each chunk is copied once, onto the end of the buffer, so a stream costs
+/// a handful of allocations instead of one per chunk and the full text needs
+/// no joining. [`clear`](Self::clear) keeps the allocation for the next stream.
+#[derive(Debug, Clone, Default)]
+pub struct ChunkArena {
+    text: String,
+    ends: Vec<usize>,
+}
+
+impl ChunkArena {
+    /// Create an empty arena
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an arena that holds `bytes` of text before it reallocates
+    pub fn with_capacity(bytes: usize) -> Self {
+        Self {
+            text: String::with_capacity(bytes),
+            ends: Vec::new(),
+        }
+    }
+
+    /// Append a chunk and return it as stored in the arena
+    ///
+    /// Fails with [`Error::Utf8Error`] and leaves the arena unchanged if the
+    /// chunk is not UTF-8.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<&str> {
+        let chunk = std::str::from_utf8(chunk)?;
+        let start = self.text.len();
+        self.text.push_str(chunk);
+        self.ends.push(self.text.len());
+        Ok(&self.text[start..])
+    }
+
+    /// All text received so far
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Length of the text in bytes
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Check if no text has been received
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Number of chunks received
+    pub fn chunk_count(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// A chunk by its position in the stream
+    pub fn chunk(&self, index: usize) -> Option<&str> {
+        self.range(index).map(|range| &self.text[range])
+    }
+
+    /// Iterate over the chunks in stream order
+    pub fn chunks(&self) -> impl Iterator<Item = &str> + '_ {
+        (0..self.ends.len()).filter_map(|index| self.chunk(index))
+    }
+
+    /// Remove all chunks, keeping the allocation
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.ends.clear();
+    }
+
+    /// Take the text, discarding the chunk boundaries
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    fn range(&self, index: usize) -> Option<Range<usize>> {
+        let end = *self.ends.get(index)?;
+        let start = index.checked_sub(1).map_or(0, |previous| self.ends[previous]);
+        Some(start..end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    fn core_response(content: &CString) -> RawResponse {
+        let response = ZekeResponse {
+            content: content.as_ptr(),
+            tokens_used: 12,
+            ..Default::default()
+        };
+        unsafe {
+            RawResponse::from_core(response, Provider::Claude, "claude-3".to_string(), Duration::from_millis(5))
+        }
+        .unwrap()
+    }
+
+    #[test]
+    fn test_raw_response_borrows_core_buffer() {
+        let content = CString::new("fn main() {}").unwrap();
+        let raw = core_response(&content);
+
+        assert!(raw.is_borrowed());
+        assert_eq!(raw.as_bytes().as_ptr(), content.as_ptr() as *const u8);
+        assert_eq!(raw.as_str().unwrap(), "fn main() {}");
+        assert_eq!(raw.len(), 12);
+        assert_eq!(raw.tokens_used(), Some(12));
+
+        let response = raw.to_response().unwrap();
+        assert_eq!(response.content, "fn main() {}");
+        assert_eq!(response.provider, Provider::Claude);
+        assert_eq!(response.model, "claude-3");
+
+        // The buffer belongs to the CString, not the core
+        std::mem::forget(raw);
+    }
+
+    #[test]
+    fn test_raw_response_invalid_utf8() {
+        let content = CString::new(vec![b'o', b'k', 0xff]).unwrap();
+        let raw = core_response(&content);
+
+        assert_eq!(raw.as_bytes(), b"ok\xff");
+        assert!(matches!(raw.as_str(), Err(Error::Utf8Error(_))));
+        assert!(raw.to_response().is_err());
+        std::mem::forget(raw);
+    }
+
+    #[test]
+    fn test_raw_response_from_chat_response() {
+        let response = ChatResponse::new(
+            "owned".to_string(),
+            Provider::Ollama,
+            "llama3".to_string(),
+            None,
+            Duration::ZERO,
+        );
+        let raw = RawResponse::from(response);
+
+        assert!(!raw.is_borrowed());
+        assert_eq!(raw.as_str().unwrap(), "owned");
+        assert_eq!(raw.model(), "llama3");
+        assert_eq!(raw.into_string().unwrap(), "owned");
+    }
+
+    #[test]
+    fn test_chunk_arena() {
+        let mut arena = ChunkArena::with_capacity(64);
+        assert!(arena.is_empty());
+
+        assert_eq!(arena.push(b"Hello").unwrap(), "Hello");
+        assert_eq!(arena.push(b"").unwrap(), "");
+        assert_eq!(arena.push(" wörld".as_bytes()).unwrap(), " wörld");
+        assert_eq!(arena.as_str(), "Hello wörld");
+        assert_eq!(arena.chunk_count(), 3);
+        assert_eq!(arena.chunk(2), Some(" wörld"));
+        assert_eq!(arena.chunk(3), None);
+        assert_eq!(arena.chunks().collect::<Vec<_>>(), ["Hello", "", " wörld"]);
+
+        // Invalid chunks are rejected without touching the arena
+        assert!(arena.push(b"\xff").is_err());
+        assert_eq!(arena.chunk_count(), 3);
+
+        let capacity = arena.text.capacity();
+        arena.clear();
+        assert!(arena.is_empty());
+        assert_eq!(arena.chunk_count(), 0);
+        assert_eq!(arena.text.capacity(), capacity);
+
+        arena.push(b"again").unwrap();
+        assert_eq!(arena.into_string(), "again");
+    }
+}
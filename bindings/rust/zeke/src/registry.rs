@@ -0,0 +1,340 @@
+//! Registry of user-defined OpenAI-compatible providers
+//!
+//! Servers such as vLLM, llama.cpp and LM Studio speak the OpenAI chat
+//! completions protocol. Registering them as [`CustomProvider`]s gives each one
+//! its own [`Provider::Custom`] identity, so responses, status and routing are
+//! attributed to the right server instead of to OpenAI.
+
+use crate::{Error, Provider, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{LazyLock, RwLock};
+use url::Url;
+
+/// How a custom provider expects the API key to be sent
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthScheme {
+    /// No authentication
+    #[default]
+    None,
+    /// `Authorization: Bearer <key>`
+    Bearer,
+    /// The raw key in a named header, e.g. `api-key`
+    Header(String),
+}
+
+impl AuthScheme {
+    /// Header name and value carrying `key` under this scheme
+    pub fn header(&self, key: &str) -> Option<(String, String)> {
+        match self {
+            AuthScheme::None => None,
+            AuthScheme::Bearer => Some(("Authorization".to_string(), format!("Bearer {}", key))),
+            AuthScheme::Header(name) => Some((name.clone(), key.to_string())),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Definition of an OpenAI-compatible provider supplied by the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomProvider {
+    /// Identifier used in configs and responses, e.g. `vllm`
+    pub name: String,
+    /// Human-readable name (defaults to `name`)
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Base URL of the API, including any `/v1` suffix
+    pub base_url: String,
+    /// How the API key is sent
+    #[serde(default)]
+    pub auth: AuthScheme,
+    /// Environment variable holding the API key (defaults to `<NAME>_API_KEY`)
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Models served by this provider
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Model used when none is configured (defaults to the first of `models`)
+    #[serde(default)]
+    pub default_model: Option<String>,
+    /// Whether the server supports streaming responses
+    #[serde(default = "default_true")]
+    pub streaming: bool,
+    /// Whether the server runs locally (inferred from `base_url` when unset)
+    #[serde(default)]
+    pub local: Option<bool>,
+    /// Rate limit in requests per minute
+    #[serde(default)]
+    pub rate_limit: Option<u32>,
+}
+
+impl CustomProvider {
+    /// Create a definition with no authentication and no known models
+    pub fn new<N: Into<String>, U: Into<String>>(name: N, base_url: U) -> Self {
+        Self {
+            name: name.into(),
+            display_name: None,
+            base_url: base_url.into(),
+            auth: AuthScheme::None,
+            api_key_env: None,
+            models: Vec::new(),
+            default_model: None,
+            streaming: true,
+            local: None,
+            rate_limit: None,
+        }
+    }
+
+    /// Set the display name
+    pub fn display_name<S: Into<String>>(mut self, display_name: S) -> Self {
+        self.display_name = Some(display_name.into());
+        self
+    }
+
+    /// Set the authentication scheme
+    pub fn auth(mut self, auth: AuthScheme) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Set the environment variable holding the API key
+    pub fn api_key_env<S: Into<String>>(mut self, var: S) -> Self {
+        self.api_key_env = Some(var.into());
+        self
+    }
+
+    /// Add a served model
+    pub fn model<S: Into<String>>(mut self, model: S) -> Self {
+        self.models.push(model.into());
+        self
+    }
+
+    /// Set the default model
+    pub fn default_model<S: Into<String>>(mut self, model: S) -> Self {
+        self.default_model = Some(model.into());
+        self
+    }
+
+    /// Mark the server as local or remote
+    pub fn local(mut self, local: bool) -> Self {
+        self.local = Some(local);
+        self
+    }
+
+    /// Check the name and base URL
+    pub fn validate(&self) -> Result<()> {
+        validate_name(&self.name)?;
+        Url::parse(&self.base_url).map_err(|e| Error::ConfigError {
+            message: format!("Invalid base URL for custom provider '{}': {}", self.name, e),
+        })?;
+        Ok(())
+    }
+
+    /// Whether the server runs on this machine
+    pub fn is_local(&self) -> bool {
+        self.local.unwrap_or_else(|| {
+            Url::parse(&self.base_url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_owned))
+                .is_some_and(|host| matches!(host.as_str(), "localhost" | "127.0.0.1" | "[::1]"))
+        })
+    }
+
+    /// Environment variables checked for the API key, in order
+    pub fn api_key_env_vars(&self) -> Vec<String> {
+        let derived = format!("{}_API_KEY", self.name.to_uppercase().replace('-', "_"));
+        match &self.api_key_env {
+            Some(var) if *var != derived => vec![var.clone(), derived],
+            _ => vec![derived],
+        }
+    }
+}
+
+/// Check that a name is usable as a custom provider identifier
+pub(crate) fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err(Error::ConfigError {
+            message: format!(
+                "Invalid custom provider name '{}': use lowercase letters, digits, '-' or '_'",
+                name
+            ),
+        });
+    }
+
+    if Provider::builtin(name).is_some() {
+        return Err(Error::ConfigError {
+            message: format!("Custom provider name '{}' clashes with a built-in provider", name),
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct RegistryState {
+    names: HashSet<&'static str>,
+    /// Every definition ever registered, so re-registering one reuses it
+    definitions: BTreeMap<&'static str, Vec<&'static CustomProvider>>,
+    providers: BTreeMap<&'static str, &'static CustomProvider>,
+}
+
+static REGISTRY: LazyLock<RwLock<RegistryState>> = LazyLock::new(Default::default);
+
+/// Process-wide registry of [`CustomProvider`] definitions
+///
+/// Names and definitions are kept for the lifetime of the process so that
+/// [`Provider`] can stay `Copy` and hand out `&'static str` metadata. Both are
+/// interned, so only distinct definitions take up memory however often a
+/// config is reloaded.
+#[derive(Debug, Clone, Copy)]
+pub struct ProviderRegistry;
+
+impl ProviderRegistry {
+    /// Register (or replace) a custom provider and return its identity
+    pub fn register(definition: CustomProvider) -> Result<Provider> {
+        definition.validate()?;
+
+        let mut state = REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+        let name = intern(&mut state, &definition.name);
+        let definitions = state.definitions.entry(name).or_default();
+        let definition = match definitions.iter().find(|existing| ***existing == definition) {
+            Some(existing) => *existing,
+            None => {
+                let leaked: &'static CustomProvider = Box::leak(Box::new(definition));
+                definitions.push(leaked);
+                leaked
+            }
+        };
+        state.providers.insert(name, definition);
+
+        Ok(Provider::Custom(name))
+    }
+
+    /// Look up the definition of a registered provider
+    pub fn get(name: &str) -> Option<&'static CustomProvider> {
+        let state = REGISTRY.read().unwrap_or_else(|e| e.into_inner());
+        state.providers.get(name).copied()
+    }
+
+    /// Look up a registered provider by name
+    pub fn find(name: &str) -> Option<Provider> {
+        let state = REGISTRY.read().unwrap_or_else(|e| e.into_inner());
+        state.providers.get_key_value(name).map(|(key, _)| Provider::Custom(key))
+    }
+
+    /// Remove a registered provider, returning whether it existed
+    pub fn unregister(name: &str) -> bool {
+        let mut state = REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+        state.providers.remove(name).is_some()
+    }
+
+    /// All registered custom providers, ordered by name
+    pub fn providers() -> Vec<Provider> {
+        let state = REGISTRY.read().unwrap_or_else(|e| e.into_inner());
+        state.providers.keys().map(|name| Provider::Custom(name)).collect()
+    }
+
+    /// Intern a validated name without registering a definition
+    pub(crate) fn intern(name: &str) -> Result<&'static str> {
+        validate_name(name)?;
+        let mut state = REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+        Ok(intern(&mut state, name))
+    }
+}
+
+fn intern(state: &mut RegistryState, name: &str) -> &'static str {
+    if let Some(existing) = state.names.get(name) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(name.to_string().into_boxed_str());
+    state.names.insert(leaked);
+    leaked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_lookup() {
+        let provider = ProviderRegistry::register(
+            CustomProvider::new("test-vllm", "http://localhost:8000/v1")
+                .model("qwen2.5-coder-32b")
+                .auth(AuthScheme::Bearer),
+        )
+        .unwrap();
+
+        assert_eq!(provider, Provider::Custom("test-vllm"));
+        assert_eq!(provider.identifier(), "test-vllm");
+        assert_eq!(provider.default_base_url(), "http://localhost:8000/v1");
+        assert_eq!(provider.default_model(), "qwen2.5-coder-32b");
+        assert!(provider.is_local());
+        assert!(provider.requires_api_key());
+        assert_eq!(Provider::from_str("TEST-VLLM"), Some(provider));
+        assert!(ProviderRegistry::providers().contains(&provider));
+
+        assert!(ProviderRegistry::unregister("test-vllm"));
+        assert_eq!(Provider::from_str("test-vllm"), None);
+    }
+
+    #[test]
+    fn test_reregistering_reuses_definitions() {
+        let first = CustomProvider::new("test-reload", "http://localhost:8000/v1");
+        let second = first.clone().model("llama-3.1-8b");
+
+        ProviderRegistry::register(first.clone()).unwrap();
+        let original = ProviderRegistry::get("test-reload").unwrap();
+        ProviderRegistry::register(second).unwrap();
+        assert!(!std::ptr::eq(ProviderRegistry::get("test-reload").unwrap(), original));
+        ProviderRegistry::register(first).unwrap();
+        assert!(std::ptr::eq(ProviderRegistry::get("test-reload").unwrap(), original));
+
+        ProviderRegistry::unregister("test-reload");
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        assert!(ProviderRegistry::register(CustomProvider::new("openai", "http://x")).is_err());
+        assert!(ProviderRegistry::register(CustomProvider::new("LM Studio", "http://x")).is_err());
+        assert!(ProviderRegistry::register(CustomProvider::new("lmstudio", "not a url")).is_err());
+    }
+
+    #[test]
+    fn test_auth_scheme_header() {
+        assert_eq!(AuthScheme::None.header("k"), None);
+        assert_eq!(
+            AuthScheme::Bearer.header("k"),
+            Some(("Authorization".to_string(), "Bearer k".to_string()))
+        );
+        assert_eq!(
+            AuthScheme::Header("api-key".to_string()).header("k"),
+            Some(("api-key".to_string(), "k".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_definition_from_toml() {
+        let definition: CustomProvider = toml::from_str(
+            r#"
+            name = "lm-studio"
+            base_url = "http://192.168.1.20:1234/v1"
+            auth = { header = "x-api-key" }
+            models = ["llama-3.1-8b"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(definition.auth, AuthScheme::Header("x-api-key".to_string()));
+        assert!(definition.streaming);
+        assert!(!definition.is_local());
+        assert_eq!(definition.api_key_env_vars(), vec!["LM_STUDIO_API_KEY"]);
+    }
+}
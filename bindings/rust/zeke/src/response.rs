@@ -116,6 +116,10 @@ pub struct ResponseMetadata {
     /// Whether the response was streamed
     pub streamed: bool,
     
+    /// Why generation stopped (e.g. `end_turn`, `max_tokens`, `tool_use`)
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+    
     /// Provider-specific metadata
     pub provider_data: std::collections::HashMap<String, serde_json::Value>,
     
@@ -0,0 +1,428 @@
+//! Model routing
+//!
+//! [`Router`] loads the `routing_hints.json` schema shipped with Zeke and
+//! resolves model aliases such as `code-fast` to a concrete provider and model.
+//! [`SmartRouter`] picks an alias automatically from the prompt itself.
+
+mod smart;
+
+pub use smart::{
+    PromptProfile, Rejection, RouteCandidate, RoutingDecision, RoutingPolicy, SmartRouter,
+};
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use smart::RoutedResponse;
+
+use crate::{Error, Provider, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// What a request is trying to achieve
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Intent {
+    /// Writing or editing code
+    #[default]
+    Code,
+    /// Inline code completion
+    Completion,
+    /// Restructuring existing code
+    Refactor,
+    /// Multi-step reasoning
+    Reason,
+    /// System design
+    Architecture,
+    /// General conversation
+    Chat,
+}
+
+/// Acceptable response latency
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Latency {
+    /// Interactive, first token as fast as possible
+    Low,
+    /// Normal request/response
+    #[default]
+    Normal,
+    /// Background work where latency does not matter
+    High,
+}
+
+/// How much a request may cost
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Budget {
+    /// Prefer free or cheap models
+    Low,
+    /// Default spending
+    #[default]
+    Normal,
+    /// Quality over cost
+    High,
+}
+
+/// Expected size of the task
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeHint {
+    /// A line or two
+    Tiny,
+    /// A function
+    #[default]
+    Small,
+    /// A file
+    Medium,
+    /// Several files or a long document
+    Large,
+}
+
+macro_rules! display_lowercase {
+    ($($ty:ty),*) => {$(
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let value = serde_json::to_value(self).map_err(|_| fmt::Error)?;
+                write!(f, "{}", value.as_str().unwrap_or_default())
+            }
+        }
+    )*};
+}
+
+display_lowercase!(Intent, Latency, Budget, SizeHint);
+
+/// Tags describing a request, used for routing and request headers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoutingTags {
+    /// Project the request belongs to
+    pub project: String,
+    /// Acceptable latency
+    pub latency: Latency,
+    /// Spending limit
+    pub budget: Budget,
+    /// Purpose of the request
+    pub intent: Intent,
+    /// Expected task size
+    pub size_hint: SizeHint,
+}
+
+impl Default for RoutingTags {
+    fn default() -> Self {
+        Self {
+            project: "default".to_string(),
+            latency: Latency::default(),
+            budget: Budget::default(),
+            intent: Intent::default(),
+            size_hint: SizeHint::default(),
+        }
+    }
+}
+
+/// Defaults applied when a request does not specify a model or tags
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingDefaults {
+    /// Default model name or alias (`auto` lets the router decide)
+    #[serde(default = "default_model")]
+    pub model: String,
+    /// Default request tags
+    #[serde(default)]
+    pub tags: RoutingTags,
+}
+
+fn default_model() -> String {
+    "auto".to_string()
+}
+
+impl Default for RoutingDefaults {
+    fn default() -> Self {
+        Self {
+            model: default_model(),
+            tags: RoutingTags::default(),
+        }
+    }
+}
+
+/// A named shortcut for a concrete provider and model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelAlias {
+    /// Provider serving the model
+    pub provider: Provider,
+    /// Model identifier
+    pub model: String,
+    /// Intent the alias is meant for
+    #[serde(default)]
+    pub intent: Option<Intent>,
+    /// Latency class of the model
+    #[serde(default)]
+    pub latency: Option<Latency>,
+    /// Cost class of the model
+    #[serde(default)]
+    pub budget: Option<Budget>,
+    /// Largest task size the model is suited for
+    #[serde(default)]
+    pub size_hint: Option<SizeHint>,
+}
+
+/// When to escalate to a stronger model, and where to fall back to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Escalation {
+    /// Escalate when the first token takes longer than this
+    pub first_token_ms: u32,
+    /// Escalate when the expected output exceeds this many tokens
+    pub large_tokens_out: u32,
+    /// Escalate after this many tool calls
+    pub max_tool_calls: u32,
+    /// Providers to try in order when one fails
+    #[serde(default)]
+    pub fallback_order: Vec<Provider>,
+}
+
+/// Contents of a `routing_hints.json` file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingHints {
+    /// JSON schema reference
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    /// Defaults for requests
+    #[serde(default)]
+    pub defaults: RoutingDefaults,
+    /// Model aliases by name
+    #[serde(default)]
+    pub aliases: BTreeMap<String, ModelAlias>,
+    /// Escalation policy
+    #[serde(default)]
+    pub escalation: Option<Escalation>,
+    /// Request header templates; `{{tag}}` is replaced with the tag value
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// Resolves model aliases from routing hints
+#[derive(Debug, Clone, Default)]
+pub struct Router {
+    hints: RoutingHints,
+}
+
+impl Router {
+    /// Create a router from parsed hints, validating them
+    pub fn new(hints: RoutingHints) -> Result<Self> {
+        let router = Self { hints };
+        router.validate()?;
+        Ok(router)
+    }
+
+    /// Load and validate a `routing_hints.json` file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| Error::ConfigError {
+            message: format!("Failed to read routing hints {}: {}", path.display(), e),
+        })?;
+        Self::from_json(&content)
+    }
+
+    /// Parse and validate routing hints from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        let hints = serde_json::from_str(json).map_err(|e| Error::ConfigError {
+            message: format!("Failed to parse routing hints: {}", e),
+        })?;
+        Self::new(hints)
+    }
+
+    /// The loaded hints
+    pub fn hints(&self) -> &RoutingHints {
+        &self.hints
+    }
+
+    /// Check the hints for aliases and providers that cannot be resolved
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| Err(Error::ConfigError { message });
+
+        for (name, alias) in &self.hints.aliases {
+            if name.trim().is_empty() {
+                return invalid("Routing alias names must not be empty".to_string());
+            }
+            if alias.model.trim().is_empty() {
+                return invalid(format!("Routing alias '{}' has an empty model", name));
+            }
+            if self.hints.aliases.contains_key(&alias.model) {
+                return invalid(format!(
+                    "Routing alias '{}' points at another alias '{}'",
+                    name, alias.model
+                ));
+            }
+            check_provider(alias.provider, &format!("alias '{}'", name))?;
+        }
+
+        let default_model = &self.hints.defaults.model;
+        if default_model != "auto" && !self.hints.aliases.contains_key(default_model) {
+            return invalid(format!(
+                "Default routing model '{}' is neither 'auto' nor a known alias",
+                default_model
+            ));
+        }
+
+        if let Some(escalation) = &self.hints.escalation {
+            for provider in &escalation.fallback_order {
+                check_provider(*provider, "escalation.fallback_order")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Look up an alias by name
+    pub fn alias(&self, name: &str) -> Option<&ModelAlias> {
+        self.hints.aliases.get(name)
+    }
+
+    /// Check whether a name is a known alias
+    pub fn is_alias(&self, name: &str) -> bool {
+        self.hints.aliases.contains_key(name)
+    }
+
+    /// Resolve an alias to its provider and model
+    pub fn model(&self, name: &str) -> Option<(Provider, String)> {
+        self.alias(name)
+            .map(|alias| (alias.provider, alias.model.clone()))
+    }
+
+    /// Providers to try in order when one fails
+    pub fn fallback_order(&self) -> &[Provider] {
+        self.hints
+            .escalation
+            .as_ref()
+            .map_or(&[], |e| e.fallback_order.as_slice())
+    }
+
+    /// Render the header templates for a set of tags
+    pub fn headers(&self, tags: &RoutingTags) -> BTreeMap<String, String> {
+        let values = [
+            ("project", tags.project.clone()),
+            ("intent", tags.intent.to_string()),
+            ("latency", tags.latency.to_string()),
+            ("budget", tags.budget.to_string()),
+            ("size_hint", tags.size_hint.to_string()),
+        ];
+
+        self.hints
+            .headers
+            .iter()
+            .map(|(name, template)| {
+                let value = values
+                    .iter()
+                    .fold(template.clone(), |value, (tag, tag_value)| {
+                        value.replace(&format!("{{{{{}}}}}", tag), tag_value)
+                    });
+                (name.clone(), value)
+            })
+            .collect()
+    }
+}
+
+fn check_provider(provider: Provider, context: &str) -> Result<()> {
+    if let Provider::Custom(name) = provider
+        && provider.custom_definition().is_none()
+    {
+        return Err(Error::ConfigError {
+            message: format!("Unknown provider '{}' in routing {}", name, context),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HINTS: &str = r#"{
+        "$schema": "https://example.com/zeke-routing.schema.json",
+        "defaults": {
+            "model": "auto",
+            "tags": {"project": "default", "latency": "normal", "budget": "normal", "intent": "code", "size_hint": "small"}
+        },
+        "aliases": {
+            "code-fast": {"provider": "ollama", "model": "deepseek-coder:14b", "intent": "code", "latency": "low", "size_hint": "tiny"},
+            "reason-deep": {"provider": "anthropic", "model": "claude-3-5-sonnet", "intent": "reason", "latency": "normal", "size_hint": "large"}
+        },
+        "escalation": {"first_token_ms": 2000, "large_tokens_out": 2000, "max_tool_calls": 1, "fallback_order": ["ollama", "anthropic", "openai", "azure"]},
+        "headers": {"x-client": "zeke", "x-intent": "{{intent}}", "x-size-hint": "{{size_hint}}"}
+    }"#;
+
+    #[test]
+    fn test_resolve_aliases() {
+        let router = Router::from_json(HINTS).unwrap();
+
+        assert_eq!(
+            router.model("code-fast"),
+            Some((Provider::Ollama, "deepseek-coder:14b".to_string()))
+        );
+        assert_eq!(
+            router.model("reason-deep"),
+            Some((Provider::Claude, "claude-3-5-sonnet".to_string()))
+        );
+        assert_eq!(router.model("gpt-4o"), None);
+        assert_eq!(
+            router.alias("code-fast").unwrap().latency,
+            Some(Latency::Low)
+        );
+        assert_eq!(router.fallback_order()[1], Provider::Claude);
+    }
+
+    #[test]
+    fn test_render_headers() {
+        let router = Router::from_json(HINTS).unwrap();
+        let tags = RoutingTags {
+            intent: Intent::Reason,
+            size_hint: SizeHint::Large,
+            ..Default::default()
+        };
+
+        let headers = router.headers(&tags);
+        assert_eq!(headers["x-client"], "zeke");
+        assert_eq!(headers["x-intent"], "reason");
+        assert_eq!(headers["x-size-hint"], "large");
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        // Unknown tag value
+        assert!(
+            Router::from_json(
+                r#"{"aliases": {"a": {"provider": "ollama", "model": "m", "latency": "warp"}}}"#
+            )
+            .is_err()
+        );
+        // Unregistered provider
+        assert!(
+            Router::from_json(
+                r#"{"aliases": {"a": {"provider": "routing-nowhere", "model": "m"}}}"#
+            )
+            .is_err()
+        );
+        // Empty model
+        assert!(
+            Router::from_json(r#"{"aliases": {"a": {"provider": "ollama", "model": ""}}}"#)
+                .is_err()
+        );
+        // Default model must be auto or an alias
+        assert!(Router::from_json(r#"{"defaults": {"model": "missing"}}"#).is_err());
+    }
+
+    #[test]
+    fn test_repository_hints_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../routing_hints.json");
+        if path.exists() {
+            let router = Router::from_file(&path).unwrap();
+            assert!(router.is_alias("code-smart"));
+        }
+    }
+}
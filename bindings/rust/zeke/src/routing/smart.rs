@@ -0,0 +1,754 @@
+//! Automatic model selection
+//!
+//! [`SmartRouter`] classifies a prompt locally and picks the best alias of a
+//! [`Router`] under a [`RoutingPolicy`]. Every decision carries the reasons it
+//! was made, so callers can show or log why a model was chosen.
+
+use super::{Budget, Intent, Latency, ModelAlias, Router, RoutingTags, SizeHint};
+use crate::{Error, Provider, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[cfg(feature = "async")]
+use crate::{
+    backend::{ChatBackend, ChatMessage, ChatRequest},
+    response::ChatResponse,
+};
+#[cfg(feature = "async")]
+use std::{collections::HashMap, sync::Arc};
+
+/// Keywords suggesting the prompt needs multi-step reasoning
+const REASONING_KEYWORDS: &[&str] = &[
+    "why",
+    "explain",
+    "prove",
+    "step by step",
+    "trade-off",
+    "tradeoff",
+    "compare",
+    "analyze",
+    "analyse",
+    "reason",
+    "evaluate",
+    "pros and cons",
+];
+
+/// Keywords suggesting a design question
+const ARCHITECTURE_KEYWORDS: &[&str] = &["architecture", "design a", "system design", "scalab"];
+
+/// Keywords suggesting the prompt is about code
+const CODE_KEYWORDS: &[&str] = &[
+    "function",
+    "compile",
+    "refactor",
+    "implement",
+    "bug",
+    "stack trace",
+    "unit test",
+    "struct",
+    "method",
+    "variable",
+    "syntax",
+];
+
+/// Line fragments that usually only appear in source code
+const CODE_SYNTAX: &[&str] = &[
+    "fn ", "def ", "class ", "import ", "#include", "=>", "->", "::", "};", "();", "const ",
+    "let ", "pub ", "return ",
+];
+
+/// Constraints applied when choosing between local and cloud models
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoutingPolicy {
+    /// Use a local model whenever one can handle the prompt
+    pub prefer_local: bool,
+    /// Allow cloud models when no local model fits
+    pub fallback_to_cloud: bool,
+    /// Highest estimated cost of a single cloud request, in US cents
+    pub max_cloud_cost_cents: u32,
+}
+
+impl Default for RoutingPolicy {
+    fn default() -> Self {
+        Self {
+            prefer_local: true,
+            fallback_to_cloud: true,
+            max_cloud_cost_cents: 200,
+        }
+    }
+}
+
+/// What the local classifier found out about a prompt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptProfile {
+    /// Rough input size in tokens
+    pub estimated_tokens: u32,
+    /// Rough response size in tokens
+    pub expected_output_tokens: u32,
+    /// Whether the prompt contains or asks for code
+    pub is_code: bool,
+    /// Whether the prompt asks for multi-step reasoning
+    pub needs_reasoning: bool,
+    /// Inferred intent
+    pub intent: Intent,
+    /// Inferred task size
+    pub size_hint: SizeHint,
+}
+
+impl PromptProfile {
+    /// Classify a prompt by size, code content and reasoning keywords
+    pub fn classify(prompt: &str) -> Self {
+        let lower = prompt.to_lowercase();
+        let contains_any = |keywords: &[&str]| keywords.iter().any(|k| lower.contains(k));
+
+        let estimated_tokens = prompt.chars().count().div_ceil(4) as u32;
+
+        let fences = prompt.matches("```").count() / 2;
+        let syntax_lines = prompt
+            .lines()
+            .filter(|line| {
+                CODE_SYNTAX.iter().any(|s| line.contains(s)) || line.trim_end().ends_with(';')
+            })
+            .count();
+        let code_words = CODE_KEYWORDS.iter().filter(|k| lower.contains(*k)).count();
+        let is_code =
+            fences > 0 || syntax_lines >= 2 || code_words >= 2 || (syntax_lines + code_words) >= 2;
+
+        let needs_reasoning = contains_any(REASONING_KEYWORDS);
+
+        let intent = if contains_any(ARCHITECTURE_KEYWORDS) {
+            Intent::Architecture
+        } else if lower.contains("refactor") {
+            Intent::Refactor
+        } else if is_code && needs_reasoning {
+            Intent::Reason
+        } else if is_code && estimated_tokens < 64 && fences == 0 {
+            Intent::Completion
+        } else if is_code {
+            Intent::Code
+        } else if needs_reasoning {
+            Intent::Reason
+        } else {
+            Intent::Chat
+        };
+
+        let size_hint = match estimated_tokens {
+            0..64 => SizeHint::Tiny,
+            64..512 => SizeHint::Small,
+            512..2048 => SizeHint::Medium,
+            _ => SizeHint::Large,
+        };
+
+        // Reasoning and design answers tend to be longer than the question
+        let expected_output_tokens = match (size_hint, needs_reasoning) {
+            (SizeHint::Tiny, false) => 256,
+            (SizeHint::Tiny, true) | (SizeHint::Small, false) => 512,
+            (SizeHint::Small, true) | (SizeHint::Medium, false) => 1024,
+            _ => 2048,
+        };
+
+        Self {
+            estimated_tokens,
+            expected_output_tokens,
+            is_code,
+            needs_reasoning,
+            intent,
+            size_hint,
+        }
+    }
+}
+
+/// An alias that was ruled out, and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rejection {
+    /// Alias name
+    pub alias: String,
+    /// Why it was ruled out
+    pub reason: String,
+}
+
+/// An alias that passed the policy, with its score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteCandidate {
+    /// Alias name
+    pub alias: String,
+    /// Provider serving the model
+    pub provider: Provider,
+    /// Model identifier
+    pub model: String,
+    /// Whether the provider runs locally
+    pub local: bool,
+    /// Estimated request cost in US cents, if pricing is known
+    pub estimated_cost_cents: Option<f64>,
+    /// Ranking score; higher is better
+    pub score: i32,
+    /// How the score came about
+    pub reasons: Vec<String>,
+}
+
+/// The model chosen for a prompt, and why
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoutingDecision {
+    /// Classification of the prompt
+    pub profile: PromptProfile,
+    /// Tags the decision was made for
+    pub tags: RoutingTags,
+    /// Candidates that passed the policy, best first
+    pub candidates: Vec<RouteCandidate>,
+    /// Index in `candidates` of the selected model
+    ///
+    /// The best-ranked one, unless it failed and a fallback answered instead.
+    #[serde(default)]
+    pub selected_index: usize,
+    /// Aliases ruled out by the policy
+    pub rejected: Vec<Rejection>,
+}
+
+impl RoutingDecision {
+    /// The selected candidate
+    pub fn selected(&self) -> &RouteCandidate {
+        &self.candidates[self.selected_index]
+    }
+
+    /// Provider of the selected model
+    pub fn provider(&self) -> Provider {
+        self.selected().provider
+    }
+
+    /// Identifier of the selected model
+    pub fn model(&self) -> &str {
+        &self.selected().model
+    }
+
+    /// Human-readable explanation of the decision
+    pub fn explanation(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for RoutingDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let selected = self.selected();
+        let profile = &self.profile;
+        writeln!(
+            f,
+            "Selected '{}' ({} {}) for a {} {} prompt (~{} tokens{}{})",
+            selected.alias,
+            selected.provider,
+            selected.model,
+            profile.size_hint,
+            profile.intent,
+            profile.estimated_tokens,
+            if profile.is_code { ", code" } else { "" },
+            if profile.needs_reasoning {
+                ", reasoning"
+            } else {
+                ""
+            },
+        )?;
+        for reason in &selected.reasons {
+            writeln!(f, "  + {}", reason)?;
+        }
+        for (index, other) in self.candidates.iter().enumerate() {
+            if index < self.selected_index {
+                writeln!(
+                    f,
+                    "  ranked above: '{}' scored {}, did not answer",
+                    other.alias, other.score
+                )?;
+            } else if index > self.selected_index {
+                writeln!(f, "  fallback '{}' scored {}", other.alias, other.score)?;
+            }
+        }
+        for rejection in &self.rejected {
+            writeln!(f, "  rejected '{}': {}", rejection.alias, rejection.reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// A chat response together with the routing decision that produced it
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[derive(Debug, Clone)]
+pub struct RoutedResponse {
+    /// The model's answer
+    pub response: ChatResponse,
+    /// Why the answering model was chosen
+    pub decision: RoutingDecision,
+    /// Candidates that were tried first and failed, with their errors
+    pub failures: Vec<Rejection>,
+}
+
+/// Picks a model for each prompt from the aliases of a [`Router`]
+#[derive(Debug, Clone)]
+pub struct SmartRouter {
+    router: Router,
+    policy: RoutingPolicy,
+    #[cfg(feature = "async")]
+    backends: HashMap<Provider, Arc<dyn ChatBackend>>,
+}
+
+impl SmartRouter {
+    /// Create a smart router over the aliases of a router
+    pub fn new(router: Router, policy: RoutingPolicy) -> Self {
+        Self {
+            router,
+            policy,
+            #[cfg(feature = "async")]
+            backends: HashMap::new(),
+        }
+    }
+
+    /// The routing policy
+    pub fn policy(&self) -> &RoutingPolicy {
+        &self.policy
+    }
+
+    /// The underlying alias router
+    pub fn router(&self) -> &Router {
+        &self.router
+    }
+
+    /// Choose a model for a prompt using the default tags of the routing hints
+    pub fn route(&self, prompt: &str) -> Result<RoutingDecision> {
+        let mut tags = self.router.hints().defaults.tags.clone();
+        let profile = PromptProfile::classify(prompt);
+        tags.intent = profile.intent;
+        tags.size_hint = profile.size_hint;
+        self.decide(profile, tags)
+    }
+
+    /// Choose a model for a prompt, letting explicit tags override the classifier
+    pub fn route_with_tags(&self, prompt: &str, tags: RoutingTags) -> Result<RoutingDecision> {
+        let mut profile = PromptProfile::classify(prompt);
+        profile.intent = tags.intent;
+        profile.size_hint = profile.size_hint.max(tags.size_hint);
+        self.decide(profile, tags)
+    }
+
+    fn decide(&self, profile: PromptProfile, tags: RoutingTags) -> Result<RoutingDecision> {
+        let mut candidates = Vec::new();
+        let mut rejected = Vec::new();
+
+        for (name, alias) in &self.router.hints().aliases {
+            match self.evaluate(name, alias, &profile, &tags) {
+                Ok(candidate) => candidates.push(candidate),
+                Err(reason) => rejected.push(Rejection {
+                    alias: name.clone(),
+                    reason,
+                }),
+            }
+        }
+
+        if self.policy.prefer_local && !self.policy.fallback_to_cloud {
+            let (local, cloud): (Vec<_>, Vec<_>) = candidates.into_iter().partition(|c| c.local);
+            candidates = local;
+            rejected.extend(cloud.into_iter().map(|c| Rejection {
+                alias: c.alias,
+                reason: "cloud fallback is disabled".to_string(),
+            }));
+        }
+
+        if candidates.is_empty() {
+            let reasons: Vec<String> = rejected
+                .iter()
+                .map(|r| format!("{}: {}", r.alias, r.reason))
+                .collect();
+            return Err(Error::ConfigError {
+                message: format!(
+                    "No routing alias satisfies the routing policy ({})",
+                    if reasons.is_empty() {
+                        "no aliases defined".to_string()
+                    } else {
+                        reasons.join("; ")
+                    }
+                ),
+            });
+        }
+
+        // Local models that can handle the prompt go first when preferred, and
+        // cloud models only serve as fallbacks. Otherwise the highest score
+        // wins; ties keep alias order so decisions are stable.
+        let local_fits = candidates
+            .iter()
+            .any(|c| c.local && self.fits(&c.alias, &profile));
+        let locals_first = self.policy.prefer_local && local_fits;
+        candidates.sort_by_key(|c| std::cmp::Reverse((locals_first && c.local, c.score)));
+
+        Ok(RoutingDecision {
+            profile,
+            tags,
+            candidates,
+            selected_index: 0,
+            rejected,
+        })
+    }
+
+    fn fits(&self, name: &str, profile: &PromptProfile) -> bool {
+        self.router
+            .alias(name)
+            .and_then(|alias| alias.size_hint)
+            .is_none_or(|size| size >= profile.size_hint)
+    }
+
+    /// Score an alias, or explain why it cannot serve the prompt
+    fn evaluate(
+        &self,
+        name: &str,
+        alias: &ModelAlias,
+        profile: &PromptProfile,
+        tags: &RoutingTags,
+    ) -> std::result::Result<RouteCandidate, String> {
+        let local = alias.provider.is_local();
+        let info = alias.provider.model_info(&alias.model);
+        let estimated_cost_cents = info
+            .as_ref()
+            .and_then(|info| {
+                info.estimate_cost(profile.estimated_tokens, profile.expected_output_tokens)
+            })
+            .map(|dollars| dollars * 100.0);
+
+        if !local
+            && let Some(cents) = estimated_cost_cents
+            && cents > self.policy.max_cloud_cost_cents as f64
+        {
+            return Err(format!(
+                "estimated {:.2}¢ exceeds the {}¢ cloud budget",
+                cents, self.policy.max_cloud_cost_cents
+            ));
+        }
+        if let Some(info) = &info
+            && profile.estimated_tokens + profile.expected_output_tokens > info.context_length
+        {
+            return Err(format!(
+                "prompt does not fit the {}-token context window",
+                info.context_length
+            ));
+        }
+
+        let mut score = 0;
+        let mut reasons = Vec::new();
+
+        match alias.intent {
+            Some(intent) if intent == profile.intent => {
+                score += 4;
+                reasons.push(format!("built for {} prompts", intent));
+            }
+            Some(Intent::Reason) if profile.needs_reasoning => {
+                score += 3;
+                reasons.push("suited to reasoning".to_string());
+            }
+            Some(Intent::Code | Intent::Completion | Intent::Refactor) if profile.is_code => {
+                score += 2;
+                reasons.push("suited to code".to_string());
+            }
+            _ => {}
+        }
+
+        match alias.size_hint {
+            Some(size) if size < profile.size_hint => {
+                score -= 4 * (profile.size_hint as i32 - size as i32);
+                reasons.push(format!("sized for {} tasks, smaller than this one", size));
+            }
+            Some(size) => {
+                // The smallest model that fits is the fastest and cheapest
+                score += 3 - (size as i32 - profile.size_hint as i32);
+                reasons.push(format!("sized for {} tasks", size));
+            }
+            None => {}
+        }
+
+        if tags.latency == Latency::Low && alias.latency == Some(Latency::Low) {
+            score += 2;
+            reasons.push("low latency".to_string());
+        }
+
+        if local {
+            if self.policy.prefer_local {
+                score += 2;
+                reasons.push("runs locally".to_string());
+            }
+        } else {
+            match estimated_cost_cents {
+                Some(cents) => {
+                    if tags.budget == Budget::Low {
+                        score -= cents.ceil() as i32;
+                    }
+                    reasons.push(format!("estimated cost {:.2}¢", cents));
+                }
+                None => reasons.push("cost unknown".to_string()),
+            }
+        }
+
+        Ok(RouteCandidate {
+            alias: name.to_string(),
+            provider: alias.provider,
+            model: alias.model.clone(),
+            local,
+            estimated_cost_cents,
+            score,
+            reasons,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+impl SmartRouter {
+    /// Register the backend that serves a provider's models
+    pub fn with_backend(mut self, backend: Arc<dyn ChatBackend>) -> Self {
+        self.backends.insert(backend.provider(), backend);
+        self
+    }
+
+    /// Route a prompt and send it to the chosen model
+    ///
+    /// Candidates are tried best first until one answers; those without a
+    /// registered backend are skipped.
+    pub async fn chat(&self, prompt: &str) -> Result<RoutedResponse> {
+        let decision = self.route(prompt)?;
+        self.send(prompt, decision).await
+    }
+
+    /// Like [`chat`](Self::chat), with explicit routing tags
+    pub async fn chat_with_tags(&self, prompt: &str, tags: RoutingTags) -> Result<RoutedResponse> {
+        let decision = self.route_with_tags(prompt, tags)?;
+        self.send(prompt, decision).await
+    }
+
+    async fn send(&self, prompt: &str, mut decision: RoutingDecision) -> Result<RoutedResponse> {
+        let mut failures = Vec::new();
+        let mut last_error = None;
+
+        for index in 0..decision.candidates.len() {
+            let candidate = &decision.candidates[index];
+            let Some(backend) = self.backends.get(&candidate.provider) else {
+                failures.push(Rejection {
+                    alias: candidate.alias.clone(),
+                    reason: format!("no backend registered for {}", candidate.provider),
+                });
+                continue;
+            };
+
+            let request =
+                ChatRequest::new(candidate.model.clone()).message(ChatMessage::user(prompt));
+            match backend.chat(request).await {
+                Ok(response) => {
+                    decision.selected_index = index;
+                    return Ok(RoutedResponse {
+                        response,
+                        decision,
+                        failures,
+                    });
+                }
+                Err(error) => {
+                    failures.push(Rejection {
+                        alias: candidate.alias.clone(),
+                        reason: error.to_string(),
+                    });
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            let reasons: Vec<String> = failures
+                .iter()
+                .map(|f| format!("{}: {}", f.alias, f.reason))
+                .collect();
+            Error::provider_unavailable("router".to_string(), reasons.join("; "))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HINTS: &str = r#"{
+        "defaults": {"model": "auto", "tags": {"project": "test", "latency": "normal", "budget": "normal", "intent": "code", "size_hint": "small"}},
+        "aliases": {
+            "code-fast": {"provider": "ollama", "model": "deepseek-coder:14b", "intent": "code", "latency": "low", "size_hint": "tiny"},
+            "code-plus": {"provider": "ollama", "model": "deepseek-coder:33b", "intent": "code", "latency": "normal", "size_hint": "small"},
+            "code-smart": {"provider": "openai", "model": "gpt-4o", "intent": "code", "latency": "normal", "size_hint": "medium"},
+            "reason-deep": {"provider": "anthropic", "model": "claude-3-5-sonnet-20241022", "intent": "reason", "latency": "normal", "size_hint": "large"}
+        }
+    }"#;
+
+    fn smart(policy: RoutingPolicy) -> SmartRouter {
+        SmartRouter::new(Router::from_json(HINTS).unwrap(), policy)
+    }
+
+    #[test]
+    fn test_classify() {
+        let profile = PromptProfile::classify("Hello there, how are you?");
+        assert_eq!(profile.intent, Intent::Chat);
+        assert_eq!(profile.size_hint, SizeHint::Tiny);
+        assert!(!profile.is_code);
+
+        let profile = PromptProfile::classify(
+            "```rust\nfn main() {\n    let x = 1;\n}\n```\nAdd error handling",
+        );
+        assert!(profile.is_code);
+        assert_eq!(profile.intent, Intent::Code);
+
+        let profile = PromptProfile::classify(
+            "Explain why the borrow checker rejects this function: let y = &mut x;",
+        );
+        assert!(profile.needs_reasoning);
+        assert_eq!(profile.intent, Intent::Reason);
+
+        let long = "word ".repeat(4000);
+        assert_eq!(PromptProfile::classify(&long).size_hint, SizeHint::Large);
+    }
+
+    #[test]
+    fn test_prefers_smallest_local_model() {
+        let decision = smart(RoutingPolicy::default())
+            .route("Write a function that reverses a string; return the result.")
+            .unwrap();
+
+        assert_eq!(decision.selected().alias, "code-fast");
+        assert_eq!(decision.candidates[1].alias, "code-plus");
+        assert!(decision.candidates.iter().skip(2).all(|c| !c.local));
+        assert!(decision.explanation().contains("runs locally"));
+    }
+
+    #[test]
+    fn test_falls_back_to_cloud_for_large_reasoning() {
+        let prompt = format!(
+            "Explain step by step why this design is slow.\n{}",
+            "context ".repeat(1500)
+        );
+        let decision = smart(RoutingPolicy::default()).route(&prompt).unwrap();
+
+        assert_eq!(decision.provider(), Provider::Claude);
+        assert_eq!(decision.selected().alias, "reason-deep");
+    }
+
+    #[test]
+    fn test_policy_constraints() {
+        let prompt = format!(
+            "Explain step by step why this design is slow.\n{}",
+            "context ".repeat(1500)
+        );
+
+        // Without cloud fallback a too-small local model is still preferred
+        let local_only = RoutingPolicy {
+            fallback_to_cloud: false,
+            ..Default::default()
+        };
+        assert!(smart(local_only).route(&prompt).unwrap().selected().local);
+
+        // A tiny cloud budget rules out every priced cloud model
+        let cheap = RoutingPolicy {
+            prefer_local: false,
+            max_cloud_cost_cents: 0,
+            ..Default::default()
+        };
+        let decision = smart(cheap).route(&prompt).unwrap();
+        assert!(decision.candidates.iter().all(|c| c.local));
+        assert!(
+            decision
+                .rejected
+                .iter()
+                .any(|r| r.reason.contains("cloud budget"))
+        );
+    }
+
+    #[test]
+    fn test_explicit_tags_override_classifier() {
+        let tags = RoutingTags {
+            intent: Intent::Reason,
+            size_hint: SizeHint::Large,
+            ..Default::default()
+        };
+        let decision = smart(RoutingPolicy::default())
+            .route_with_tags("hi", tags)
+            .unwrap();
+        assert_eq!(decision.selected().alias, "reason-deep");
+    }
+
+    #[cfg(feature = "async")]
+    #[derive(Debug)]
+    struct MockBackend {
+        provider: Provider,
+        fail: bool,
+    }
+
+    #[cfg(feature = "async")]
+    impl ChatBackend for MockBackend {
+        fn provider(&self) -> Provider {
+            self.provider
+        }
+
+        fn chat(
+            &self,
+            request: ChatRequest,
+        ) -> futures::future::BoxFuture<'_, Result<ChatResponse>> {
+            Box::pin(async move {
+                if self.fail {
+                    return Err(Error::network("connection refused"));
+                }
+                Ok(crate::response::ResponseBuilder::new()
+                    .content("ok")
+                    .provider(self.provider)
+                    .model(request.model)
+                    .build())
+            })
+        }
+
+        fn chat_stream(
+            &self,
+            _request: ChatRequest,
+        ) -> futures::future::BoxFuture<'_, Result<crate::backend::ChunkStream>> {
+            Box::pin(async { Err(Error::streaming("not supported")) })
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_chat_falls_back_when_local_model_fails() {
+        let router = smart(RoutingPolicy::default())
+            .with_backend(Arc::new(MockBackend {
+                provider: Provider::Ollama,
+                fail: true,
+            }))
+            .with_backend(Arc::new(MockBackend {
+                provider: Provider::OpenAI,
+                fail: false,
+            }));
+
+        // Both local models rank first but fail; Claude has no backend
+        let routed = router
+            .chat("```rust\nfn main() {}\n```\nAdd logging")
+            .await
+            .unwrap();
+        assert_eq!(routed.response.content, "ok");
+        assert_eq!(routed.decision.provider(), Provider::OpenAI);
+        assert_eq!(routed.decision.model(), "gpt-4o");
+        assert!(routed.failures.iter().any(|f| f.alias == "code-fast"));
+
+        // The ranking is kept as routed; only the selection moves
+        let decision = &routed.decision;
+        assert_eq!(decision.candidates[0].alias, "code-fast");
+        assert!(decision.selected_index > 0);
+        assert_eq!(decision.selected().model, "gpt-4o");
+        let explanation = decision.explanation();
+        assert!(explanation.starts_with(&format!("Selected '{}'", decision.selected().alias)));
+        assert!(explanation.contains("ranked above: 'code-fast'"));
+
+        // Without cloud fallback the first local failure is final
+        let local_only = smart(RoutingPolicy {
+            fallback_to_cloud: false,
+            ..Default::default()
+        })
+        .with_backend(Arc::new(MockBackend {
+            provider: Provider::Ollama,
+            fail: true,
+        }));
+        assert!(local_only.chat("fn main() {}; let x = 1;").await.is_err());
+    }
+}
@@ -2,8 +2,8 @@
 
 #[cfg(feature = "async")]
 use crate::{
-    ffi_utils::CStringHolder,
-    error::{check_result_with_context, Error, Result},
+    ffi_utils::{catch_callback_panic, CStringHolder},
+    error::{Error, Result},
     response::StreamChunk,
     Zeke,
 };
@@ -22,6 +22,72 @@ pub struct ZekeStream {
     completed: bool,
 }
 
+/// State shared with [`stream_callback`] for one streaming call
+#[cfg(feature = "async")]
+struct StreamContext {
+    sender: mpsc::UnboundedSender<Result<StreamChunk>>,
+    stream_id: Uuid,
+    chunk_index: u32,
+}
+
+/// A boxed [`StreamContext`] on its way to the thread that streams
+///
+/// The pointer owns the context until the core call that uses it returns.
+#[cfg(feature = "async")]
+struct ContextPtr(*mut StreamContext);
+
+// SAFETY: the pointer is the only handle to its context, and the core only
+// calls back on the thread making the streaming call
+#[cfg(feature = "async")]
+unsafe impl Send for ContextPtr {}
+
+#[cfg(feature = "async")]
+impl ContextPtr {
+    fn get(&self) -> *mut StreamContext {
+        self.0
+    }
+}
+
+/// Forward a chunk from the core to the stream's channel
+///
+/// Returns false to stop the core once the [`ZekeStream`] has been dropped,
+/// or after a panic, which is sent to the stream as
+/// [`Error::CallbackPanicked`].
+#[cfg(feature = "async")]
+unsafe extern "C" fn stream_callback(
+    chunk: *const ZekeStreamChunk,
+    user_data: *mut std::ffi::c_void,
+) -> bool {
+    if chunk.is_null() || user_data.is_null() {
+        return false;
+    }
+    
+    let context = unsafe { &mut *(user_data as *mut StreamContext) };
+    let chunk_ref = unsafe { &*chunk };
+    
+    let forwarded = catch_callback_panic(|| {
+        let result = unsafe { crate::ffi_utils::c_string_to_string(chunk_ref.content) }
+            .map(|content| {
+                let stream_chunk = StreamChunk::new(
+                    context.stream_id,
+                    content,
+                    context.chunk_index,
+                    chunk_ref.is_final,
+                );
+                context.chunk_index += 1;
+                stream_chunk
+            });
+        
+        // A send error means the receiver was dropped
+        context.sender.send(result).is_ok()
+    });
+    
+    forwarded.unwrap_or_else(|e| {
+        let _ = context.sender.send(Err(e));
+        false
+    })
+}
+
 #[cfg(feature = "async")]
 impl ZekeStream {
     /// Create a new streaming chat session
@@ -31,70 +97,43 @@ impl ZekeStream {
         
         let message_cstr = CStringHolder::new(message)?;
         
-        // Context for the callback
-        struct StreamContext {
-            sender: mpsc::UnboundedSender<Result<StreamChunk>>,
-            stream_id: Uuid,
-            chunk_index: u32,
-        }
-        
-        let mut context = Box::new(StreamContext {
+        let context = Box::new(StreamContext {
             sender,
             stream_id,
             chunk_index: 0,
         });
         
-        unsafe extern "C" fn stream_callback(
-            chunk: *const ZekeStreamChunk,
-            user_data: *mut std::ffi::c_void,
-        ) {
-            if chunk.is_null() || user_data.is_null() {
-                return;
-            }
-            
-            let context = &mut *(user_data as *mut StreamContext);
-            let chunk_ref = &*chunk;
-            
-            let result = crate::ffi_utils::c_string_to_string(chunk_ref.content)
-                .map(|content| {
-                    let stream_chunk = StreamChunk::new(
-                        context.stream_id,
-                        content,
-                        context.chunk_index,
-                        chunk_ref.is_final,
-                    );
-                    context.chunk_index += 1;
-                    stream_chunk
-                });
-            
-            // Send the result (ignore send errors - receiver might be dropped)
-            let _ = context.sender.send(result);
+        // Stream on the blocking pool, as the core call lasts the whole stream;
+        // the task keeps the core alive even if the configuration is swapped
+        let core = zeke.core()?;
+        if !zeke_has!(zeke_chat_stream) {
+            return Err(Error::MissingSymbol {
+                symbol: "zeke_chat_stream".to_string(),
+            });
         }
-        
-        // Start the streaming in a background task
-        let handle = zeke.handle;
-        let context_ptr = Box::into_raw(context);
-        
-        tokio::spawn(async move {
+        let context_ptr = ContextPtr(Box::into_raw(context));
+
+        tokio::task::spawn_blocking(move || {
+            let context_ptr = context_ptr.get();
+            let handle = core.lock();
+            // Checked above, so the call cannot fail to resolve
             let result = unsafe {
-                zeke_chat_stream(
-                    handle,
+                zeke_call!(zeke_chat_stream(
+                    handle.raw(),
                     message_cstr.as_ptr(),
                     Some(stream_callback),
                     context_ptr as *mut std::ffi::c_void,
-                )
-            };
-            
-            // Clean up the context
-            unsafe {
-                let _ = Box::from_raw(context_ptr);
+                ))
             }
+            .unwrap_or(ZekeErrorCode::ZEKE_STREAMING_FAILED);
+            let outcome = handle.check(result);
+            drop(handle);
             
-            // If there was an error, send it through the channel
-            if let Err(e) = check_result_with_context(result) {
-                // The context is already cleaned up, so we can't send through sender
-                // This is a limitation of the current design
+            // The core is done with the context; reclaim it and report any failure
+            let context = unsafe { Box::from_raw(context_ptr) };
+            if let Err(e) = outcome {
                 tracing::error!("Streaming error: {}", e);
+                let _ = context.sender.send(Err(e));
             }
         });
         
@@ -205,8 +244,9 @@ pub mod utils {
     /// Create a mock stream for testing
     pub fn mock_stream(chunks: Vec<String>) -> BoxStream<'static, Result<StreamChunk>> {
         let stream_id = Uuid::new_v4();
+        let last = chunks.len().saturating_sub(1);
         let chunk_stream = stream::iter(chunks.into_iter().enumerate().map(move |(i, content)| {
-            let is_final = i == chunks.len() - 1;
+            let is_final = i == last;
             Ok(StreamChunk::new(stream_id, content, i as u32, is_final))
         }));
         
@@ -218,7 +258,7 @@ pub mod utils {
     where
         S: Stream<Item = Result<StreamChunk>> + Send + 'static,
     {
-        let combined = stream::select_all(streams);
+        let combined = stream::select_all(streams.into_iter().map(StreamExt::boxed));
         Box::pin(combined)
     }
     
@@ -246,7 +286,7 @@ pub mod utils {
     where
         S: Stream<Item = Result<StreamChunk>> + Send + 'static,
     {
-        let buffered = stream.buffered(buffer_size);
+        let buffered = stream.map(futures::future::ready).buffered(buffer_size);
         Box::pin(buffered)
     }
 }
@@ -281,12 +321,43 @@ mod tests {
     #[tokio::test]
     async fn test_collect_content() {
         let chunks = vec!["Hello".to_string(), " ".to_string(), "World!".to_string()];
-        let stream = utils::mock_stream(chunks);
-        
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut mock = utils::mock_stream(chunks);
+        while let Some(chunk) = mock.next().await {
+            sender.send(chunk).unwrap();
+        }
+        drop(sender);
+        let stream = ZekeStream {
+            receiver,
+            stream_id: Uuid::new_v4(),
+            completed: false,
+        };
+
         let content = stream.collect_content().await.unwrap();
         assert_eq!(content, "Hello World!");
     }
     
+    #[test]
+    fn test_stream_callback_stops_when_dropped() {
+        use crate::ffi_utils::harness::drive_stream;
+        
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut context = StreamContext {
+            sender,
+            stream_id: Uuid::new_v4(),
+            chunk_index: 0,
+        };
+        let user_data = &mut context as *mut _ as *mut std::ffi::c_void;
+        
+        assert_eq!(unsafe { drive_stream(Some(stream_callback), user_data, &["a", "b"]) }, 2);
+        assert_eq!(receiver.try_recv().unwrap().unwrap().content, "a");
+        assert!(receiver.try_recv().unwrap().unwrap().is_final);
+        
+        // Once the consumer is gone the core is told to stop
+        drop(receiver);
+        assert_eq!(unsafe { drive_stream(Some(stream_callback), user_data, &["c", "d"]) }, 1);
+    }
+    
     #[tokio::test]
     async fn test_rate_limiting() {
         use std::time::Instant;
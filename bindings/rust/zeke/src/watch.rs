@@ -0,0 +1,194 @@
+//! Configuration file watching
+//!
+//! [`Config::watch`] turns edits to a configuration file into a stream of
+//! validated configurations, ready to hand to [`Zeke::apply_config`](crate::Zeke::apply_config).
+
+use crate::{Config, Error, Result};
+use futures::stream::{self, BoxStream};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tracing::{debug, warn};
+
+/// Quiet period after the last change before a file is reloaded
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Stream of reloaded configurations
+///
+/// Each item is either a configuration that loaded and validated, or the
+/// error that stopped an edit from being applied. The stream keeps watching
+/// after an error, so fixing the file produces a new configuration.
+pub type ConfigStream = BoxStream<'static, Result<Config>>;
+
+struct WatchState {
+    // Dropping the watcher stops the notifications
+    _watcher: RecommendedWatcher,
+    events: UnboundedReceiver<notify::Result<Event>>,
+    path: PathBuf,
+    debounce: Duration,
+    last_contents: Option<String>,
+}
+
+impl WatchState {
+    /// Wait for the next change to the watched file, then for it to settle
+    async fn next_change(&mut self) -> Option<()> {
+        loop {
+            let event = self.events.recv().await?;
+            if self.concerns_file(event) {
+                break;
+            }
+        }
+
+        // Editors often write a file in several steps; reload once they stop
+        while let Ok(Some(_)) = tokio::time::timeout(self.debounce, self.events.recv()).await {}
+        Some(())
+    }
+
+    fn concerns_file(&self, event: notify::Result<Event>) -> bool {
+        match event {
+            Ok(event) => {
+                !matches!(event.kind, EventKind::Access(_))
+                    && event.paths.iter().any(|p| p.file_name() == self.path.file_name())
+            }
+            Err(e) => {
+                warn!("Config watcher error: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Reload the file, or `None` if its contents have not changed
+    fn reload(&mut self) -> Option<Result<Config>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                self.last_contents = None;
+                return Some(Err(Error::ConfigError {
+                    message: format!("Failed to read config file {}: {}", self.path.display(), e),
+                }));
+            }
+        };
+        if self.last_contents.as_deref() == Some(contents.as_str()) {
+            return None;
+        }
+        self.last_contents = Some(contents);
+
+        debug!("Reloading config from {}", self.path.display());
+        Some(Config::from_file(&self.path).and_then(|config| {
+            config.validate()?;
+            Ok(config)
+        }))
+    }
+}
+
+impl Config {
+    /// Watch a configuration file for changes
+    ///
+    /// Nothing is emitted for the file as it is now; each later edit yields
+    /// one item once writes have paused for [`DEFAULT_DEBOUNCE`]. Invalid
+    /// edits are reported as errors rather than applied.
+    pub fn watch<P: AsRef<Path>>(path: P) -> Result<ConfigStream> {
+        Self::watch_with_debounce(path, DEFAULT_DEBOUNCE)
+    }
+
+    /// Watch a configuration file with a custom debounce interval
+    pub fn watch_with_debounce<P: AsRef<Path>>(path: P, debounce: Duration) -> Result<ConfigStream> {
+        let path = path.as_ref().to_path_buf();
+        if path.file_name().is_none() {
+            return Err(Error::ConfigError {
+                message: format!("Not a config file: {}", path.display()),
+            });
+        }
+
+        // Watch the directory so saves that replace the file are still seen
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (sender, events) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        })
+        .map_err(|e| Error::ConfigError {
+            message: format!("Failed to create config watcher: {}", e),
+        })?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| Error::ConfigError {
+                message: format!("Failed to watch {}: {}", dir.display(), e),
+            })?;
+
+        let state = WatchState {
+            _watcher: watcher,
+            events,
+            last_contents: std::fs::read_to_string(&path).ok(),
+            path,
+            debounce,
+        };
+
+        Ok(Box::pin(stream::unfold(state, |mut state| async move {
+            loop {
+                state.next_change().await?;
+                if let Some(item) = state.reload() {
+                    return Some((item, state));
+                }
+            }
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Provider;
+    use futures::StreamExt;
+    use tempfile::TempDir;
+
+    const DEBOUNCE: Duration = Duration::from_millis(50);
+
+    async fn next(stream: &mut ConfigStream) -> Result<Config> {
+        tokio::time::timeout(Duration::from_secs(10), stream.next())
+            .await
+            .expect("no config update within 10s")
+            .expect("watch stream ended")
+    }
+
+    #[tokio::test]
+    async fn test_watch_reports_valid_and_invalid_edits() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("zeke.json");
+        std::fs::write(&path, r#"{"provider": "ollama", "model": "llama3"}"#).unwrap();
+
+        let mut stream = Config::watch_with_debounce(&path, DEBOUNCE).unwrap();
+
+        std::fs::write(&path, r#"{"provider": "ollama", "model": "qwen2.5-coder"}"#).unwrap();
+        let config = next(&mut stream).await.unwrap();
+        assert_eq!(config.provider, Provider::Ollama);
+        assert_eq!(config.model, "qwen2.5-coder");
+
+        std::fs::write(&path, r#"{"provider": "ollama", "model": "llama3", "temperature": 5.0}"#)
+            .unwrap();
+        assert!(next(&mut stream).await.is_err());
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(next(&mut stream).await.is_err());
+
+        std::fs::write(&path, r#"{"provider": "ollama", "model": "llama3"}"#).unwrap();
+        assert_eq!(next(&mut stream).await.unwrap().model, "llama3");
+    }
+
+    #[tokio::test]
+    async fn test_watch_ignores_other_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("zeke.json");
+        std::fs::write(&path, r#"{"provider": "ollama", "model": "llama3"}"#).unwrap();
+
+        let mut stream = Config::watch_with_debounce(&path, DEBOUNCE).unwrap();
+        std::fs::write(dir.path().join("other.json"), "{}").unwrap();
+
+        let update = tokio::time::timeout(Duration::from_millis(500), stream.next()).await;
+        assert!(update.is_err());
+    }
+}
@@ -1,74 +1,478 @@
 //! Main Zeke client implementation
 
 use crate::{
-    ffi_utils::{CStringManager, CStringHolder},
+    ffi_utils::{catch_callback_panic, run_blocking, CStringManager, CStringHolder},
     error::{check_result_with_context, Error, Result},
+    raw::{ChunkArena, RawResponse},
     response::{ChatResponse, ResponseMetadata, StreamChunk},
     Config, Provider,
 };
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 use std::time::{Duration, Instant};
-use tracing::{debug, error, info, trace};
+use tracing::{debug, info, trace, warn};
 use uuid::Uuid;
 use zeke_sys::*;
 
+#[cfg(feature = "async")]
+use crate::backend::{ChatBackend, ChatRequest};
+
+/// A Zig core handle, destroyed once the last request using it finishes
+///
+/// The core keeps per-handle state such as the active provider and auth
+/// tokens, so a handle must never be used by two threads at once. Every call
+/// goes through [`lock`](Self::lock), which serializes them; a
+/// [`ZekePool`](crate::ZekePool) runs requests in parallel on several handles.
+#[derive(Debug)]
+pub(crate) struct CoreHandle {
+    raw: *mut ZekeHandle,
+    lock: Mutex<()>,
+    _string_manager: CStringManager, // Keep strings alive
+}
+
+// Safety: the handle is only passed to the core while `lock` is held (or from
+// `drop`, which has exclusive access), so calls never overlap. The core does
+// not tie a handle to the thread that created it, so moving it is fine.
+unsafe impl Send for CoreHandle {}
+unsafe impl Sync for CoreHandle {}
+
+/// Exclusive access to a core handle for the duration of one call
+///
+/// The core reports errors through a thread-local, so check a call's result
+/// before the next `.await`, which may resume on another thread.
+pub(crate) struct CoreGuard<'a> {
+    raw: *mut ZekeHandle,
+    _guard: MutexGuard<'a, ()>,
+}
+
+impl CoreGuard<'_> {
+    /// The raw handle; only valid while the guard is held
+    pub(crate) fn raw(&self) -> *mut ZekeHandle {
+        self.raw
+    }
+
+    /// Check the code a call on this handle returned
+    ///
+    /// No other call can run on the handle while the guard is held, so the
+    /// details read back describe the call that returned `code`.
+    pub(crate) fn check(&self, code: ZekeErrorCode) -> Result<()> {
+        if code == ZekeErrorCode::ZEKE_SUCCESS {
+            return Ok(());
+        }
+
+        let mut detail = ZekeErrorDetail::default();
+        let read = unsafe { zeke_call!(zeke_get_error_detail(self.raw, &mut detail)) };
+        match read {
+            Ok(ZekeErrorCode::ZEKE_SUCCESS) if detail.code != ZekeErrorCode::ZEKE_SUCCESS => {
+                Err(Error::from(&detail))
+            }
+            _ => check_result_with_context(code),
+        }
+    }
+}
+
+impl CoreHandle {
+    /// Initialize the Zig core for a configuration
+    fn init(config: &Config) -> Result<Self> {
+        let mut string_manager = CStringManager::new();
+        let ffi_config = crate::native_config::to_ffi(config, &mut string_manager)?;
+
+        // Initialize Zeke
+        let handle = unsafe { zeke_call!(zeke_init(&ffi_config)) }?;
+        
+        if handle.is_null() {
+            let error_msg = unsafe { get_last_error().unwrap_or_else(|| "Unknown initialization error".to_string()) };
+            return Err(Error::initialization(error_msg));
+        }
+
+        Ok(Self {
+            raw: handle,
+            lock: Mutex::new(()),
+            _string_manager: string_manager,
+        })
+    }
+
+    /// Placeholder for instances served by a native backend
+    fn none() -> Self {
+        Self {
+            raw: std::ptr::null_mut(),
+            lock: Mutex::new(()),
+            _string_manager: CStringManager::new(),
+        }
+    }
+
+    /// Wait for exclusive use of the handle
+    pub(crate) fn lock(&self) -> CoreGuard<'_> {
+        CoreGuard {
+            raw: self.raw,
+            _guard: self.lock.lock().unwrap_or_else(|e| e.into_inner()),
+        }
+    }
+}
+
+impl Drop for CoreHandle {
+    fn drop(&mut self) {
+        if !self.raw.is_null() {
+            debug!("Destroying Zeke instance");
+            // The library is loaded, or `init` could not have produced a handle
+            let _ = unsafe { zeke_call!(zeke_destroy(self.raw)) };
+            self.raw = std::ptr::null_mut();
+        }
+    }
+}
+
+/// The configuration and core handle a request runs against
+///
+/// Requests take a snapshot when they start, so [`Zeke::apply_config`] never
+/// pulls either out from under a request in flight.
+#[derive(Debug, Clone)]
+struct Session {
+    core: Arc<CoreHandle>,
+    config: Arc<Config>,
+}
+
+/// State shared with [`stream_callback`] for one [`Zeke::chat_stream_callback`] call
+struct CallbackContext<F> {
+    callback: F,
+    stream_id: Uuid,
+    chunk_index: u32,
+    panic: Option<Error>,
+}
+
+/// Hand a chunk from the core to the user's callback
+///
+/// Returns false to stop the core once the callback has panicked; the panic
+/// is kept in the context for `chat_stream_callback` to return.
+unsafe extern "C" fn stream_callback<F>(
+    chunk: *const ZekeStreamChunk,
+    user_data: *mut std::ffi::c_void,
+) -> bool
+where
+    F: FnMut(Result<StreamChunk>),
+{
+    if chunk.is_null() || user_data.is_null() {
+        return false;
+    }
+
+    let context = unsafe { &mut *(user_data as *mut CallbackContext<F>) };
+    if context.panic.is_some() {
+        return false;
+    }
+    let chunk_ref = unsafe { &*chunk };
+
+    let delivered = catch_callback_panic(|| {
+        let result = unsafe { crate::ffi_utils::c_string_to_string(chunk_ref.content) }
+            .map(|content| {
+                let stream_chunk = StreamChunk::new(
+                    context.stream_id,
+                    content,
+                    context.chunk_index,
+                    chunk_ref.is_final,
+                );
+                context.chunk_index += 1;
+                stream_chunk
+            });
+
+        (context.callback)(result);
+    });
+
+    match delivered {
+        Ok(()) => true,
+        Err(e) => {
+            context.panic = Some(e);
+            false
+        }
+    }
+}
+
+/// State shared with [`arena_callback`] for one [`Zeke::chat_stream_arena`] call
+struct ArenaContext<'a, F> {
+    arena: &'a mut ChunkArena,
+    on_chunk: F,
+    error: Option<Error>,
+}
+
+/// Copy a chunk from the core into the arena and show it to the callback
+///
+/// Returns false to stop the core after a chunk that is not UTF-8 or a
+/// panic; the error is kept in the context for `chat_stream_arena` to return.
+unsafe extern "C" fn arena_callback<F>(
+    chunk: *const ZekeStreamChunk,
+    user_data: *mut std::ffi::c_void,
+) -> bool
+where
+    F: FnMut(&str),
+{
+    if chunk.is_null() || user_data.is_null() {
+        return false;
+    }
+
+    let context = unsafe { &mut *(user_data as *mut ArenaContext<'_, F>) };
+    if context.error.is_some() {
+        return false;
+    }
+    let content = unsafe { (*chunk).content };
+    if content.is_null() {
+        context.error = Some(Error::custom("Received null chunk content"));
+        return false;
+    }
+    let content = unsafe { std::ffi::CStr::from_ptr(content) }.to_bytes();
+
+    let delivered = catch_callback_panic(|| {
+        let text = context.arena.push(content)?;
+        (context.on_chunk)(text);
+        Ok(())
+    });
+
+    match delivered.and_then(|pushed| pushed) {
+        Ok(()) => true,
+        Err(e) => {
+            context.error = Some(e);
+            false
+        }
+    }
+}
+
+/// Run a stream through [`stream_callback`] into a `String` per chunk
+///
+/// What `chat_stream_callback` hands a caller that keeps every chunk.
+#[cfg(feature = "bench")]
+pub fn collect_strings(chunks: &[std::ffi::CString]) -> Result<Vec<String>> {
+    fn drive<F: FnMut(Result<StreamChunk>)>(context: &mut CallbackContext<F>, chunks: &[std::ffi::CString]) {
+        unsafe {
+            crate::ffi_utils::harness::drive_c_stream(
+                Some(stream_callback::<F>),
+                context as *mut _ as *mut std::ffi::c_void,
+                chunks,
+            )
+        };
+    }
+
+    let mut strings = Vec::with_capacity(chunks.len());
+    let mut error = None;
+    let mut context = CallbackContext {
+        callback: |chunk: Result<StreamChunk>| match chunk {
+            Ok(chunk) => strings.push(chunk.content),
+            Err(e) => {
+                error.get_or_insert(e);
+            }
+        },
+        stream_id: Uuid::new_v4(),
+        chunk_index: 0,
+        panic: None,
+    };
+    drive(&mut context, chunks);
+    match context.panic.take().or(error) {
+        Some(e) => Err(e),
+        None => Ok(strings),
+    }
+}
+
+/// Run a stream through [`arena_callback`] into `arena`
+///
+/// What `chat_stream_arena` does with each chunk from the core.
+#[cfg(feature = "bench")]
+pub fn collect_arena(arena: &mut ChunkArena, chunks: &[std::ffi::CString]) -> Result<()> {
+    fn drive<F: FnMut(&str)>(context: &mut ArenaContext<'_, F>, chunks: &[std::ffi::CString]) {
+        unsafe {
+            crate::ffi_utils::harness::drive_c_stream(
+                Some(arena_callback::<F>),
+                context as *mut _ as *mut std::ffi::c_void,
+                chunks,
+            )
+        };
+    }
+
+    let mut context = ArenaContext {
+        arena,
+        on_chunk: |_: &str| {},
+        error: None,
+    };
+    drive(&mut context, chunks);
+    context.error.map_or(Ok(()), Err)
+}
+
 /// Main Zeke client for AI interactions
+///
+/// An instance can be shared between threads, but its calls into the Zig core
+/// run one at a time. Use a [`ZekePool`](crate::ZekePool) to serve requests
+/// in parallel.
 #[derive(Debug)]
 pub struct Zeke {
-    handle: *mut ZekeHandle,
-    config: Config,
-    _string_manager: Arc<Mutex<CStringManager>>, // Keep strings alive
+    session: RwLock<Session>,
+    #[cfg(feature = "async")]
+    backend: Option<Arc<dyn ChatBackend>>,
 }
 
-// Safety: The underlying Zeke handle is thread-safe
-unsafe impl Send for Zeke {}
-unsafe impl Sync for Zeke {}
-
 impl Zeke {
     /// Create a new Zeke instance with the provided configuration
+    ///
+    /// Fails with [`Error::AbiMismatch`] if the Zeke library was built from a
+    /// different `zeke_ffi.h` than these bindings.
     pub fn new(config: Config) -> Result<Self> {
         debug!("Creating new Zeke instance with provider: {}", config.provider);
         
         // Validate configuration
         config.validate()?;
+        crate::library::check_abi()?;
+        let config = Self::without_missing_features(config);
         
-        let mut string_manager = CStringManager::new();
-        
-        // Prepare C strings for FFI
-        let base_url = string_manager.add(&config.effective_base_url())?;
-        let api_key = string_manager.add_optional(config.api_key())?;
-        let model = string_manager.add(&config.model)?;
-        
-        let ffi_config = ZekeConfig {
-            base_url,
-            api_key,
-            provider: config.provider.to_ffi() as i32,
-            model_name: model,
-            temperature: config.temperature,
-            max_tokens: config.max_tokens,
-            stream: config.streaming,
-            enable_gpu: config.enable_gpu,
-            enable_fallback: config.enable_fallback,
-            timeout_ms: config.timeout_ms,
-        };
+        let core = CoreHandle::init(&config)?;
+        info!("Successfully initialized Zeke with provider: {}", config.provider);
 
-        // Initialize Zeke
-        let handle = unsafe { zeke_init(&ffi_config) };
-        
-        if handle.is_null() {
-            let error_msg = unsafe { get_last_error().unwrap_or_else(|| "Unknown initialization error".to_string()) };
-            return Err(Error::initialization(error_msg));
-        }
+        Ok(Self {
+            session: RwLock::new(Session {
+                core: Arc::new(core),
+                config: Arc::new(config),
+            }),
+            #[cfg(feature = "async")]
+            backend: None,
+        })
+    }
 
-        info!("Successfully initialized Zeke with provider: {}", config.provider);
+    /// Create a Zeke instance that serves chat requests through a native backend
+    ///
+    /// No Zig core is initialized, so FFI-only operations such as
+    /// [`switch_provider`](Self::switch_provider) return
+    /// [`Error::ProviderUnavailable`].
+    #[cfg(feature = "async")]
+    pub fn with_backend(config: Config, backend: Arc<dyn ChatBackend>) -> Result<Self> {
+        debug!("Creating Zeke instance with native {} backend", backend.provider());
+
+        config.validate()?;
 
         Ok(Self {
-            handle,
-            config,
-            _string_manager: Arc::new(Mutex::new(string_manager)),
+            session: RwLock::new(Session {
+                core: Arc::new(CoreHandle::none()),
+                config: Arc::new(config),
+            }),
+            backend: Some(backend),
         })
     }
 
+    /// Get the native backend, if this instance uses one
+    #[cfg(feature = "async")]
+    pub fn backend(&self) -> Option<&Arc<dyn ChatBackend>> {
+        self.backend.as_ref()
+    }
+
+    /// Snapshot of the current configuration and core handle
+    fn session(&self) -> Session {
+        self.session.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Get the core handle, failing if this instance has no Zig core
+    ///
+    /// Keep the returned `Arc` alive for as long as the raw handle is used.
+    pub(crate) fn core(&self) -> Result<Arc<CoreHandle>> {
+        self.core_of(&self.session())
+    }
+
+    /// The core handle of a snapshot, failing if this instance has no Zig core
+    fn core_of(&self, session: &Session) -> Result<Arc<CoreHandle>> {
+        if session.core.raw.is_null() {
+            return Err(Error::provider_unavailable(
+                session.config.provider.identifier(),
+                "operation requires the Zig core, but this instance uses a native backend",
+            ));
+        }
+        Ok(session.core.clone())
+    }
+
+    /// Replace the configuration of a running instance
+    ///
+    /// Provider, model, sampling settings and keys all change at once: the Zig
+    /// core is re-initialized with the new configuration and swapped in.
+    /// Requests already in flight finish on the previous core, which is
+    /// destroyed when the last of them completes. If validation or
+    /// initialization fails, the instance is left unchanged.
+    ///
+    /// A native backend keeps its own endpoint and keys, so instances created
+    /// with [`with_backend`](Self::with_backend) only pick up the model and
+    /// sampling settings, and cannot change provider or API keys.
+    pub fn apply_config(&self, config: Config) -> Result<()> {
+        config.validate()?;
+
+        #[cfg(feature = "async")]
+        if let Some(backend) = &self.backend
+            && backend.provider() != config.provider
+        {
+            return Err(Error::ConfigError {
+                message: format!(
+                    "Cannot switch to {}: this instance uses a native {} backend",
+                    config.provider,
+                    backend.provider()
+                ),
+            });
+        }
+
+        let current = self.session();
+
+        #[cfg(feature = "async")]
+        if self.backend.is_some() && !Self::same_keys(&current.config, &config) {
+            return Err(Error::ConfigError {
+                message: "Cannot change API keys: this instance's native backend holds its own; \
+                          build a new backend and instance instead"
+                    .to_string(),
+            });
+        }
+        let (core, config) = if current.core.raw.is_null() {
+            (current.core, config)
+        } else {
+            let config = Self::without_missing_features(config);
+            (Arc::new(CoreHandle::init(&config)?), config)
+        };
+
+        info!(
+            "Applying configuration: {} {} -> {} {}",
+            current.config.provider, current.config.model, config.provider, config.model
+        );
+        *self.session.write().unwrap_or_else(|e| e.into_inner()) = Session {
+            core,
+            config: Arc::new(config),
+        };
+        Ok(())
+    }
+
+    /// Whether two configurations carry the same API keys, in the same order
+    #[cfg(feature = "async")]
+    fn same_keys(a: &Config, b: &Config) -> bool {
+        use secrecy::ExposeSecret;
+
+        let keys = |config: &Config| -> Vec<String> {
+            config
+                .api_key
+                .iter()
+                .chain(&config.api_keys)
+                .map(|key| key.expose_secret().clone())
+                .collect()
+        };
+        keys(a) == keys(b)
+    }
+
+    /// Turn off features the loaded core library cannot provide
+    ///
+    /// A library without GhostLLM (see [`LibraryFeatures`](crate::LibraryFeatures))
+    /// runs with GPU acceleration disabled rather than failing to start.
+    fn without_missing_features(mut config: Config) -> Config {
+        if config.enable_gpu && !crate::LibraryFeatures::detect().ghostllm {
+            warn!("Zeke library has no GhostLLM support; disabling GPU acceleration");
+            config.enable_gpu = false;
+        }
+        config
+    }
+
+    /// Map a provider reported by the core back onto a [`Provider`]
+    ///
+    /// The core drives custom providers through its OpenAI client, so while one
+    /// is active its reports are attributed to it rather than to OpenAI.
+    fn provider_from_ffi(config: &Config, raw: i32) -> Option<Provider> {
+        let provider = Provider::from_ffi_int(raw)?;
+        match config.provider {
+            custom @ Provider::Custom(_) if provider == Provider::OpenAI => Some(custom),
+            _ => Some(provider),
+        }
+    }
+
     /// Create a configuration builder for easy setup
     pub fn builder() -> crate::ConfigBuilder {
         Config::builder()
@@ -76,61 +480,145 @@ impl Zeke {
 
     /// Send a chat message and get a response
     pub async fn chat(&self, message: &str) -> Result<ChatResponse> {
+        self.chat_as(message, None).await
+    }
+
+    /// Send a chat message, authenticated with `token` if one is given
+    ///
+    /// On the core the token is set under the same lock as the chat, so a
+    /// concurrent caller cannot swap it in between; a native backend gets it
+    /// with the request.
+    async fn chat_as(&self, message: &str, token: Option<secrecy::Secret<String>>) -> Result<ChatResponse> {
         let start_time = Instant::now();
         debug!("Sending chat message with {} characters", message.len());
         trace!("Message content: {}", message);
 
-        // Create C string for the message
-        let message_cstr = CStringHolder::new(message)?;
-        let mut response = unsafe { std::mem::zeroed::<ZekeResponse>() };
-
-        // Make the FFI call
-        let result = unsafe {
-            zeke_chat(self.handle, message_cstr.as_ptr(), &mut response)
-        };
+        let session = self.session();
+        let config = &session.config;
 
-        // Check for errors
-        check_result_with_context(result)?;
-
-        // Convert response
-        let content = unsafe {
-            if response.content.is_null() {
-                return Err(Error::custom("Received null response content"));
+        #[cfg(feature = "async")]
+        if let Some(backend) = &self.backend {
+            let mut request = ChatRequest::from_config(config, message);
+            if let Some(token) = token {
+                request = request.access_token(token);
             }
-            let content = crate::ffi_utils::c_string_to_string(response.content)?;
-            
-            // Free the FFI response memory
-            zeke_free_response(&mut response);
-            
-            content
-        };
+            return backend.chat(request).await;
+        }
 
-        let response_time = start_time.elapsed();
-        let provider_used = Provider::from_ffi(
-            unsafe { std::mem::transmute(response.provider_used) }
-        ).unwrap_or(self.config.provider);
+        let core = self.core_of(&session)?;
+        let core_config = Arc::clone(config);
+        let message = message.to_owned();
+        let token = token
+            .map(|token| CStringHolder::new(secrecy::ExposeSecret::expose_secret(&token)))
+            .transpose()?;
+        let raw = run_blocking(move || {
+            let handle = core.lock();
+            if let Some(token) = &token {
+                Self::core_set_auth_token(&handle, core_config.provider, token)?;
+            }
+            Self::core_chat(&handle, &core_config, &message, start_time)
+        })
+        .await?;
 
         debug!(
             "Received response from {}: {} characters in {:?}",
-            provider_used,
-            content.len(),
-            response_time
+            raw.provider(),
+            raw.len(),
+            raw.response_time()
         );
 
         // Create metadata
         let metadata = ResponseMetadata {
             streamed: false,
-            temperature: Some(self.config.temperature),
+            temperature: Some(config.temperature),
             ..Default::default()
         };
 
-        Ok(ChatResponse::new(
-            content,
-            provider_used,
-            self.config.model.clone(),
-            Some(response.tokens_used),
-            response_time,
-        ).with_metadata(metadata))
+        Ok(raw.to_response()?.with_metadata(metadata))
+    }
+
+    /// Send a chat message and borrow the response from the core
+    ///
+    /// Unlike [`chat`](Self::chat), the text is not copied: it stays in the
+    /// buffer the Zig core allocated until the [`RawResponse`] is dropped.
+    /// Worth it for outputs of hundreds of KB; native backends return an
+    /// owned buffer instead.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(zeke: &zeke::Zeke) -> zeke::Result<()> {
+    /// let raw = zeke.chat_raw("Generate the parser module").await?;
+    /// std::fs::write("parser.rs", raw.as_bytes())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn chat_raw(&self, message: &str) -> Result<RawResponse> {
+        debug!("Sending raw chat message with {} characters", message.len());
+        let start_time = Instant::now();
+        let session = self.session();
+
+        #[cfg(feature = "async")]
+        if let Some(backend) = &self.backend {
+            let request = ChatRequest::from_config(&session.config, message);
+            return backend.chat(request).await.map(RawResponse::from);
+        }
+
+        let core = self.core_of(&session)?;
+        let message = message.to_owned();
+        run_blocking(move || Self::core_chat(&core.lock(), &session.config, &message, start_time)).await
+    }
+
+    /// Run a chat on the Zig core, leaving the text in the core's buffer
+    ///
+    /// Blocks until the core answers; async callers go through [`run_blocking`].
+    fn core_chat(handle: &CoreGuard<'_>, config: &Config, message: &str, start_time: Instant) -> Result<RawResponse> {
+        // Create C string for the message
+        let message_cstr = CStringHolder::new(message)?;
+        let mut response = unsafe { std::mem::zeroed::<ZekeResponse>() };
+
+        // Make the FFI call
+        let result = unsafe {
+            zeke_call!(zeke_chat(handle.raw(), message_cstr.as_ptr(), &mut response))
+        }?;
+
+        // Check for errors
+        handle.check(result)?;
+
+        let provider_used = Self::provider_from_ffi(config, response.provider_used)
+            .unwrap_or(config.provider);
+
+        // The response now owns the buffer and frees it when dropped
+        unsafe { RawResponse::from_core(response, provider_used, config.model.clone(), start_time.elapsed()) }
+    }
+
+    /// Send many chat messages, several at a time
+    ///
+    /// Each prompt gets its own [`BatchItem`](crate::BatchItem), so failures
+    /// are reported per prompt. Calls into the Zig core still run one at a
+    /// time on a single instance; use [`ZekePool::chat_batch`](crate::ZekePool::chat_batch)
+    /// to run them in parallel. Native backends honor the full concurrency.
+    ///
+    /// ```rust,no_run
+    /// use zeke::{BatchOptions, Zeke};
+    /// use std::time::Duration;
+    ///
+    /// # async fn example(zeke: &Zeke) {
+    /// let options = BatchOptions::new()
+    ///     .with_concurrency(8)
+    ///     .with_timeout(Duration::from_secs(60))
+    ///     .on_progress(|p| eprintln!("{}/{}", p.completed, p.total));
+    ///
+    /// let batch = zeke.chat_batch(["Explain E0382", "Explain E0499"], options).await;
+    /// println!("{} failed, {:.1} prompts/s", batch.summary.failed, batch.summary.throughput());
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn chat_batch<I>(&self, prompts: I, options: crate::BatchOptions) -> crate::BatchResult
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let prompts = prompts.into_iter().map(Into::into).collect();
+        crate::batch::run(prompts, &options, |prompt| async move { self.chat(&prompt).await }).await
     }
 
     /// Send a streaming chat message
@@ -138,90 +626,132 @@ impl Zeke {
     pub async fn chat_stream(
         &self,
         message: &str,
-    ) -> Result<impl futures::Stream<Item = Result<StreamChunk>>> {
+    ) -> Result<crate::backend::ChunkStream> {
         use crate::stream::ZekeStream;
-        ZekeStream::new(self, message).await
+        use futures::StreamExt;
+
+        if let Some(backend) = &self.backend {
+            return backend
+                .chat_stream(ChatRequest::from_config(&self.config(), message))
+                .await;
+        }
+
+        Ok(ZekeStream::new(self, message).await?.boxed())
     }
 
     /// Send a streaming chat message with callback
-    pub fn chat_stream_callback<F>(&self, message: &str, mut callback: F) -> Result<()>
+    ///
+    /// A panic in `callback` stops the stream and is returned as
+    /// [`Error::CallbackPanicked`] instead of unwinding into the core.
+    pub fn chat_stream_callback<F>(&self, message: &str, callback: F) -> Result<()>
     where
         F: FnMut(Result<StreamChunk>) + Send + 'static,
     {
         debug!("Starting streaming chat with {} characters", message.len());
-        
-        let message_cstr = CStringHolder::new(message)?;
-        let stream_id = Uuid::new_v4();
-        let mut chunk_index = 0u32;
 
-        // Create callback context
-        struct CallbackContext<F> {
-            callback: F,
-            stream_id: Uuid,
-            chunk_index: u32,
-        }
+        let core = self.core()?;
+        let handle = core.lock();
+        let message_cstr = CStringHolder::new(message)?;
 
         let mut context = CallbackContext {
             callback,
-            stream_id,
+            stream_id: Uuid::new_v4(),
             chunk_index: 0,
+            panic: None,
         };
 
-        unsafe extern "C" fn stream_callback<F>(
-            chunk: *const ZekeStreamChunk,
-            user_data: *mut std::ffi::c_void,
-        ) where
-            F: FnMut(Result<StreamChunk>),
-        {
-            if chunk.is_null() || user_data.is_null() {
-                return;
-            }
+        let result = unsafe {
+            zeke_call!(zeke_chat_stream(
+                handle.raw(),
+                message_cstr.as_ptr(),
+                Some(stream_callback::<F>),
+                &mut context as *mut _ as *mut std::ffi::c_void,
+            ))
+        }?;
 
-            let context = &mut *(user_data as *mut CallbackContext<F>);
-            let chunk_ref = &*chunk;
+        if let Some(e) = context.panic {
+            warn!("Streaming stopped after callback panic: {}", e);
+            return Err(e);
+        }
+        handle.check(result)?;
+        
+        debug!("Streaming completed with {} chunks", context.chunk_index);
+        Ok(())
+    }
 
-            let result = crate::ffi_utils::c_string_to_string(chunk_ref.content)
-                .map(|content| {
-                    let stream_chunk = StreamChunk::new(
-                        context.stream_id,
-                        content,
-                        context.chunk_index,
-                        chunk_ref.is_final,
-                    );
-                    context.chunk_index += 1;
-                    stream_chunk
-                });
+    /// Send a streaming chat message, collecting the chunks into `arena`
+    ///
+    /// Each chunk is copied once, onto the end of the arena, and `on_chunk`
+    /// sees it borrowed from there instead of as a fresh `String`. Chunks are
+    /// appended to whatever the arena already holds; reuse one arena across
+    /// calls, with [`ChunkArena::clear`], to keep its allocation. A chunk that
+    /// is not UTF-8, or a panic in `on_chunk`, stops the stream and is returned.
+    ///
+    /// ```rust,no_run
+    /// # fn example(zeke: &zeke::Zeke) -> zeke::Result<()> {
+    /// let mut arena = zeke::ChunkArena::with_capacity(256 * 1024);
+    /// zeke.chat_stream_arena("Generate the parser module", &mut arena, |chunk| print!("{}", chunk))?;
+    /// println!("\n{} bytes in {} chunks", arena.len(), arena.chunk_count());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn chat_stream_arena<F>(&self, message: &str, arena: &mut ChunkArena, on_chunk: F) -> Result<()>
+    where
+        F: FnMut(&str),
+    {
+        debug!("Starting arena streaming chat with {} characters", message.len());
 
-            (context.callback)(result);
-        }
+        let core = self.core()?;
+        let handle = core.lock();
+        let message_cstr = CStringHolder::new(message)?;
+        let first_chunk = arena.chunk_count();
+
+        let mut context = ArenaContext {
+            arena,
+            on_chunk,
+            error: None,
+        };
 
         let result = unsafe {
-            zeke_chat_stream(
-                self.handle,
+            zeke_call!(zeke_chat_stream(
+                handle.raw(),
                 message_cstr.as_ptr(),
-                Some(stream_callback::<F>),
+                Some(arena_callback::<F>),
                 &mut context as *mut _ as *mut std::ffi::c_void,
-            )
-        };
+            ))
+        }?;
 
-        check_result_with_context(result)?;
-        
-        debug!("Streaming completed with {} chunks", context.chunk_index);
+        if let Some(e) = context.error {
+            warn!("Streaming stopped early: {}", e);
+            return Err(e);
+        }
+        handle.check(result)?;
+
+        debug!("Streaming completed with {} chunks", context.arena.chunk_count() - first_chunk);
         Ok(())
     }
 
     /// Switch to a different provider
     pub async fn switch_provider(&mut self, provider: Provider) -> Result<()> {
-        debug!("Switching from {} to {}", self.config.provider, provider);
-
-        let result = unsafe {
-            zeke_switch_provider(self.handle, provider.to_ffi() as i32)
-        };
+        let session = self.session();
+        debug!("Switching from {} to {}", session.config.provider, provider);
 
-        check_result_with_context(result)?;
+        let core = self.core_of(&session)?;
+        let switching = Arc::clone(&core);
+        run_blocking(move || {
+            let handle = switching.lock();
+            let result = unsafe {
+                zeke_call!(zeke_switch_provider(handle.raw(), provider.core_client() as i32))
+            }?;
+            handle.check(result)
+        })
+        .await?;
         
         // Update internal config
-        self.config = self.config.with_provider(provider);
+        *self.session.get_mut().unwrap_or_else(|e| e.into_inner()) = Session {
+            core,
+            config: Arc::new(session.config.with_provider(provider)),
+        };
         
         info!("Successfully switched to provider: {}", provider);
         Ok(())
@@ -229,45 +759,74 @@ impl Zeke {
 
     /// Set authentication token for the current provider
     pub async fn set_auth_token(&self, token: &str) -> Result<()> {
-        debug!("Setting auth token for provider: {}", self.config.provider);
+        let session = self.session();
+        let provider = session.config.provider;
+        debug!("Setting auth token for provider: {}", provider);
 
+        let core = self.core_of(&session)?;
         let token_cstr = CStringHolder::new(token)?;
-        let result = unsafe {
-            zeke_set_auth_token(
-                self.handle,
-                self.config.provider.to_ffi() as i32,
-                token_cstr.as_ptr(),
-            )
-        };
+        run_blocking(move || Self::core_set_auth_token(&core.lock(), provider, &token_cstr)).await?;
 
-        check_result_with_context(result)?;
-        
-        info!("Successfully set auth token for: {}", self.config.provider);
+        info!("Successfully set auth token for: {}", provider);
         Ok(())
     }
 
+    /// Set the auth token on a locked core
+    fn core_set_auth_token(handle: &CoreGuard<'_>, provider: Provider, token: &CStringHolder) -> Result<()> {
+        let result = unsafe {
+            zeke_call!(zeke_set_auth_token(
+                handle.raw(),
+                provider.core_client() as i32,
+                token.as_ptr(),
+            ))
+        }?;
+        handle.check(result)
+    }
+
+    /// Send a chat message authenticated with a managed OAuth token
+    ///
+    /// The token is refreshed before it expires, and once more if the provider
+    /// rejects it. Native backends send it in place of their API keys.
+    #[cfg(feature = "oauth")]
+    pub async fn chat_with_tokens(
+        &self,
+        tokens: &crate::auth::TokenManager,
+        message: &str,
+    ) -> Result<ChatResponse> {
+        tokens
+            .call(|token| self.chat_as(message, Some(token)))
+            .await
+    }
+
     /// Test authentication for the current provider
     pub async fn test_auth(&self) -> Result<bool> {
-        debug!("Testing authentication for: {}", self.config.provider);
+        let session = self.session();
+        debug!("Testing authentication for: {}", session.config.provider);
 
-        let result = unsafe {
-            zeke_test_auth(self.handle, self.config.provider.to_ffi() as i32)
-        };
+        let core = self.core_of(&session)?;
+        let provider = session.config.provider;
+        run_blocking(move || {
+            let handle = core.lock();
+            let result = unsafe {
+                zeke_call!(zeke_test_auth(handle.raw(), provider.core_client() as i32))
+            }?;
 
-        match result {
-            ZekeErrorCode::ZEKE_SUCCESS => {
-                debug!("Authentication test passed");
-                Ok(true)
-            }
-            ZekeErrorCode::ZEKE_AUTHENTICATION_FAILED => {
-                debug!("Authentication test failed");
-                Ok(false)
-            }
-            _ => {
-                check_result_with_context(result)?;
-                Ok(false)
+            match result {
+                ZekeErrorCode::ZEKE_SUCCESS => {
+                    debug!("Authentication test passed");
+                    Ok(true)
+                }
+                ZekeErrorCode::ZEKE_AUTHENTICATION_FAILED => {
+                    debug!("Authentication test failed");
+                    Ok(false)
+                }
+                _ => {
+                    handle.check(result)?;
+                    Ok(false)
+                }
             }
-        }
+        })
+        .await
     }
 
     /// Get status of all providers
@@ -275,99 +834,159 @@ impl Zeke {
         debug!("Getting provider status");
 
         const MAX_PROVIDERS: usize = 10;
-        let mut status_array = vec![unsafe { std::mem::zeroed::<ZekeProviderStatus>() }; MAX_PROVIDERS];
-        let mut actual_count: usize = 0;
 
-        let result = unsafe {
-            zeke_get_provider_status(
-                self.handle,
-                status_array.as_mut_ptr(),
-                MAX_PROVIDERS,
-                &mut actual_count,
-            )
-        };
+        let session = self.session();
+        let core = self.core_of(&session)?;
+        let status_array = run_blocking(move || {
+            let mut status_array =
+                vec![unsafe { std::mem::zeroed::<ZekeProviderStatus>() }; MAX_PROVIDERS];
+            let mut actual_count: usize = 0;
+            let handle = core.lock();
+            let result = unsafe {
+                zeke_call!(zeke_get_provider_status(
+                    handle.raw(),
+                    status_array.as_mut_ptr(),
+                    MAX_PROVIDERS,
+                    &mut actual_count,
+                ))
+            }?;
+            handle.check(result)?;
 
-        check_result_with_context(result)?;
-
-        let mut provider_statuses = Vec::new();
-        for i in 0..actual_count {
-            let ffi_status = &status_array[i];
-            
-            if let Some(provider) = Provider::from_ffi(
-                unsafe { std::mem::transmute(ffi_status.provider) }
-            ) {
-                let status = crate::provider::ProviderStatus {
-                    provider,
-                    is_healthy: ffi_status.is_healthy,
-                    response_time_ms: ffi_status.response_time_ms,
-                    error_rate: ffi_status.error_rate,
-                    requests_per_minute: ffi_status.requests_per_minute,
-                    last_check: std::time::SystemTime::now(),
-                };
-                provider_statuses.push(status);
-            }
-        }
+            status_array.truncate(actual_count);
+            Ok(status_array)
+        })
+        .await?;
+        let provider_statuses = Self::statuses_from_ffi(&session.config, &status_array);
 
         debug!("Retrieved status for {} providers", provider_statuses.len());
         Ok(provider_statuses)
     }
 
+    /// Map the core's status reports onto one entry per provider
+    ///
+    /// While a custom provider is active the core's OpenAI client is serving
+    /// it, so that client's numbers belong to the custom provider and OpenAI
+    /// itself has none. Other registered custom providers are listed without
+    /// health data, like the core lists providers it has not checked.
+    fn statuses_from_ffi(
+        config: &Config,
+        raw: &[ZekeProviderStatus],
+    ) -> Vec<crate::provider::ProviderStatus> {
+        use crate::provider::ProviderStatus;
+
+        let mut statuses = Vec::new();
+        for ffi_status in raw {
+            let (Some(reported), Some(provider)) = (
+                Provider::from_ffi_int(ffi_status.provider),
+                Self::provider_from_ffi(config, ffi_status.provider),
+            ) else {
+                continue;
+            };
+            if provider != reported {
+                statuses.push(ProviderStatus::unknown(reported));
+            }
+            statuses.push(ProviderStatus {
+                provider,
+                is_healthy: ffi_status.is_healthy,
+                response_time_ms: ffi_status.response_time_ms,
+                error_rate: ffi_status.error_rate,
+                requests_per_minute: ffi_status.requests_per_minute,
+                last_check: std::time::SystemTime::now(),
+            });
+        }
+
+        for provider in crate::ProviderRegistry::providers() {
+            if !statuses.iter().any(|status| status.provider == provider) {
+                statuses.push(ProviderStatus::unknown(provider));
+            }
+        }
+        statuses
+    }
+
     /// Perform a health check on the current instance
     pub async fn health_check(&self) -> Result<()> {
         debug!("Performing health check");
 
-        let result = unsafe { zeke_health_check(self.handle) };
-        check_result_with_context(result)?;
-        
+        #[cfg(feature = "async")]
+        if let Some(backend) = &self.backend {
+            return backend.health_check().await;
+        }
+
+        let core = self.core()?;
+        run_blocking(move || {
+            let handle = core.lock();
+            let result = unsafe { zeke_call!(zeke_health_check(handle.raw())) }?;
+            handle.check(result)
+        })
+        .await?;
+
         debug!("Health check passed");
         Ok(())
     }
 
+    /// List the models available from a provider
+    ///
+    /// Uses this instance's base URL and API key for the current provider and
+    /// the provider defaults otherwise. Results are cached on disk; see
+    /// [`ModelDiscovery`](crate::ModelDiscovery) for the fallback order.
+    #[cfg(feature = "async")]
+    pub async fn list_models(&self, provider: Provider) -> Result<crate::ModelList> {
+        let config = self.config();
+        let (base_url, api_key) = if provider == config.provider {
+            (config.effective_base_url(), config.api_key().map(str::to_owned))
+        } else {
+            (provider.default_base_url().to_string(), Config::get_api_key_from_env(provider))
+        };
+
+        Ok(crate::ModelDiscovery::new()
+            .list(provider, &base_url, api_key.as_deref())
+            .await)
+    }
+
     /// Get the current configuration
-    pub fn config(&self) -> &Config {
-        &self.config
+    ///
+    /// This is a snapshot; it does not follow later [`apply_config`](Self::apply_config) calls.
+    pub fn config(&self) -> Arc<Config> {
+        self.session().config
     }
 
     /// Get the current provider
     pub fn current_provider(&self) -> Provider {
-        self.config.provider
+        self.config().provider
     }
 
     /// Get the current model
-    pub fn current_model(&self) -> &str {
-        &self.config.model
+    pub fn current_model(&self) -> String {
+        self.config().model.clone()
     }
 
     /// Check if streaming is enabled
     pub fn streaming_enabled(&self) -> bool {
-        self.config.streaming
+        self.config().streaming
     }
 
     /// Check if GPU acceleration is enabled
     pub fn gpu_enabled(&self) -> bool {
-        self.config.enable_gpu
+        self.config().enable_gpu
     }
 
     /// Get Zeke version
+    ///
+    /// Returns `"unknown"` if the library cannot be loaded.
     pub fn version() -> &'static str {
-        unsafe {
-            let version_ptr = zeke_version();
-            std::ffi::CStr::from_ptr(version_ptr)
-                .to_str()
-                .unwrap_or("unknown")
+        match unsafe { zeke_call!(zeke_version()) } {
+            Ok(version_ptr) if !version_ptr.is_null() => unsafe {
+                std::ffi::CStr::from_ptr(version_ptr)
+                    .to_str()
+                    .unwrap_or("unknown")
+            },
+            _ => "unknown",
         }
     }
-}
 
-impl Drop for Zeke {
-    fn drop(&mut self) {
-        if !self.handle.is_null() {
-            debug!("Destroying Zeke instance");
-            unsafe {
-                zeke_destroy(self.handle);
-            }
-            self.handle = std::ptr::null_mut();
-        }
+    /// Optional library features available at runtime
+    pub fn library_features() -> crate::LibraryFeatures {
+        crate::LibraryFeatures::detect()
     }
 }
 
@@ -412,6 +1031,154 @@ mod tests {
         println!("Zeke version: {}", version);
     }
 
+    fn context<F>(callback: F) -> CallbackContext<F> {
+        CallbackContext {
+            callback,
+            stream_id: Uuid::new_v4(),
+            chunk_index: 0,
+            panic: None,
+        }
+    }
+
+    fn drive<F>(context: &mut CallbackContext<F>, chunks: &[&str]) -> usize
+    where
+        F: FnMut(Result<StreamChunk>),
+    {
+        unsafe {
+            crate::ffi_utils::harness::drive_stream(
+                Some(stream_callback::<F>),
+                context as *mut _ as *mut std::ffi::c_void,
+                chunks,
+            )
+        }
+    }
+
+    #[test]
+    fn test_stream_callback_delivers_chunks() {
+        let mut received = Vec::new();
+        let mut context = context(|chunk: Result<StreamChunk>| received.push(chunk.unwrap()));
+
+        assert_eq!(drive(&mut context, &["Hello", " ", "World"]), 3);
+        assert_eq!(context.chunk_index, 3);
+        assert!(context.panic.is_none());
+        drop(context);
+        assert_eq!(received.len(), 3);
+        assert!(received[2].is_final);
+    }
+
+    #[test]
+    fn test_stream_callback_contains_panic() {
+        let mut calls = 0;
+        let mut context = context(|chunk: Result<StreamChunk>| {
+            calls += 1;
+            if chunk.unwrap().content == "boom" {
+                panic!("callback failed on boom");
+            }
+        });
+
+        // The panic stops the stream at the second chunk
+        assert_eq!(drive(&mut context, &["ok", "boom", "never"]), 2);
+        let err = context.panic.take().unwrap();
+        assert!(matches!(err, Error::CallbackPanicked { ref message } if message == "callback failed on boom"));
+
+        // A stream that keeps going after a panic gets no further callbacks
+        context.panic = Some(err);
+        assert_eq!(drive(&mut context, &["late"]), 1);
+        drop(context);
+        assert_eq!(calls, 2);
+    }
+
+    fn drive_arena<F>(context: &mut ArenaContext<'_, F>, chunks: &[&str]) -> usize
+    where
+        F: FnMut(&str),
+    {
+        unsafe {
+            crate::ffi_utils::harness::drive_stream(
+                Some(arena_callback::<F>),
+                context as *mut _ as *mut std::ffi::c_void,
+                chunks,
+            )
+        }
+    }
+
+    #[test]
+    fn test_arena_callback_collects_chunks() {
+        let mut arena = ChunkArena::new();
+        let mut seen = Vec::new();
+        let mut context = ArenaContext {
+            arena: &mut arena,
+            on_chunk: |chunk: &str| seen.push(chunk.len()),
+            error: None,
+        };
+
+        assert_eq!(drive_arena(&mut context, &["Hello", " ", "World"]), 3);
+        assert!(context.error.is_none());
+        drop(context);
+        assert_eq!(seen, [5, 1, 5]);
+        assert_eq!(arena.as_str(), "Hello World");
+        assert_eq!(arena.chunk_count(), 3);
+    }
+
+    #[test]
+    fn test_arena_callback_contains_panic() {
+        let mut arena = ChunkArena::new();
+        let mut context = ArenaContext {
+            arena: &mut arena,
+            on_chunk: |chunk: &str| assert_ne!(chunk, "boom", "callback failed on boom"),
+            error: None,
+        };
+
+        assert_eq!(drive_arena(&mut context, &["ok", "boom", "never"]), 2);
+        assert!(matches!(context.error, Some(Error::CallbackPanicked { .. })));
+        assert_eq!(drive_arena(&mut context, &["late"]), 1);
+        drop(context);
+        // The chunk is stored before the callback sees it
+        assert_eq!(arena.as_str(), "okboom");
+    }
+
+    #[test]
+    fn test_custom_provider_status_is_separate() {
+        let custom = crate::ProviderRegistry::register(crate::CustomProvider::new(
+            "status-vllm",
+            "http://localhost:8000/v1",
+        ))
+        .unwrap();
+        let other = crate::ProviderRegistry::register(crate::CustomProvider::new(
+            "status-lmstudio",
+            "http://localhost:1234/v1",
+        ))
+        .unwrap();
+        let raw = |provider: ZekeProvider, is_healthy| ZekeProviderStatus {
+            provider: provider as i32,
+            is_healthy,
+            response_time_ms: 120,
+            error_rate: 0.0,
+            requests_per_minute: 0,
+        };
+        let report = [
+            raw(ZekeProvider::ZEKE_PROVIDER_CLAUDE, false),
+            raw(ZekeProvider::ZEKE_PROVIDER_OPENAI, true),
+        ];
+        let status_of = |statuses: &[crate::provider::ProviderStatus], provider| {
+            let matching: Vec<_> = statuses.iter().filter(|s| s.provider == provider).collect();
+            assert_eq!(matching.len(), 1, "one status for {}", provider);
+            matching[0].is_healthy
+        };
+
+        let config = Config { provider: custom, ..Config::default() };
+        let statuses = Zeke::statuses_from_ffi(&config, &report);
+        assert!(status_of(&statuses, custom));
+        assert!(!status_of(&statuses, Provider::OpenAI));
+        assert!(!status_of(&statuses, other));
+
+        let statuses = Zeke::statuses_from_ffi(&Config::default(), &report);
+        assert!(status_of(&statuses, Provider::OpenAI));
+        assert!(!status_of(&statuses, custom));
+
+        crate::ProviderRegistry::unregister("status-vllm");
+        crate::ProviderRegistry::unregister("status-lmstudio");
+    }
+
     #[tokio::test]
     async fn test_provider_switching() {
         let config = test_config();
@@ -424,4 +1191,151 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "async")]
+    #[derive(Debug)]
+    struct EchoBackend;
+
+    #[cfg(feature = "async")]
+    impl ChatBackend for EchoBackend {
+        fn provider(&self) -> Provider {
+            Provider::Ollama
+        }
+
+        fn chat(
+            &self,
+            request: ChatRequest,
+        ) -> futures::future::BoxFuture<'_, Result<ChatResponse>> {
+            Box::pin(async move {
+                Ok(crate::response::ResponseBuilder::new()
+                    .content(format!("{:?}", request.temperature))
+                    .provider(Provider::Ollama)
+                    .model(request.model)
+                    .build())
+            })
+        }
+
+        fn chat_stream(
+            &self,
+            _request: ChatRequest,
+        ) -> futures::future::BoxFuture<'_, Result<crate::backend::ChunkStream>> {
+            Box::pin(async { Err(Error::streaming("not supported")) })
+        }
+    }
+
+    /// Backend answering with the access token it was sent
+    #[cfg(feature = "oauth")]
+    #[derive(Debug)]
+    struct TokenEchoBackend;
+
+    #[cfg(feature = "oauth")]
+    impl ChatBackend for TokenEchoBackend {
+        fn provider(&self) -> Provider {
+            Provider::Claude
+        }
+
+        fn chat(&self, request: ChatRequest) -> futures::future::BoxFuture<'_, Result<ChatResponse>> {
+            use secrecy::ExposeSecret;
+
+            Box::pin(async move {
+                let token = request.access_token.as_ref().map(|t| t.secret().expose_secret().clone());
+                Ok(crate::response::ResponseBuilder::new()
+                    .content(token.unwrap_or_default())
+                    .provider(Provider::Claude)
+                    .model(request.model)
+                    .build())
+            })
+        }
+
+        fn chat_stream(
+            &self,
+            _request: ChatRequest,
+        ) -> futures::future::BoxFuture<'_, Result<crate::backend::ChunkStream>> {
+            Box::pin(async { Err(Error::streaming("not supported")) })
+        }
+    }
+
+    #[cfg(feature = "oauth")]
+    #[tokio::test]
+    async fn test_chat_with_tokens_on_backend() {
+        use crate::auth::{OAuthClient, OAuthProvider, OAuthTokens, TokenManager};
+        use secrecy::Secret;
+
+        let tokens = OAuthTokens {
+            access_token: Secret::new("oauth-access".to_string()),
+            refresh_token: None,
+            token_type: "Bearer".to_string(),
+            expires_at: Some(std::time::SystemTime::now() + Duration::from_secs(3600)),
+            scope: None,
+        };
+        let manager = TokenManager::new(OAuthClient::new(OAuthProvider::anthropic()).unwrap(), tokens);
+        let config = Config::builder()
+            .provider(Provider::Claude)
+            .api_key("sk-ant-unused")
+            .build()
+            .unwrap();
+        let zeke = Zeke::with_backend(config, Arc::new(TokenEchoBackend)).unwrap();
+
+        let response = zeke.chat_with_tokens(&manager, "hi").await.unwrap();
+        assert_eq!(response.content, "oauth-access");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_apply_config() {
+        let config = |model: &str, temperature: f32| {
+            Config::builder()
+                .provider(Provider::Ollama)
+                .model(model)
+                .temperature(temperature)
+                .build()
+                .unwrap()
+        };
+        let zeke = Zeke::with_backend(config("llama3", 0.5), Arc::new(EchoBackend)).unwrap();
+        let before = zeke.config();
+
+        zeke.apply_config(config("qwen2.5-coder", 0.2)).unwrap();
+        assert_eq!(zeke.current_model(), "qwen2.5-coder");
+        assert_eq!(before.model, "llama3");
+
+        let response = zeke.chat("hi").await.unwrap();
+        assert_eq!(response.model, "qwen2.5-coder");
+        assert_eq!(response.content, "Some(0.2)");
+
+        // Invalid or unsupported configurations leave the instance untouched
+        let mut invalid = config("llama3", 0.5);
+        invalid.temperature = 5.0;
+        assert!(zeke.apply_config(invalid).is_err());
+
+        let mut other_provider = config("llama3", 0.5);
+        other_provider.provider = Provider::Claude;
+        assert!(zeke.apply_config(other_provider).is_err());
+        assert_eq!(zeke.current_model(), "qwen2.5-coder");
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_apply_config_rejects_new_keys() {
+        use secrecy::ExposeSecret;
+
+        let config = |key: &str| {
+            Config::builder()
+                .provider(Provider::Ollama)
+                .api_key(key)
+                .build()
+                .unwrap()
+        };
+        let zeke = Zeke::with_backend(config("key-1"), Arc::new(EchoBackend)).unwrap();
+
+        // The backend would keep sending the old key, so the swap is refused
+        let err = zeke.apply_config(config("key-2")).unwrap_err();
+        assert!(matches!(err, Error::ConfigError { .. }));
+        let current = zeke.config();
+        assert_eq!(current.api_key.as_ref().map(|k| k.expose_secret().as_str()), Some("key-1"));
+
+        let mut same_keys = config("key-1");
+        same_keys.model = "qwen2.5-coder".to_string();
+        zeke.apply_config(same_keys).unwrap();
+        assert_eq!(zeke.current_model(), "qwen2.5-coder");
+    }
+}
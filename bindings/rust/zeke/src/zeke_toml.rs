@@ -0,0 +1,528 @@
+//! The multi-provider `zeke.toml` layout
+//!
+//! The Zig CLI reads a `zeke.toml` with a `[default]` section, one
+//! `[providers.<name>]` section per provider and a `[features]` section.
+//! [`ZekeToml`] reads that layout, builds a [`Config`] for any provider section
+//! and writes the file back with comments, formatting and unknown keys intact.
+
+use crate::{keys::KeySelection, Config, Error, Provider, Result};
+use secrecy::Secret;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+/// The `[default]` section
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DefaultSection {
+    /// Provider used when none is specified
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model used with the default provider
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// A `[providers.<name>]` section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderSection {
+    /// Whether the provider may be used
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Model to use (`default_model` is accepted too)
+    #[serde(default, alias = "default_model")]
+    pub model: Option<String>,
+    /// API endpoint (`endpoint` and `base_url` are accepted too)
+    #[serde(default, alias = "endpoint", alias = "base_url")]
+    pub host: Option<String>,
+    /// API key; prefer the environment or the credential store
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Further API keys rotated alongside `api_key`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_keys: Vec<String>,
+    /// How pooled API keys are picked (`round-robin` or `least-used`)
+    #[serde(default)]
+    pub key_selection: Option<KeySelection>,
+    /// Name of a stored credential holding the API key
+    #[serde(default)]
+    pub credential: Option<String>,
+    /// Sampling temperature
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Maximum tokens to generate
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// How the CLI authenticates (`oauth`, `api_key`, ...)
+    #[serde(default)]
+    pub auth_method: Option<String>,
+    /// Keys this crate does not interpret
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, toml::Value>,
+}
+
+impl Default for ProviderSection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            model: None,
+            host: None,
+            api_key: None,
+            api_keys: Vec::new(),
+            key_selection: None,
+            credential: None,
+            temperature: None,
+            max_tokens: None,
+            auth_method: None,
+            extra: BTreeMap::new(),
+        }
+    }
+}
+
+/// The `[features]` section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeatureSection {
+    /// Stream responses
+    #[serde(default)]
+    pub streaming: bool,
+    /// Allow reading and writing files
+    #[serde(default = "default_true")]
+    pub file_operations: bool,
+    /// Allow web searches
+    #[serde(default)]
+    pub web_search: bool,
+    /// Ask before destructive operations
+    #[serde(default = "default_true")]
+    pub tool_confirmation: bool,
+    /// Save conversation history
+    #[serde(default = "default_true")]
+    pub auto_save: bool,
+    /// Keys this crate does not interpret
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, toml::Value>,
+}
+
+impl Default for FeatureSection {
+    fn default() -> Self {
+        Self {
+            streaming: false,
+            file_operations: true,
+            web_search: false,
+            tool_confirmation: true,
+            auto_save: true,
+            extra: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A `zeke.toml` document in the CLI's multi-provider layout
+#[derive(Debug, Clone, Default)]
+pub struct ZekeToml {
+    doc: DocumentMut,
+}
+
+impl ZekeToml {
+    /// Parse a `zeke.toml` document
+    pub fn parse(content: &str) -> Result<Self> {
+        let doc = content.parse::<DocumentMut>().map_err(|e| Error::ConfigError {
+            message: format!("Failed to parse zeke.toml: {}", e),
+        })?;
+        let toml = Self { doc };
+
+        // Surface type errors in known sections now rather than on first use
+        toml.defaults()?;
+        toml.features()?;
+        for name in toml.section_names() {
+            toml.view::<ProviderSection>(&["providers", &name])?;
+        }
+
+        Ok(toml)
+    }
+
+    /// Load a `zeke.toml` file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| Error::ConfigError {
+            message: format!("Failed to read {}: {}", path.display(), e),
+        })?;
+        Self::parse(&content)
+    }
+
+    /// Write the document back, keeping comments and unknown keys
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.doc.to_string()).map_err(|e| Error::ConfigError {
+            message: format!("Failed to write {}: {}", path.display(), e),
+        })
+    }
+
+    /// Check whether TOML content looks like this layout rather than a flat [`Config`]
+    pub fn is_layout(content: &str) -> bool {
+        content.parse::<DocumentMut>().is_ok_and(|doc| {
+            !doc.contains_key("provider")
+                && (doc.contains_table("default") || doc.contains_table("providers"))
+        })
+    }
+
+    /// The underlying document, for keys without a typed accessor
+    pub fn document(&self) -> &DocumentMut {
+        &self.doc
+    }
+
+    /// Mutable access to the underlying document
+    pub fn document_mut(&mut self) -> &mut DocumentMut {
+        &mut self.doc
+    }
+
+    /// The `[default]` section
+    pub fn defaults(&self) -> Result<DefaultSection> {
+        Ok(self.view(&["default"])?.unwrap_or_default())
+    }
+
+    /// The `[features]` section
+    pub fn features(&self) -> Result<FeatureSection> {
+        Ok(self.view(&["features"])?.unwrap_or_default())
+    }
+
+    /// The default provider, from `[default] provider` or `[providers] default`
+    pub fn default_provider(&self) -> Result<Option<Provider>> {
+        let name = self.defaults()?.provider.or_else(|| {
+            self.doc
+                .get("providers")
+                .and_then(|p| p.get("default"))
+                .and_then(Item::as_str)
+                .map(str::to_string)
+        });
+
+        name.map(|name| parse_provider(&name)).transpose()
+    }
+
+    /// Providers that have a section, in file order
+    pub fn providers(&self) -> Result<Vec<Provider>> {
+        self.section_names()
+            .iter()
+            .map(|name| parse_provider(name))
+            .collect()
+    }
+
+    /// The section of a provider, if the file has one
+    pub fn provider(&self, provider: Provider) -> Result<Option<ProviderSection>> {
+        match self.section_key(provider) {
+            Some(key) => self.view(&["providers", &key]),
+            None => Ok(None),
+        }
+    }
+
+    /// Build a [`Config`] for the default provider
+    pub fn to_config(&self) -> Result<Config> {
+        let provider = self.default_provider()?.ok_or_else(|| Error::ConfigError {
+            message: "zeke.toml does not name a default provider".to_string(),
+        })?;
+        self.to_config_for(provider)
+    }
+
+    /// Build a [`Config`] from a provider's section
+    ///
+    /// Keys this crate does not interpret end up in
+    /// [`Config::provider_settings`].
+    pub fn to_config_for(&self, provider: Provider) -> Result<Config> {
+        let section = self.provider(provider)?.unwrap_or_default();
+        if !section.enabled {
+            return Err(Error::ConfigError {
+                message: format!("Provider '{}' is disabled in zeke.toml", provider.identifier()),
+            });
+        }
+
+        let defaults = self.defaults()?;
+        let default_model = defaults
+            .model
+            .filter(|_| self.default_provider().ok().flatten() == Some(provider));
+
+        let mut config = Config {
+            provider,
+            base_url: section.host,
+            api_key: section.api_key.map(Secret::new),
+            api_keys: section.api_keys.into_iter().map(Secret::new).collect(),
+            key_selection: section.key_selection.unwrap_or_default(),
+            credential: section.credential,
+            model: default_model
+                .or(section.model)
+                .unwrap_or_else(|| provider.default_model().to_string()),
+            streaming: self.features()?.streaming,
+            ..Config::default()
+        };
+        if let Some(temperature) = section.temperature {
+            config.temperature = temperature;
+        }
+        if let Some(max_tokens) = section.max_tokens {
+            config.max_tokens = max_tokens;
+        }
+        for (key, value) in section.extra {
+            config.set_provider_setting(&key, value)?;
+        }
+        if let Some(auth_method) = section.auth_method {
+            config.set_provider_setting("auth_method", auth_method)?;
+        }
+
+        config.finish_loading()?;
+        Ok(config)
+    }
+
+    /// Set the default provider, keeping any trailing comment
+    pub fn set_default_provider(&mut self, provider: Provider) {
+        set_value(&mut self.doc, &["default"], "provider", provider.identifier().into());
+    }
+
+    /// Set the model used with the default provider
+    pub fn set_default_model(&mut self, model: &str) {
+        set_value(&mut self.doc, &["default"], "model", model.into());
+    }
+
+    /// Set a key in a provider's section, creating the section if needed
+    pub fn set_provider_value<V: Into<Value>>(&mut self, provider: Provider, key: &str, value: V) {
+        let section = self
+            .section_key(provider)
+            .unwrap_or_else(|| provider.identifier().to_string());
+        set_value(&mut self.doc, &["providers", &section], key, value.into());
+    }
+
+    /// Enable or disable a provider
+    pub fn set_enabled(&mut self, provider: Provider, enabled: bool) {
+        self.set_provider_value(provider, "enabled", enabled);
+    }
+
+    /// Names of the `[providers.<name>]` tables
+    fn section_names(&self) -> Vec<String> {
+        self.doc
+            .get("providers")
+            .and_then(Item::as_table_like)
+            .map(|providers| {
+                providers
+                    .iter()
+                    .filter(|(_, item)| item.is_table_like())
+                    .map(|(name, _)| name.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Section name used for a provider, which may be an alias such as `anthropic`
+    fn section_key(&self, provider: Provider) -> Option<String> {
+        self.section_names()
+            .into_iter()
+            .find(|name| Provider::from_str(name) == Some(provider))
+    }
+
+    /// Deserialize the table at a path
+    fn view<T: DeserializeOwned>(&self, path: &[&str]) -> Result<Option<T>> {
+        let mut item = self.doc.as_item();
+        for key in path {
+            match item.get(key) {
+                Some(next) => item = next,
+                None => return Ok(None),
+            }
+        }
+
+        let table = match item {
+            Item::Table(table) => table.clone(),
+            Item::Value(Value::InlineTable(table)) => table.clone().into_table(),
+            _ => {
+                return Err(Error::ConfigError {
+                    message: format!("zeke.toml: [{}] must be a table", path.join(".")),
+                });
+            }
+        };
+
+        toml_edit::de::from_document(DocumentMut::from(table))
+            .map(Some)
+            .map_err(|e| Error::ConfigError {
+                message: format!("zeke.toml: invalid [{}]: {}", path.join("."), e),
+            })
+    }
+}
+
+impl FromStr for ZekeToml {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for ZekeToml {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.doc)
+    }
+}
+
+fn parse_provider(name: &str) -> Result<Provider> {
+    Provider::from_str(name).ok_or_else(|| Error::ConfigError {
+        message: format!("zeke.toml: unknown provider '{}'", name),
+    })
+}
+
+/// Set `key` in the table at `path`, creating tables as needed and keeping
+/// the decoration (comments, spacing) of a value being replaced
+fn set_value(doc: &mut DocumentMut, path: &[&str], key: &str, mut value: Value) {
+    let mut table = doc.as_table_mut();
+    for name in path {
+        let item = table.entry(name).or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        });
+        if !item.is_table() {
+            *item = Item::Table(Table::new());
+        }
+        table = item.as_table_mut().expect("item was just made a table");
+    }
+    // The innermost table holds keys, so it must be written out
+    table.set_implicit(false);
+
+    if let Some(old) = table.get(key).and_then(Item::as_value) {
+        *value.decor_mut() = old.decor().clone();
+    }
+    table.insert(key, Item::Value(value));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"# Zeke Configuration File
+
+[default]
+provider = "ollama"  # Options: openai, claude, ollama
+model = "qwen2.5-coder:7b"
+
+[providers.openai]
+enabled = true
+model = "gpt-4-turbo"
+# api_key = "sk-..."
+temperature = 0.5
+max_tokens = 4096
+organization = "acme"
+
+[providers.anthropic]
+enabled = false
+model = "claude-3-5-sonnet-20241022"
+
+[providers.ollama]
+enabled = true
+model = "llama3"
+host = "http://gpu-box:11434"  # Docker host
+
+[features]
+streaming = true
+web_search = false
+
+[ui]
+theme = "dark"
+"#;
+
+    #[test]
+    fn test_round_trip_is_lossless() {
+        let toml = ZekeToml::parse(SAMPLE).unwrap();
+        assert_eq!(toml.to_string(), SAMPLE);
+    }
+
+    #[test]
+    fn test_read_sections() {
+        let toml = ZekeToml::parse(SAMPLE).unwrap();
+
+        assert_eq!(toml.default_provider().unwrap(), Some(Provider::Ollama));
+        assert_eq!(
+            toml.providers().unwrap(),
+            vec![Provider::OpenAI, Provider::Claude, Provider::Ollama]
+        );
+        assert!(toml.features().unwrap().streaming);
+
+        let claude = toml.provider(Provider::Claude).unwrap().unwrap();
+        assert!(!claude.enabled);
+        assert!(toml.provider(Provider::XAI).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_to_config() {
+        let toml = ZekeToml::parse(SAMPLE).unwrap();
+
+        // The default provider uses the [default] model
+        let config = toml.to_config().unwrap();
+        assert_eq!(config.provider, Provider::Ollama);
+        assert_eq!(config.model, "qwen2.5-coder:7b");
+        assert_eq!(config.effective_base_url(), "http://gpu-box:11434");
+        assert!(config.streaming);
+
+        let config = toml.to_config_for(Provider::OpenAI).unwrap();
+        assert_eq!(config.model, "gpt-4-turbo");
+        assert_eq!(config.temperature, 0.5);
+        assert_eq!(config.max_tokens, 4096);
+        assert_eq!(
+            config.get_provider_setting::<String>("organization").as_deref(),
+            Some("acme")
+        );
+
+        assert!(toml.to_config_for(Provider::Claude).is_err());
+    }
+
+    #[test]
+    fn test_edits_keep_comments() {
+        let mut toml = ZekeToml::parse(SAMPLE).unwrap();
+        toml.set_default_provider(Provider::OpenAI);
+        toml.set_enabled(Provider::Claude, true);
+        toml.set_provider_value(Provider::XAI, "model", "grok-beta");
+
+        let output = toml.to_string();
+        assert!(output.contains("provider = \"openai\"  # Options: openai, claude, ollama"));
+        assert!(output.contains("# api_key = \"sk-...\""));
+        assert!(output.contains("[providers.anthropic]\nenabled = true"));
+        assert!(output.contains("[providers.xai]\nmodel = \"grok-beta\""));
+        assert!(output.contains("[ui]\ntheme = \"dark\""));
+
+        let reparsed = ZekeToml::parse(&output).unwrap();
+        assert_eq!(reparsed.default_provider().unwrap(), Some(Provider::OpenAI));
+        assert!(reparsed.provider(Provider::Claude).unwrap().unwrap().enabled);
+    }
+
+    #[test]
+    fn test_example_layout() {
+        let toml = ZekeToml::parse(
+            "[providers]\ndefault = \"github_copilot\"\n\n[providers.ollama]\ndefault_model = \"qwen2.5-coder:7b\"\nendpoint = \"http://localhost:11434\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(toml.default_provider().unwrap(), Some(Provider::Copilot));
+        let ollama = toml.provider(Provider::Ollama).unwrap().unwrap();
+        assert_eq!(ollama.model.as_deref(), Some("qwen2.5-coder:7b"));
+        assert_eq!(ollama.host.as_deref(), Some("http://localhost:11434"));
+    }
+
+    #[test]
+    fn test_layout_detection_and_errors() {
+        assert!(ZekeToml::is_layout(SAMPLE));
+        assert!(!ZekeToml::is_layout("provider = \"OpenAI\"\nmodel = \"gpt-4o\"\n"));
+
+        assert!(ZekeToml::parse("[providers.openai]\nmax_tokens = \"lots\"\n").is_err());
+        assert!(ZekeToml::parse("[default]\nprovider = \"nowhere\"\n")
+            .unwrap()
+            .default_provider()
+            .is_err());
+    }
+
+    #[test]
+    fn test_repository_zeke_toml() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../zeke.toml");
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            let toml = ZekeToml::parse(&content).unwrap();
+            assert_eq!(toml.to_string(), content);
+            assert!(toml.to_config_for(Provider::Ollama).is_ok());
+        }
+    }
+}
@@ -1,6 +1,9 @@
 //! Use `zig init --strip` next time to generate a project without comments.
 const std = @import("std");
 
+/// Version reported by the pkg-config file; keep in sync with build.zig.zon
+const ffi_version = "0.3.3";
+
 // Although this function looks imperative, it does not perform the build
 // directly and instead it mutates the build graph (`b`) that will be then
 // executed by an external runner. The functions in `std.Build` implement a DSL
@@ -199,6 +202,45 @@ pub fn build(b: *std.Build) void {
         run_cmd.addArgs(args);
     }
 
+    // The C ABI library behind the Rust bindings (bindings/rust/zeke-sys). It
+    // is static so the Rust build can link it without a runtime search path,
+    // and it bundles compiler_rt because rustc does not link Zig's.
+    const ffi_lib = b.addLibrary(.{
+        .name = "zeke_ffi",
+        .linkage = .static,
+        .root_module = b.createModule(.{
+            .root_source_file = b.path("src/ffi/zeke_ffi.zig"),
+            .target = target,
+            .optimize = optimize,
+            .link_libc = true,
+            .imports = &.{
+                .{ .name = "zeke", .module = mod },
+            },
+        }),
+    });
+    ffi_lib.bundle_compiler_rt = true;
+
+    // `zig build ffi --prefix /usr/local` installs the library together with
+    // its header and a pkg-config file, which zeke-sys finds without Zig
+    const ffi_pc = b.addWriteFiles().add("zeke.pc", b.fmt(
+        \\prefix={s}
+        \\libdir=${{prefix}}/lib
+        \\includedir=${{prefix}}/include
+        \\
+        \\Name: zeke
+        \\Description: C ABI of the Zeke AI development companion
+        \\Version: {s}
+        \\Libs: -L${{libdir}} -lzeke_ffi
+        \\Libs.private: -lpthread -ldl -lm
+        \\Cflags: -I${{includedir}}
+        \\
+    , .{ b.install_prefix, ffi_version }));
+
+    const ffi_step = b.step("ffi", "Build the C ABI library for the Rust bindings");
+    ffi_step.dependOn(&b.addInstallArtifact(ffi_lib, .{}).step);
+    ffi_step.dependOn(&b.addInstallHeaderFile(b.path("src/ffi/zeke_ffi.h"), "zeke_ffi.h").step);
+    ffi_step.dependOn(&b.addInstallFileWithDir(ffi_pc, .lib, "pkgconfig/zeke.pc").step);
+
     // Creates an executable that will run `test` blocks from the provided module.
     // Here `mod` needs to define a target, which is why earlier we made sure to
     // set the releative field.
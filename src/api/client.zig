@@ -52,6 +52,19 @@ pub const RateLimiter = struct {
         try self.requests.append(self.allocator, @intCast(now));
         return true;
     }
+
+    /// Milliseconds until the oldest request leaves the window, 0 if a request is allowed now
+    pub fn retryAfterMs(self: *const Self) u32 {
+        if (self.requests.items.len < self.max_requests) return 0;
+
+        var oldest: u64 = std.math.maxInt(u64);
+        for (self.requests.items) |timestamp| oldest = @min(oldest, timestamp);
+
+        const now: u64 = @intCast(std.time.milliTimestamp());
+        const expires = oldest + self.window_ms;
+        if (expires <= now) return 0;
+        return @intCast(@min(expires - now, std.math.maxInt(u32)));
+    }
 };
 
 pub const ApiClient = struct {
@@ -62,6 +75,10 @@ pub const ApiClient = struct {
     base_url: []const u8,
     provider: ApiProvider,
     rate_limiter: ?*RateLimiter,
+    /// HTTP status of the last failed request, 0 if it never reached the provider
+    last_status: u16 = 0,
+    /// How long to wait before retrying the last failed request, 0 if unknown
+    retry_after_ms: u32 = 0,
 
     const Self = @This();
 
@@ -133,9 +150,13 @@ pub const ApiClient = struct {
     }
 
     pub fn chatCompletion(self: *Self, messages: []const ChatMessage, model: []const u8) !ChatResponse {
+        self.last_status = 0;
+        self.retry_after_ms = 0;
+
         // Check rate limiting
         if (self.rate_limiter) |limiter| {
             if (!try limiter.canMakeRequest()) {
+                self.retry_after_ms = limiter.retryAfterMs();
                 return error.RateLimitExceeded;
             }
         }
@@ -615,6 +636,7 @@ pub const ApiClient = struct {
         // Check response status
         if (result.status != .ok) {
             std.log.err("HTTP request failed with status: {}", .{@intFromEnum(result.status)});
+            self.last_status = @intFromEnum(result.status);
             return self.createMockResponse(model);
         }
 
@@ -5,6 +5,7 @@
 extern "C" {
 #endif
 
+#include <stddef.h>
 #include <stdint.h>
 #include <stdbool.h>
 
@@ -12,6 +13,16 @@ extern "C" {
 // Type Definitions
 // ============================================================================
 
+// ABI revision; bumped whenever a struct, enum or function signature changes
+#define ZEKE_ABI_VERSION 4
+
+// Upper bound on the fields of any structure described by ZekeStructLayout
+#define ZEKE_MAX_STRUCT_FIELDS 16
+
+// Buffer sizes of the strings in ZekeErrorDetail, including the terminator
+#define ZEKE_ERROR_MODEL_LEN 128
+#define ZEKE_ERROR_MESSAGE_LEN 512
+
 // Opaque handles for Rust integration
 typedef struct ZekeHandle ZekeHandle;
 typedef struct ZekeConfigHandle ZekeConfigHandle; 
@@ -33,7 +44,8 @@ typedef enum {
     ZEKE_MEMORY_ERROR = -8,
     ZEKE_INVALID_PARAMETER = -9,
     ZEKE_PROVIDER_UNAVAILABLE = -10,
-    ZEKE_STREAMING_FAILED = -11
+    ZEKE_STREAMING_FAILED = -11,
+    ZEKE_RATE_LIMITED = -12
 } ZekeErrorCode;
 
 // Provider types
@@ -42,7 +54,11 @@ typedef enum {
     ZEKE_PROVIDER_CLAUDE = 1,
     ZEKE_PROVIDER_OPENAI = 2,
     ZEKE_PROVIDER_OLLAMA = 3,
-    ZEKE_PROVIDER_GHOSTLLM = 4
+    ZEKE_PROVIDER_GHOSTLLM = 4,
+    ZEKE_PROVIDER_XAI = 5,
+    ZEKE_PROVIDER_GOOGLE = 6,
+    ZEKE_PROVIDER_AZURE = 7,
+    ZEKE_PROVIDER_OMEN = 8
 } ZekeProvider;
 
 // Configuration structure
@@ -74,7 +90,7 @@ typedef struct {
     const char* content;
     bool is_final;
     uint32_t chunk_index;
-    uint32_t total_chunks;
+    uint32_t total_chunks;  // 0 when the stream length is not known
 } ZekeStreamChunk;
 
 // GPU information
@@ -96,8 +112,37 @@ typedef struct {
     uint32_t requests_per_minute;
 } ZekeProviderStatus;
 
+// Outcome of the most recent call on a handle
+typedef struct {
+    ZekeErrorCode code;                     // ZEKE_SUCCESS if the call succeeded
+    uint16_t http_status;                   // 0 when no HTTP status is known
+    int provider;                           // ZekeProvider the call used
+    uint32_t retry_after_ms;                // 0 when the provider gave no hint
+    char model[ZEKE_ERROR_MODEL_LEN];       // Model the call used; truncated if longer
+    char message[ZEKE_ERROR_MESSAGE_LEN];   // Empty on success; truncated if longer
+} ZekeErrorDetail;
+
+// Structures whose layout the library reports
+typedef enum {
+    ZEKE_STRUCT_CONFIG = 0,
+    ZEKE_STRUCT_RESPONSE = 1,
+    ZEKE_STRUCT_STREAM_CHUNK = 2,
+    ZEKE_STRUCT_GPU_INFO = 3,
+    ZEKE_STRUCT_PROVIDER_STATUS = 4,
+    ZEKE_STRUCT_ERROR_DETAIL = 5
+} ZekeStructId;
+
+// Size, alignment and field offsets of a structure as compiled into the library
+typedef struct {
+    size_t size;
+    size_t alignment;
+    size_t field_count;
+    size_t offsets[ZEKE_MAX_STRUCT_FIELDS]; // In declaration order; unused entries are 0
+} ZekeStructLayout;
+
 // Callback types
-typedef void (*ZekeStreamCallback)(const ZekeStreamChunk* chunk, void* user_data);
+// A stream callback returns false to stop the stream; no further chunks are delivered
+typedef bool (*ZekeStreamCallback)(const ZekeStreamChunk* chunk, void* user_data);
 typedef void (*ZekeAsyncCallback)(const ZekeResponse* response, void* user_data);
 
 // ============================================================================
@@ -123,6 +168,24 @@ void zeke_destroy(ZekeHandle* handle);
  */
 const char* zeke_version(void);
 
+// ============================================================================
+// ABI Compatibility
+// ============================================================================
+
+/**
+ * Get the ABI revision the library was built with
+ * @return ZEKE_ABI_VERSION from the library's copy of this header
+ */
+uint32_t zeke_abi_version(void);
+
+/**
+ * Describe how the library lays out one of the public structures
+ * @param id Structure to describe
+ * @param layout_out Output layout
+ * @return Error code (ZEKE_INVALID_PARAMETER for an unknown structure)
+ */
+ZekeErrorCode zeke_struct_layout(ZekeStructId id, ZekeStructLayout* layout_out);
+
 // ============================================================================
 // Chat and Completion API
 // ============================================================================
@@ -140,7 +203,8 @@ ZekeErrorCode zeke_chat(ZekeHandle* handle, const char* message, ZekeResponse* r
  * Send a streaming chat message with callback for chunks
  * @param handle Zeke instance handle
  * @param message Input message
- * @param callback Callback function for stream chunks
+ * @param callback Callback function for stream chunks; returning false cancels the
+ *        stream, no further chunks are delivered and the call returns ZEKE_SUCCESS
  * @param user_data User data passed to callback
  * @return Error code
  */
@@ -166,7 +230,8 @@ void zeke_free_response(ZekeResponse* response);
  * @param handle Zeke instance handle
  * @param provider Provider type
  * @param token Authentication token
- * @return Error code
+ * @return Error code; ZEKE_INVALID_PARAMETER for GhostLLM, xAI and OMEN,
+ *         which have no credential slot in the core
  */
 ZekeErrorCode zeke_set_auth_token(ZekeHandle* handle, int provider, const char* token);
 
@@ -174,7 +239,8 @@ ZekeErrorCode zeke_set_auth_token(ZekeHandle* handle, int provider, const char*
  * Test authentication for a provider
  * @param handle Zeke instance handle
  * @param provider Provider type
- * @return Error code (ZEKE_SUCCESS if authenticated)
+ * @return Error code (ZEKE_SUCCESS if authenticated); ZEKE_INVALID_PARAMETER
+ *         for GhostLLM, xAI and OMEN, which have no credential slot in the core
  */
 ZekeErrorCode zeke_test_auth(ZekeHandle* handle, int provider);
 
@@ -186,7 +252,8 @@ ZekeErrorCode zeke_test_auth(ZekeHandle* handle, int provider);
  * Switch to a different provider
  * @param handle Zeke instance handle
  * @param provider Provider to switch to
- * @return Error code
+ * @return Error code; ZEKE_INVALID_PARAMETER for GhostLLM and OMEN, which the
+ *         core has no API client for
  */
 ZekeErrorCode zeke_switch_provider(ZekeHandle* handle, int provider);
 
@@ -214,15 +281,15 @@ ZekeErrorCode zeke_get_provider_status(
  * @param handle Zeke instance handle
  * @param base_url GhostLLM server URL
  * @param enable_gpu Whether to enable GPU acceleration
- * @return Error code
+ * @return Error code; ZEKE_PROVIDER_UNAVAILABLE, the core has no GhostLLM client
  */
 ZekeErrorCode zeke_ghostllm_init(ZekeHandle* handle, const char* base_url, bool enable_gpu);
 
 /**
  * Get GPU information from GhostLLM
  * @param handle Zeke instance handle
- * @param gpu_info Output GPU information
- * @return Error code
+ * @param gpu_info Output GPU information, zeroed
+ * @return Error code; ZEKE_PROVIDER_UNAVAILABLE, the core has no GhostLLM client
  */
 ZekeErrorCode zeke_ghostllm_get_gpu_info(ZekeHandle* handle, ZekeGpuInfo* gpu_info);
 
@@ -237,7 +304,7 @@ void zeke_free_gpu_info(ZekeGpuInfo* gpu_info);
  * @param handle Zeke instance handle
  * @param model_name Model to benchmark
  * @param batch_size Batch size for benchmark
- * @return Error code
+ * @return Error code; ZEKE_PROVIDER_UNAVAILABLE, the core has no GhostLLM client
  */
 ZekeErrorCode zeke_ghostllm_benchmark(ZekeHandle* handle, const char* model_name, uint32_t batch_size);
 
@@ -247,11 +314,27 @@ ZekeErrorCode zeke_ghostllm_benchmark(ZekeHandle* handle, const char* model_name
 
 /**
  * Load configuration from file
- * @param config_path Path to configuration file
+ * @param config_path Path to configuration file; `.toml` files are read like the CLI reads zeke.toml
  * @return Configuration handle or NULL on failure
  */
 ZekeConfigHandle* zeke_load_config(const char* config_path);
 
+/**
+ * Create a configuration handle from instance settings
+ * @param config Settings to store; strings are copied
+ * @return Configuration handle or NULL on failure
+ */
+ZekeConfigHandle* zeke_config_from(const ZekeConfig* config);
+
+/**
+ * Read the instance settings of a configuration handle
+ * @param config_handle Configuration handle
+ * @param config_out Receives the settings; unset strings are empty, and all
+ *                   strings stay valid until the handle is freed
+ * @return Error code
+ */
+ZekeErrorCode zeke_config_get(ZekeConfigHandle* config_handle, ZekeConfig* config_out);
+
 /**
  * Save configuration to file
  * @param config_handle Configuration handle
@@ -272,10 +355,20 @@ void zeke_free_config(ZekeConfigHandle* config_handle);
 
 /**
  * Get last error message (thread-local)
+ * Any call on the thread may overwrite it; prefer zeke_get_error_detail for calls on a handle
  * @return Error message string
  */
 const char* zeke_get_last_error(void);
 
+/**
+ * Get the outcome of the most recent call on a handle
+ * Every call taking the handle replaces it, so read it before making another
+ * @param handle Zeke instance handle
+ * @param detail_out Receives the outcome
+ * @return Error code
+ */
+ZekeErrorCode zeke_get_error_detail(const ZekeHandle* handle, ZekeErrorDetail* detail_out);
+
 /**
  * Check if Zeke instance is healthy
  * @param handle Zeke instance handle
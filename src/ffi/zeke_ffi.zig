@@ -3,15 +3,13 @@
 //! to enable seamless integration with Rust projects like GhostFlow and Jarvis
 
 const std = @import("std");
-// Import root with explicit fallback for different Zig versions
-const zeke = @import("../root.zig");
+// The library module is wired in by the `ffi` step in build.zig
+const zeke = @import("zeke");
 
-// Safe imports with compatibility checks
-const ghostllm = if (@hasDecl(zeke, "ghostllm")) zeke.ghostllm else struct {};
-const api = if (@hasDecl(zeke, "api")) zeke.api else struct {};
-const auth = if (@hasDecl(zeke, "auth")) zeke.auth else struct {};
-const config = if (@hasDecl(zeke, "config")) zeke.config else struct {};  
-const streaming = if (@hasDecl(zeke, "streaming")) zeke.streaming else struct {};
+const api = zeke.api;
+const auth = zeke.auth;
+const config = zeke.config;
+const streaming = zeke.streaming;
 
 // C-compatible type definitions
 pub const ZekeHandle = opaque {};
@@ -35,6 +33,7 @@ pub const ZekeErrorCode = enum(c_int) {
     invalid_parameter = -9,
     provider_unavailable = -10,
     streaming_failed = -11,
+    rate_limited = -12,
 };
 
 // Core structures for FFI
@@ -84,42 +83,168 @@ pub const ZekeProviderStatus = extern struct {
     requests_per_minute: u32,
 };
 
+// Keep in sync with ZEKE_ERROR_MODEL_LEN and ZEKE_ERROR_MESSAGE_LEN in zeke_ffi.h
+pub const error_model_len = 128;
+pub const error_message_len = 512;
+
+pub const ZekeErrorDetail = extern struct {
+    code: ZekeErrorCode,
+    http_status: u16,
+    provider: c_int,
+    retry_after_ms: u32,
+    model: [error_model_len]u8,
+    message: [error_message_len]u8,
+};
+
+// ABI revision; keep in sync with ZEKE_ABI_VERSION in zeke_ffi.h
+pub const abi_version: u32 = 4;
+
+// Keep in sync with ZEKE_MAX_STRUCT_FIELDS in zeke_ffi.h
+pub const max_struct_fields = 16;
+
+pub const ZekeStructId = enum(c_int) {
+    config = 0,
+    response = 1,
+    stream_chunk = 2,
+    gpu_info = 3,
+    provider_status = 4,
+    error_detail = 5,
+};
+
+pub const ZekeStructLayout = extern struct {
+    size: usize,
+    alignment: usize,
+    field_count: usize,
+    offsets: [max_struct_fields]usize,
+};
+
 // Callback types for streaming and async operations
-pub const ZekeStreamCallback = ?*const fn(chunk: *const ZekeStreamChunk, user_data: ?*anyopaque) callconv(.C) void;
-pub const ZekeAsyncCallback = ?*const fn(response: *const ZekeResponse, user_data: ?*anyopaque) callconv(.C) void;
+// A stream callback returns false to stop the stream
+pub const ZekeStreamCallback = ?*const fn(chunk: *const ZekeStreamChunk, user_data: ?*anyopaque) callconv(.c) bool;
+pub const ZekeAsyncCallback = ?*const fn(response: *const ZekeResponse, user_data: ?*anyopaque) callconv(.c) void;
 
 // ============================================================================
 // Core Zeke Instance Management
 // ============================================================================
 
+/// State behind a ZekeHandle
+const HandleState = struct {
+    zeke: zeke.Zeke,
+    /// Outcome of the most recent call, reported by zeke_get_error_detail
+    last_call: ZekeErrorDetail,
+    /// Owns the settings zeke_init copied from its ZekeConfig
+    strings: std.heap.ArenaAllocator,
+
+    /// Start a call: forget the previous outcome and return the instance
+    fn begin(self: *HandleState) *zeke.Zeke {
+        self.last_call = std.mem.zeroes(ZekeErrorDetail);
+        self.last_call.code = .success;
+        self.last_call.provider = zekeProvider(self.zeke.current_provider);
+        copyTerminated(&self.last_call.model, self.zeke.current_model);
+        return &self.zeke;
+    }
+
+    /// Record a failed call and return its code
+    fn fail(self: *HandleState, code: ZekeErrorCode, message: []const u8) ZekeErrorCode {
+        self.last_call.code = code;
+        copyTerminated(&self.last_call.message, message);
+        setLastError(message);
+        return code;
+    }
+
+    /// Record a call that failed with `err` and return its code
+    fn failWith(self: *HandleState, err: anyerror, fallback: ZekeErrorCode) ZekeErrorCode {
+        return self.fail(errorCode(err, fallback), @errorName(err));
+    }
+
+    /// Record a failed request, with the status and retry hint the API client saw
+    fn failRequest(self: *HandleState, err: anyerror, fallback: ZekeErrorCode) ZekeErrorCode {
+        self.last_call.http_status = self.zeke.api_client.last_status;
+        self.last_call.retry_after_ms = self.zeke.api_client.retry_after_ms;
+        return self.failWith(err, fallback);
+    }
+};
+
+/// The code for an error raised by the core, or `fallback` if it has none
+///
+/// Takes `anyerror` so callers can pass errors from any core call; the
+/// core's inferred error sets differ from function to function.
+fn errorCode(err: anyerror, fallback: ZekeErrorCode) ZekeErrorCode {
+    return switch (err) {
+        error.InitializationFailed => .initialization_failed,
+        error.AuthenticationFailed => .authentication_failed,
+        error.ConfigLoadFailed => .config_load_failed,
+        error.NetworkError, error.ConnectionRefused, error.ConnectionResetByPeer, error.ConnectionTimedOut => .network_error,
+        error.InvalidModel => .invalid_model,
+        error.TokenExchangeFailed => .token_exchange_failed,
+        error.RateLimitExceeded => .rate_limited,
+        error.UnexpectedResponse => .unexpected_response,
+        error.OutOfMemory => .memory_error,
+        else => fallback,
+    };
+}
+
+fn handleState(handle: *ZekeHandle) *HandleState {
+    return @ptrCast(@alignCast(handle));
+}
+
+/// Copy `source` into `dest`, truncating to leave room for the terminator
+fn copyTerminated(dest: []u8, source: []const u8) void {
+    const len = @min(source.len, dest.len - 1);
+    @memcpy(dest[0..len], source[0..len]);
+    dest[len] = 0;
+}
+
 /// Initialize a new Zeke instance with the given configuration
-export fn zeke_init(config: *const ZekeConfig) ?*ZekeHandle {
+export fn zeke_init(settings: *const ZekeConfig) ?*ZekeHandle {
     const allocator = std.heap.c_allocator;
     
-    const zeke_config = config.Config{
-        .api_base_url = std.mem.span(config.base_url),
-        .default_model = std.mem.span(config.model_name),
-        .temperature = config.temperature,
-        .max_tokens = config.max_tokens,
-        .stream = config.stream,
-        .timeout_ms = config.timeout_ms,
+    const state = allocator.create(HandleState) catch return null;
+    state.* = .{
+        .zeke = zeke.Zeke.init(allocator) catch |err| {
+            setLastError(@errorName(err));
+            allocator.destroy(state);
+            return null;
+        },
+        .last_call = undefined,
+        .strings = std.heap.ArenaAllocator.init(allocator),
     };
-    
-    const zeke_instance = allocator.create(zeke.Zeke) catch return null;
-    zeke_instance.* = zeke.Zeke.init(allocator, zeke_config) catch |err| {
-        allocator.destroy(zeke_instance);
+    applyInstanceSettings(state, settings) catch |err| {
+        setLastError(@errorName(err));
+        zeke_destroy(@ptrCast(state));
         return null;
     };
+    _ = state.begin();
+    
+    return @ptrCast(state);
+}
+
+/// Apply the settings of a ZekeConfig on top of the loaded configuration
+fn applyInstanceSettings(state: *HandleState, settings: *const ZekeConfig) !void {
+    const instance = &state.zeke;
+    const arena = state.strings.allocator();
+    
+    const provider = apiProvider(settings.provider) orelse return error.InvalidParameter;
+    try instance.switchToProvider(provider);
+    
+    const model = std.mem.span(settings.model_name);
+    if (model.len > 0) instance.current_model = try arena.dupe(u8, model);
+    const base_url = std.mem.span(settings.base_url);
+    if (base_url.len > 0) instance.api_client.base_url = try arena.dupe(u8, base_url);
+    const api_key = std.mem.span(settings.api_key);
+    if (api_key.len > 0) try instance.api_client.setAuth(api_key);
     
-    return @ptrCast(zeke_instance);
+    instance.config.streaming.enabled = settings.stream;
+    instance.config.providers.fallback_enabled = settings.enable_fallback;
+    if (settings.timeout_ms > 0) instance.config.request_timeout_ms = settings.timeout_ms;
 }
 
 /// Clean up and destroy a Zeke instance
 export fn zeke_destroy(handle: *ZekeHandle) void {
-    const zeke_instance = @ptrCast(*zeke.Zeke, @alignCast(handle));
-    const allocator = zeke_instance.allocator;
-    zeke_instance.deinit();
-    allocator.destroy(zeke_instance);
+    const state = handleState(handle);
+    state.zeke.deinit();
+    state.strings.deinit();
+    std.heap.c_allocator.destroy(state);
 }
 
 /// Get the version string of Zeke
@@ -127,6 +252,48 @@ export fn zeke_version() [*:0]const u8 {
     return "0.2.0";
 }
 
+// ============================================================================
+// ABI Compatibility
+// ============================================================================
+
+/// Get the ABI revision the library was built with
+export fn zeke_abi_version() u32 {
+    return abi_version;
+}
+
+/// Describe how the library lays out one of the public structures
+export fn zeke_struct_layout(id: c_int, layout_out: *ZekeStructLayout) ZekeErrorCode {
+    const struct_id = std.meta.intToEnum(ZekeStructId, id) catch {
+        setLastError("Unknown structure id");
+        return .invalid_parameter;
+    };
+    layout_out.* = switch (struct_id) {
+        .config => layoutOf(ZekeConfig),
+        .response => layoutOf(ZekeResponse),
+        .stream_chunk => layoutOf(ZekeStreamChunk),
+        .gpu_info => layoutOf(ZekeGpuInfo),
+        .provider_status => layoutOf(ZekeProviderStatus),
+        .error_detail => layoutOf(ZekeErrorDetail),
+    };
+    return .success;
+}
+
+fn layoutOf(comptime T: type) ZekeStructLayout {
+    const fields = @typeInfo(T).@"struct".fields;
+    comptime std.debug.assert(fields.len <= max_struct_fields);
+
+    var layout = ZekeStructLayout{
+        .size = @sizeOf(T),
+        .alignment = @alignOf(T),
+        .field_count = fields.len,
+        .offsets = [_]usize{0} ** max_struct_fields,
+    };
+    inline for (fields, 0..) |field, i| {
+        layout.offsets[i] = @offsetOf(T, field.name);
+    }
+    return layout;
+}
+
 // ============================================================================
 // Chat and Completion API
 // ============================================================================
@@ -137,46 +304,44 @@ export fn zeke_chat(
     message: [*:0]const u8,
     response_out: *ZekeResponse,
 ) ZekeErrorCode {
-    const zeke_instance = @ptrCast(*zeke.Zeke, @alignCast(handle));
+    const state = handleState(handle);
+    const zeke_instance = state.begin();
     const allocator = zeke_instance.allocator;
     
     const message_str = std.mem.span(message);
+    const start_ms = std.time.milliTimestamp();
     
-    const response = zeke_instance.chat(message_str) catch |err| {
+    var response = zeke_instance.chatWithUsage(message_str) catch |err| {
+        const code = state.failRequest(err, .unexpected_response);
         response_out.* = .{
             .content = "",
-            .provider_used = @intFromEnum(zeke_instance.current_provider),
+            .provider_used = zekeProvider(zeke_instance.current_provider),
             .tokens_used = 0,
             .response_time_ms = 0,
-            .error_code = switch (err) {
-                error.NetworkError => .network_error,
-                error.AuthenticationFailed => .authentication_failed,
-                error.InvalidModel => .invalid_model,
-                else => .unexpected_response,
-            },
+            .error_code = code,
             .error_message = @errorName(err).ptr,
         };
-        return response_out.error_code;
+        return code;
     };
+    defer response.deinit(allocator);
     
-    // Allocate and copy response string
-    const response_cstr = allocator.dupeZ(u8, response) catch {
-        allocator.free(response);
+    // Copy into a terminated buffer that zeke_free_response releases
+    const response_cstr = allocator.dupeZ(u8, response.content) catch {
         response_out.error_code = .memory_error;
         response_out.error_message = "Memory allocation failed";
-        return .memory_error;
+        return state.fail(.memory_error, "Memory allocation failed");
     };
     
+    const elapsed_ms = std.time.milliTimestamp() - start_ms;
     response_out.* = .{
         .content = response_cstr.ptr,
-        .provider_used = @intFromEnum(zeke_instance.current_provider),
-        .tokens_used = 0, // TODO: Get actual token count
-        .response_time_ms = 0, // TODO: Measure response time
+        .provider_used = zekeProvider(zeke_instance.current_provider),
+        .tokens_used = if (response.usage) |usage| usage.total_tokens else 0,
+        .response_time_ms = @intCast(std.math.clamp(elapsed_ms, 0, std.math.maxInt(u32))),
         .error_code = .success,
         .error_message = "",
     };
     
-    allocator.free(response);
     return .success;
 }
 
@@ -187,52 +352,142 @@ export fn zeke_chat_stream(
     callback: ZekeStreamCallback,
     user_data: ?*anyopaque,
 ) ZekeErrorCode {
-    const zeke_instance = @ptrCast(*zeke.Zeke, @alignCast(handle));
+    const state = handleState(handle);
+    const zeke_instance = state.begin();
     const message_str = std.mem.span(message);
     
-    if (callback == null) return .invalid_parameter;
+    const callback_fn = callback orelse return state.fail(.invalid_parameter, "callback is null");
     
-    const StreamHandler = struct {
-        cb: ZekeStreamCallback,
-        data: ?*anyopaque,
-        
-        fn handleChunk(self: @This(), chunk: streaming.StreamChunk) void {
-            if (self.cb) |callback_fn| {
-                const ffi_chunk = ZekeStreamChunk{
-                    .content = chunk.content.ptr,
-                    .is_final = chunk.is_final,
-                    .chunk_index = chunk.chunk_index,
-                    .total_chunks = chunk.total_chunks,
-                };
-                callback_fn(&ffi_chunk, self.data);
-            }
-        }
+    var handler = StreamHandler{
+        .zeke = zeke_instance,
+        .callback = callback_fn,
+        .user_data = user_data,
     };
+    const previous = StreamHandler.active;
+    StreamHandler.active = &handler;
+    defer StreamHandler.active = previous;
     
-    const handler = StreamHandler{ .cb = callback, .data = user_data };
-    
-    zeke_instance.streamChat(message_str, handler.handleChunk) catch |err| {
-        return switch (err) {
-            error.NetworkError => .network_error,
-            error.AuthenticationFailed => .authentication_failed,
-            error.InvalidModel => .invalid_model,
-            else => .streaming_failed,
-        };
+    zeke_instance.streamChat(message_str, StreamHandler.forward) catch |err| {
+        return state.failWith(err, .streaming_failed);
     };
+    if (handler.failed) |err| return state.failWith(err, .streaming_failed);
     
     return .success;
 }
 
+/// Forwards chunks from the core's stream to a ZekeStreamCallback
+///
+/// The core's StreamCallback is a bare function without a context pointer,
+/// so the handler of the stream running on this thread is kept in `active`.
+const StreamHandler = struct {
+    zeke: *zeke.Zeke,
+    callback: *const fn (chunk: *const ZekeStreamChunk, user_data: ?*anyopaque) callconv(.c) bool,
+    user_data: ?*anyopaque,
+    chunk_index: u32 = 0,
+    stopped: bool = false,
+    failed: ?anyerror = null,
+    
+    threadlocal var active: ?*StreamHandler = null;
+    
+    fn forward(chunk: streaming.StreamChunk) void {
+        const self = active orelse return;
+        const allocator = self.zeke.allocator;
+        // Chunks are handed over to the callback, which owns their content
+        defer allocator.free(chunk.content);
+        // Already cancelled; the core may have had this chunk in flight
+        if (self.stopped) return;
+        
+        const content = allocator.dupeZ(u8, chunk.content) catch |err| {
+            self.failed = err;
+            self.stop();
+            return;
+        };
+        defer allocator.free(content);
+        
+        const ffi_chunk = ZekeStreamChunk{
+            .content = content.ptr,
+            .is_final = chunk.is_final,
+            .chunk_index = self.chunk_index,
+            .total_chunks = 0,
+        };
+        self.chunk_index += 1;
+        // The consumer asked to stop; cancel the rest of the stream
+        if (!self.callback(&ffi_chunk, self.user_data)) self.stop();
+    }
+    
+    fn stop(self: *StreamHandler) void {
+        self.stopped = true;
+        self.zeke.cancelStream();
+    }
+};
+
 /// Free memory allocated for a ZekeResponse
 export fn zeke_free_response(response: *ZekeResponse) void {
-    if (response.content != null and response.content.len > 0) {
-        const allocator = std.heap.c_allocator;
-        const content_slice = std.mem.span(response.content);
-        allocator.free(content_slice);
+    // Failed calls leave a static empty string behind
+    const content = std.mem.span(response.content);
+    if (content.len > 0) {
+        std.heap.c_allocator.free(content);
         response.content = "";
     }
 }
 
+// ============================================================================
+// Provider Mapping
+// ============================================================================
+
+// ZekeProvider values, as declared in zeke_ffi.h
+const provider_copilot = 0;
+const provider_claude = 1;
+const provider_openai = 2;
+const provider_ollama = 3;
+const provider_ghostllm = 4;
+const provider_xai = 5;
+const provider_google = 6;
+const provider_azure = 7;
+const provider_omen = 8;
+
+/// The credential slot the core keeps for a ZekeProvider
+///
+/// GhostLLM, xAI and OMEN have no slot of their own, so they return null.
+fn authProvider(provider: c_int) ?auth.AuthProvider {
+    return switch (provider) {
+        provider_copilot => .github,
+        provider_claude => .anthropic,
+        provider_openai => .openai,
+        provider_ollama => .local,
+        provider_google => .google,
+        provider_azure => .azure,
+        else => null,
+    };
+}
+
+/// The API client behind a ZekeProvider; null for GhostLLM and OMEN
+fn apiProvider(provider: c_int) ?api.ApiProvider {
+    return switch (provider) {
+        provider_copilot => .github_copilot,
+        provider_claude => .claude,
+        provider_openai => .openai,
+        provider_ollama => .ollama,
+        provider_xai => .xai,
+        provider_google => .google,
+        provider_azure => .azure,
+        else => null,
+    };
+}
+
+/// The ZekeProvider for an API client; ApiProvider is declared in a different order
+fn zekeProvider(provider: api.ApiProvider) c_int {
+    return switch (provider) {
+        .github_copilot => provider_copilot,
+        .claude => provider_claude,
+        .openai => provider_openai,
+        .ollama => provider_ollama,
+        .xai => provider_xai,
+        .google => provider_google,
+        .azure => provider_azure,
+    };
+}
+
 // ============================================================================
 // Authentication Management
 // ============================================================================
@@ -243,45 +498,40 @@ export fn zeke_set_auth_token(
     provider: c_int,
     token: [*:0]const u8,
 ) ZekeErrorCode {
-    const zeke_instance = @ptrCast(*zeke.Zeke, @alignCast(handle));
+    const state = handleState(handle);
+    const zeke_instance = state.begin();
     const token_str = std.mem.span(token);
     
-    const auth_provider = switch (provider) {
-        0 => auth.AuthProvider.copilot,
-        1 => auth.AuthProvider.claude,
-        2 => auth.AuthProvider.openai,
-        3 => auth.AuthProvider.ollama,
-        4 => auth.AuthProvider.ghostllm,
-        else => return .invalid_parameter,
-    };
+    const auth_provider = authProvider(provider) orelse
+        return state.fail(.invalid_parameter, "provider has no credentials in the core");
     
-    zeke_instance.auth_manager.setToken(auth_provider, token_str) catch |err| {
-        return switch (err) {
-            error.AuthenticationFailed => .authentication_failed,
-            error.NetworkError => .network_error,
-            else => .unexpected_response,
-        };
+    zeke_instance.auth_manager.setApiKey(auth_provider, token_str) catch |err| {
+        return state.failWith(err, .unexpected_response);
     };
+    // The live client only picks up stored keys when it is created
+    if (apiProvider(provider) == zeke_instance.current_provider) {
+        zeke_instance.api_client.setAuth(token_str) catch |err| {
+            return state.failWith(err, .unexpected_response);
+        };
+    }
     
     return .success;
 }
 
 /// Test authentication for a provider
 export fn zeke_test_auth(handle: *ZekeHandle, provider: c_int) ZekeErrorCode {
-    const zeke_instance = @ptrCast(*zeke.Zeke, @alignCast(handle));
-    
-    const auth_provider = switch (provider) {
-        0 => auth.AuthProvider.copilot,
-        1 => auth.AuthProvider.claude,
-        2 => auth.AuthProvider.openai,
-        3 => auth.AuthProvider.ollama,
-        4 => auth.AuthProvider.ghostllm,
-        else => return .invalid_parameter,
-    };
+    const state = handleState(handle);
+    const zeke_instance = state.begin();
     
-    const is_authenticated = zeke_instance.auth_manager.isAuthenticated(auth_provider) catch false;
+    const auth_provider = authProvider(provider) orelse
+        return state.fail(.invalid_parameter, "provider has no credentials in the core");
     
-    return if (is_authenticated) .success else .authentication_failed;
+    const token = zeke_instance.auth_manager.getToken(auth_provider) catch |err| {
+        return state.failWith(err, .authentication_failed);
+    } orelse return state.fail(.authentication_failed, "not authenticated");
+    zeke_instance.allocator.free(token);
+    
+    return .success;
 }
 
 // ============================================================================
@@ -290,24 +540,14 @@ export fn zeke_test_auth(handle: *ZekeHandle, provider: c_int) ZekeErrorCode {
 
 /// Switch to a different provider
 export fn zeke_switch_provider(handle: *ZekeHandle, provider: c_int) ZekeErrorCode {
-    const zeke_instance = @ptrCast(*zeke.Zeke, @alignCast(handle));
-    
-    const api_provider = switch (provider) {
-        0 => api.ApiProvider.copilot,
-        1 => api.ApiProvider.claude,
-        2 => api.ApiProvider.openai,
-        3 => api.ApiProvider.ollama,
-        4 => api.ApiProvider.ghostllm,
-        else => return .invalid_parameter,
-    };
+    const state = handleState(handle);
+    const zeke_instance = state.begin();
+    
+    const api_provider = apiProvider(provider) orelse
+        return state.fail(.invalid_parameter, "provider is not served by the core");
     
     zeke_instance.switchToProvider(api_provider) catch |err| {
-        return switch (err) {
-            error.AuthenticationFailed => .authentication_failed,
-            error.NetworkError => .network_error,
-            error.InvalidModel => .invalid_model,
-            else => .provider_unavailable,
-        };
+        return state.failWith(err, .provider_unavailable);
     };
     
     return .success;
@@ -320,13 +560,11 @@ export fn zeke_get_provider_status(
     array_size: usize,
     actual_count: *usize,
 ) ZekeErrorCode {
-    const zeke_instance = @ptrCast(*zeke.Zeke, @alignCast(handle));
+    const state = handleState(handle);
+    const zeke_instance = state.begin();
     
     const provider_status = zeke_instance.getProviderStatus() catch |err| {
-        return switch (err) {
-            error.NetworkError => .network_error,
-            else => .unexpected_response,
-        };
+        return state.failWith(err, .unexpected_response);
     };
     defer zeke_instance.allocator.free(provider_status);
     
@@ -336,9 +574,9 @@ export fn zeke_get_provider_status(
     for (0..count) |i| {
         const status = provider_status[i];
         status_array[i] = ZekeProviderStatus{
-            .provider = @intFromEnum(status.provider),
+            .provider = zekeProvider(status.provider),
             .is_healthy = status.is_healthy,
-            .response_time_ms = status.response_time_ms,
+            .response_time_ms = @intCast(@min(status.response_time_ms, std.math.maxInt(u32))),
             .error_rate = status.error_rate,
             .requests_per_minute = 0, // TODO: Add RPM tracking
         };
@@ -351,35 +589,22 @@ export fn zeke_get_provider_status(
 // GhostLLM GPU Integration
 // ============================================================================
 
+// GhostLLM is a separate service now and the core has no GPU client, so these
+// keep their place in the ABI but report the provider as unavailable.
+
+const ghostllm_unavailable = "GhostLLM is not part of the core library";
+
 /// Initialize GhostLLM GPU client
 export fn zeke_ghostllm_init(
     handle: *ZekeHandle,
     base_url: [*:0]const u8,
     enable_gpu: bool,
 ) ZekeErrorCode {
-    const zeke_instance = @ptrCast(*zeke.Zeke, @alignCast(handle));
-    const url_str = std.mem.span(base_url);
-    
-    const ghostllm_config = ghostllm.GhostLLMConfig{
-        .base_url = url_str,
-        .enable_gpu = enable_gpu,
-        .enable_quic = true,
-        .stream = true,
-    };
-    
-    const ghostllm_client = ghostllm.GhostLLMClient.init(
-        zeke_instance.allocator,
-        ghostllm_config,
-    ) catch |err| {
-        return switch (err) {
-            error.NetworkError => .network_error,
-            error.InitializationFailed => .initialization_failed,
-            else => .unexpected_response,
-        };
-    };
-    
-    zeke_instance.ghostllm_client = ghostllm_client;
-    return .success;
+    _ = base_url;
+    _ = enable_gpu;
+    const state = handleState(handle);
+    _ = state.begin();
+    return state.fail(.provider_unavailable, ghostllm_unavailable);
 }
 
 /// Get GPU information from GhostLLM
@@ -387,44 +612,17 @@ export fn zeke_ghostllm_get_gpu_info(
     handle: *ZekeHandle,
     gpu_info: *ZekeGpuInfo,
 ) ZekeErrorCode {
-    const zeke_instance = @ptrCast(*zeke.Zeke, @alignCast(handle));
-    
-    if (zeke_instance.ghostllm_client == null) {
-        return .initialization_failed;
-    }
-    
-    const client = zeke_instance.ghostllm_client.?;
-    const stats = client.getGpuStats() catch |err| {
-        return switch (err) {
-            error.NetworkError => .network_error,
-            else => .unexpected_response,
-        };
-    };
-    
-    // Allocate device name string
-    const allocator = std.heap.c_allocator;
-    const device_name_cstr = allocator.dupeZ(u8, stats.device_name) catch {
-        return .memory_error;
-    };
-    
-    gpu_info.* = ZekeGpuInfo{
-        .device_name = device_name_cstr.ptr,
-        .memory_used_mb = stats.memory_used_mb,
-        .memory_total_mb = stats.memory_total_mb,
-        .utilization_percent = stats.utilization_percent,
-        .temperature_celsius = stats.temperature_celsius,
-        .power_watts = stats.power_watts,
-    };
-    
-    return .success;
+    const state = handleState(handle);
+    _ = state.begin();
+    gpu_info.* = std.mem.zeroInit(ZekeGpuInfo, .{ .device_name = "" });
+    return state.fail(.provider_unavailable, ghostllm_unavailable);
 }
 
 /// Free GPU info memory
 export fn zeke_free_gpu_info(gpu_info: *ZekeGpuInfo) void {
-    if (gpu_info.device_name != null) {
-        const allocator = std.heap.c_allocator;
-        const device_name_slice = std.mem.span(gpu_info.device_name);
-        allocator.free(device_name_slice);
+    const device_name = std.mem.span(gpu_info.device_name);
+    if (device_name.len > 0) {
+        std.heap.c_allocator.free(device_name);
         gpu_info.device_name = "";
     }
 }
@@ -435,43 +633,184 @@ export fn zeke_ghostllm_benchmark(
     model_name: [*:0]const u8,
     batch_size: u32,
 ) ZekeErrorCode {
-    const zeke_instance = @ptrCast(*zeke.Zeke, @alignCast(handle));
-    
-    if (zeke_instance.ghostllm_client == null) {
-        return .initialization_failed;
-    }
-    
-    const client = zeke_instance.ghostllm_client.?;
-    const model_str = std.mem.span(model_name);
-    
-    const benchmark_result = client.runBenchmark(model_str, batch_size) catch |err| {
-        return switch (err) {
-            error.NetworkError => .network_error,
-            error.InvalidModel => .invalid_model,
-            else => .unexpected_response,
-        };
-    };
-    
-    // TODO: Store benchmark result for later retrieval
-    _ = benchmark_result;
-    return .success;
+    _ = model_name;
+    _ = batch_size;
+    const state = handleState(handle);
+    _ = state.begin();
+    return state.fail(.provider_unavailable, ghostllm_unavailable);
 }
 
 // ============================================================================
 // Configuration Management
 // ============================================================================
 
+/// State behind a ZekeConfigHandle
+const ConfigState = struct {
+    config: config.Config,
+    /// Owns the strings handed out by zeke_config_get and set by zeke_config_from
+    strings: std.heap.ArenaAllocator,
+};
+
+/// Provider names as the CLI writes them, indexed by ZekeProvider value
+const provider_names = [_][]const u8{ "copilot", "anthropic", "openai", "ollama", "ghostllm", "xai", "google", "azure", "omen" };
+
+fn configState(config_handle: *ZekeConfigHandle) *ConfigState {
+    return @ptrCast(@alignCast(config_handle));
+}
+
+fn newConfigHandle(zeke_config: config.Config) ?*ZekeConfigHandle {
+    const allocator = std.heap.c_allocator;
+    const state = allocator.create(ConfigState) catch return null;
+    state.* = .{
+        .config = zeke_config,
+        .strings = std.heap.ArenaAllocator.init(allocator),
+    };
+    return @ptrCast(state);
+}
+
+fn providerIndex(name: []const u8) ?c_int {
+    if (std.mem.eql(u8, name, "claude")) return provider_claude;
+    for (provider_names, 0..) |candidate, i| {
+        if (std.mem.eql(u8, name, candidate)) return @intCast(i);
+    }
+    return null;
+}
+
+fn providerEndpoint(endpoints: *config.ProviderEndpointConfig, provider: c_int) ?*[]const u8 {
+    return switch (provider) {
+        provider_claude => &endpoints.claude,
+        provider_openai => &endpoints.openai,
+        provider_ollama => &endpoints.ollama,
+        provider_xai => &endpoints.xai,
+        provider_google => &endpoints.google,
+        provider_azure => &endpoints.azure,
+        else => null,
+    };
+}
+
+fn providerApiKey(zeke_config: *config.Config, provider: c_int) ?*?[]const u8 {
+    return switch (provider) {
+        provider_claude => &zeke_config.anthropic_api_key,
+        provider_openai => &zeke_config.openai_api_key,
+        provider_xai => &zeke_config.xai_api_key,
+        provider_azure => &zeke_config.azure_api_key,
+        else => null,
+    };
+}
+
 /// Load configuration from file
 export fn zeke_load_config(config_path: [*:0]const u8) ?*ZekeConfigHandle {
     const allocator = std.heap.c_allocator;
     const path_str = std.mem.span(config_path);
     
-    const zeke_config = config.Config.loadFromFile(allocator, path_str) catch return null;
+    // zeke.toml goes through the same loader as the CLI
+    const loaded = if (std.mem.endsWith(u8, path_str, ".toml"))
+        config.toml_loader.loadFromToml(allocator, path_str)
+    else
+        config.Config.loadFromFile(allocator, path_str);
+    var zeke_config = loaded catch |err| {
+        setLastError(@errorName(err));
+        return null;
+    };
+    
+    return newConfigHandle(zeke_config) orelse {
+        zeke_config.deinit();
+        return null;
+    };
+}
+
+/// Create a configuration handle from instance settings
+export fn zeke_config_from(settings: *const ZekeConfig) ?*ZekeConfigHandle {
+    const allocator = std.heap.c_allocator;
+    if (settings.provider < 0 or settings.provider >= provider_names.len) {
+        setLastError("unknown provider");
+        return null;
+    }
     
-    const config_ptr = allocator.create(config.Config) catch return null;
-    config_ptr.* = zeke_config;
+    const handle = newConfigHandle(config.Config.init(allocator)) orelse return null;
+    applySettings(configState(handle), settings) catch |err| {
+        setLastError(@errorName(err));
+        zeke_free_config(handle);
+        return null;
+    };
+    return handle;
+}
+
+fn applySettings(state: *ConfigState, settings: *const ZekeConfig) !void {
+    const zeke_config = &state.config;
+    const allocator = zeke_config.allocator;
+    const arena = state.strings.allocator();
+    const provider_name = provider_names[@intCast(settings.provider)];
+    const model = std.mem.span(settings.model_name);
+    
+    allocator.free(zeke_config.default_model);
+    zeke_config.default_model = try allocator.dupe(u8, model);
+    allocator.free(zeke_config.providers.default_provider);
+    zeke_config.providers.default_provider = try allocator.dupe(u8, provider_name);
+    
+    const base_url = std.mem.span(settings.base_url);
+    if (base_url.len > 0) {
+        if (providerEndpoint(&zeke_config.endpoints, settings.provider)) |endpoint| {
+            endpoint.* = try arena.dupe(u8, base_url);
+        }
+    }
+    const api_key = std.mem.span(settings.api_key);
+    if (api_key.len > 0) {
+        if (providerApiKey(zeke_config, settings.provider)) |key| {
+            key.* = try allocator.dupe(u8, api_key);
+        }
+    }
     
-    return @ptrCast(config_ptr);
+    try zeke_config.models.append(.{
+        .name = try arena.dupe(u8, model),
+        .provider = provider_name,
+        .temperature = settings.temperature,
+        .max_tokens = settings.max_tokens,
+    });
+    zeke_config.streaming.enabled = settings.stream;
+    zeke_config.providers.fallback_enabled = settings.enable_fallback;
+    zeke_config.request_timeout_ms = settings.timeout_ms;
+}
+
+/// Read the instance settings of a configuration handle
+export fn zeke_config_get(
+    config_handle: *ZekeConfigHandle,
+    config_out: *ZekeConfig,
+) ZekeErrorCode {
+    const state = configState(config_handle);
+    const zeke_config = &state.config;
+    const arena = state.strings.allocator();
+    
+    const provider = providerIndex(zeke_config.providers.default_provider) orelse {
+        setLastError("unknown default provider");
+        return .config_load_failed;
+    };
+    
+    // Per-model settings, falling back to the model defaults
+    var model = config.ModelConfig{ .name = zeke_config.default_model, .provider = "" };
+    for (zeke_config.models.items) |candidate| {
+        if (std.mem.eql(u8, candidate.name, zeke_config.default_model)) {
+            model = candidate;
+            break;
+        }
+    }
+    
+    const base_url = if (providerEndpoint(&zeke_config.endpoints, provider)) |endpoint| endpoint.* else "";
+    const api_key = if (providerApiKey(zeke_config, provider)) |key| key.* orelse "" else "";
+    
+    config_out.* = .{
+        .base_url = (arena.dupeZ(u8, base_url) catch return .memory_error).ptr,
+        .api_key = (arena.dupeZ(u8, api_key) catch return .memory_error).ptr,
+        .provider = provider,
+        .model_name = (arena.dupeZ(u8, zeke_config.default_model) catch return .memory_error).ptr,
+        .temperature = model.temperature,
+        .max_tokens = model.max_tokens,
+        .stream = zeke_config.streaming.enabled,
+        .enable_gpu = false,
+        .enable_fallback = zeke_config.providers.fallback_enabled,
+        .timeout_ms = zeke_config.request_timeout_ms,
+    };
+    return .success;
 }
 
 /// Save configuration to file
@@ -479,14 +818,14 @@ export fn zeke_save_config(
     config_handle: *ZekeConfigHandle,
     config_path: [*:0]const u8,
 ) ZekeErrorCode {
-    const zeke_config = @ptrCast(*config.Config, @alignCast(config_handle));
+    const zeke_config = &configState(config_handle).config;
     const path_str = std.mem.span(config_path);
     
     zeke_config.saveToFile(path_str) catch |err| {
-        return switch (err) {
-            error.AccessDenied => .config_load_failed,
-            error.FileNotFound => .config_load_failed,
-            else => .unexpected_response,
+        setLastError(@errorName(err));
+        return switch (@as(anyerror, err)) {
+            error.AccessDenied, error.FileNotFound => .config_load_failed,
+            else => errorCode(err, .unexpected_response),
         };
     };
     
@@ -495,10 +834,10 @@ export fn zeke_save_config(
 
 /// Free configuration handle
 export fn zeke_free_config(config_handle: *ZekeConfigHandle) void {
-    const zeke_config = @ptrCast(*config.Config, @alignCast(config_handle));
-    const allocator = std.heap.c_allocator;
-    zeke_config.deinit();
-    allocator.destroy(zeke_config);
+    const state = configState(config_handle);
+    state.config.deinit();
+    state.strings.deinit();
+    std.heap.c_allocator.destroy(state);
 }
 
 // ============================================================================
@@ -514,29 +853,24 @@ export fn zeke_get_last_error() [*:0]const u8 {
 
 /// Set internal error message
 fn setLastError(message: []const u8) void {
-    const len = @min(message.len, last_error_message.len - 1);
-    @memcpy(last_error_message[0..len], message[0..len]);
-    last_error_message[len] = 0;
+    copyTerminated(&last_error_message, message);
+}
+
+/// Get the outcome of the most recent call on a handle
+export fn zeke_get_error_detail(handle: *const ZekeHandle, detail_out: *ZekeErrorDetail) ZekeErrorCode {
+    const state: *const HandleState = @ptrCast(@alignCast(handle));
+    detail_out.* = state.last_call;
+    return .success;
 }
 
 /// Check if Zeke instance is healthy
 export fn zeke_health_check(handle: *ZekeHandle) ZekeErrorCode {
-    const zeke_instance = @ptrCast(*zeke.Zeke, @alignCast(handle));
-    
-    // Basic health checks
-    if (zeke_instance.current_provider == .ghostllm and zeke_instance.ghostllm_client == null) {
-        setLastError("GhostLLM client not initialized");
-        return .initialization_failed;
-    }
+    const state = handleState(handle);
+    const zeke_instance = state.begin();
     
     // Test a simple request to current provider
     const test_response = zeke_instance.chat("test") catch |err| {
-        setLastError(@errorName(err));
-        return switch (err) {
-            error.NetworkError => .network_error,
-            error.AuthenticationFailed => .authentication_failed,
-            else => .unexpected_response,
-        };
+        return state.failRequest(err, .unexpected_response);
     };
     defer zeke_instance.allocator.free(test_response);
     
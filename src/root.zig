@@ -518,6 +518,14 @@ pub const Zeke = struct {
         }
     }
 
+    /// Stop the stream started by streamChat or streamCodeCompletion.
+    /// Callbacks may call this to end their own stream.
+    pub fn cancelStream(self: *Self) void {
+        if (self.realtime_features) |*rt| {
+            rt.streaming_client.cancel();
+        }
+    }
+
     pub fn streamCodeCompletion(self: *Self, prompt: []const u8, code_context: api.CodeContext, callback: streaming.StreamCallback) !void {
         // Ensure real-time features are enabled
         try self.enableRealTimeFeatures();
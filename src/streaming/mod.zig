@@ -274,6 +274,8 @@ pub const StreamingClient = struct {
     allocator: std.mem.Allocator,
     http_client: *std.http.Client,
     sse_parser: SSEParser,
+    /// Set by cancel(); the running stream stops before its next chunk
+    cancelled: std.atomic.Value(bool) = std.atomic.Value(bool).init(false),
     
     const Self = @This();
     
@@ -289,6 +291,16 @@ pub const StreamingClient = struct {
         self.sse_parser.deinit();
     }
     
+    /// Stop the running stream; no further chunks, final included, are delivered.
+    /// Safe to call from inside a StreamCallback.
+    pub fn cancel(self: *Self) void {
+        self.cancelled.store(true, .release);
+    }
+    
+    pub fn isCancelled(self: *const Self) bool {
+        return self.cancelled.load(.acquire);
+    }
+    
     pub fn streamChatCompletion(
         self: *Self,
         endpoint: []const u8,
@@ -299,6 +311,7 @@ pub const StreamingClient = struct {
         _ = endpoint;
         _ = request_body;
         _ = headers;
+        self.cancelled.store(false, .release);
         
         // For now, since the HTTP client API has completely changed in Zig v0.16
         // and we can't easily implement true streaming without understanding the new API,
@@ -309,6 +322,7 @@ pub const StreamingClient = struct {
     fn simulateStreamingFromBody(self: *Self, body: []const u8, callback: StreamCallback) !void {
         // Process the complete response body as streaming chunks
         try self.sse_parser.parseChunk(body, callback);
+        if (self.isCancelled()) return;
         
         // Send final chunk
         const final_chunk = StreamChunk{
@@ -341,6 +355,7 @@ pub const StreamingClient = struct {
                 .timestamp = std.time.timestamp(),
             };
             callback(chunk);
+            if (self.isCancelled()) return;
             
             // Small delay to simulate streaming
             std.Thread.sleep(200 * std.time.ns_per_ms);